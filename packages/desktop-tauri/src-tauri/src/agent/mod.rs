@@ -8,8 +8,11 @@
 //!                    surfaced to the LLM.
 //! - [`runner`]:      Tool-use loop orchestrating `chat_with_tools` ↔
 //!                    cua-driver tool execution.
+//! - [`plan`]:        Fixed screenshot / OCR / chat sequences traced on
+//!                    the debug timeline.
 
 pub mod cua_driver;
+pub mod plan;
 pub mod protocol;
 pub mod runner;
 pub mod tools;
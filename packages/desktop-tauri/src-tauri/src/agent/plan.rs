@@ -0,0 +1,332 @@
+//! Plan executor — runs a fixed sequence of perception and AI steps
+//!
+//! Each step can use what earlier steps produced: `ocr` reads the last
+//! screenshot, `chat` can attach it and its OCR text. The run is traced on
+//! the debug timeline as one `ExecutionStart` event with an `ExecutionStep`
+//! child per step and a closing `ExecutionComplete`, and every step also
+//! reports `plan:progress` as it starts and finishes. Execution stops at the
+//! first failing step.
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::ai::rate_limit::{self, RequestPriority};
+use crate::ai::{AiProvider, ChatMessage};
+use crate::commands::debug_cmd::{finish_debug, record_debug, DebugEventType};
+use crate::error::HawkeyeError;
+use crate::event_sink::{EventSink, SharedSink};
+use crate::events;
+use crate::perception;
+use crate::perception::screen::CaptureOptions;
+use crate::state::AppState;
+
+/// Longest plan accepted by [`validate`]
+pub const MAX_PLAN_STEPS: usize = 20;
+
+/// OCR text passed to a `chat` step is cut to this many characters
+const MAX_CONTEXT_CHARS: usize = 4_000;
+
+/// One action in a plan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "action")]
+pub enum PlanStep {
+    /// Capture the primary screen
+    Screenshot,
+    /// OCR the most recent screenshot
+    Ocr,
+    /// Ask the active AI provider
+    Chat {
+        prompt: String,
+        /// Attach the most recent screenshot
+        #[serde(default, rename = "includeScreenshot")]
+        include_screenshot: bool,
+        /// Append the most recent OCR text to the prompt
+        #[serde(default, rename = "includeOcr")]
+        include_ocr: bool,
+    },
+}
+
+impl PlanStep {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Screenshot => "screenshot",
+            Self::Ocr => "ocr",
+            Self::Chat { .. } => "chat",
+        }
+    }
+}
+
+/// What one step did
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepOutcome {
+    pub index: usize,
+    pub action: String,
+    pub success: bool,
+    /// Short description of the result: image size, OCR text, AI reply
+    pub output: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Result of `execute_plan`. A failed step ends the run; later steps are
+/// not attempted.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanResult {
+    pub success: bool,
+    pub steps: Vec<StepOutcome>,
+    /// Reply of the last `chat` step, if any ran
+    pub reply: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Reject empty or oversized plans, and steps that need an earlier
+/// screenshot or OCR result that the plan never produces
+pub fn validate(steps: &[PlanStep]) -> Result<(), String> {
+    if steps.is_empty() {
+        return Err("Plan has no steps".to_string());
+    }
+    if steps.len() > MAX_PLAN_STEPS {
+        return Err(format!(
+            "Plan has {} steps; at most {} are allowed",
+            steps.len(),
+            MAX_PLAN_STEPS
+        ));
+    }
+    let (mut has_screenshot, mut has_ocr) = (false, false);
+    for (index, step) in steps.iter().enumerate() {
+        let missing = match step {
+            PlanStep::Screenshot => None,
+            PlanStep::Ocr => (!has_screenshot).then_some("a screenshot"),
+            PlanStep::Chat { prompt, .. } if prompt.trim().is_empty() => {
+                return Err(format!("Step {}: chat prompt is empty", index + 1));
+            }
+            PlanStep::Chat {
+                include_screenshot,
+                include_ocr,
+                ..
+            } => {
+                if *include_screenshot && !has_screenshot {
+                    Some("a screenshot")
+                } else if *include_ocr && !has_ocr {
+                    Some("an OCR step")
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(missing) = missing {
+            return Err(format!(
+                "Step {} ({}) needs {} before it",
+                index + 1,
+                step.name(),
+                missing
+            ));
+        }
+        has_screenshot |= *step == PlanStep::Screenshot;
+        has_ocr |= *step == PlanStep::Ocr;
+    }
+    Ok(())
+}
+
+/// What earlier steps produced
+#[derive(Default)]
+struct PlanContext {
+    screenshot: Option<String>,
+    ocr_text: Option<String>,
+    reply: Option<String>,
+}
+
+/// Run `steps` in order (call [`validate`] first)
+pub async fn execute(state: &AppState, sink: &SharedSink, steps: &[PlanStep]) -> PlanResult {
+    let started = Instant::now();
+    let total = steps.len();
+    let actions: Vec<&str> = steps.iter().map(PlanStep::name).collect();
+    let root_id = record_debug(
+        sink.as_ref(),
+        state,
+        DebugEventType::ExecutionStart,
+        "Plan started",
+        serde_json::json!({ "steps": actions }),
+        None,
+        None,
+    )
+    .await;
+
+    let mut context = PlanContext::default();
+    let mut outcomes = Vec::with_capacity(total);
+    for (index, step) in steps.iter().enumerate() {
+        sink.emit(
+            events::PLAN_PROGRESS,
+            serde_json::json!({ "index": index, "total": total, "action": step.name(), "status": "running" }),
+        );
+
+        let step_started = Instant::now();
+        let result = run_step(state, step, &mut context).await;
+        let duration_ms = step_started.elapsed().as_millis() as u64;
+        let outcome = StepOutcome {
+            index,
+            action: step.name().to_string(),
+            success: result.is_ok(),
+            output: result.as_ref().ok().cloned(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            duration_ms,
+        };
+
+        let payload = serde_json::to_value(&outcome).unwrap_or_default();
+        record_debug(
+            sink.as_ref(),
+            state,
+            if outcome.success {
+                DebugEventType::ExecutionStep
+            } else {
+                DebugEventType::Error
+            },
+            &format!("Step {}/{}: {}", index + 1, total, step.name()),
+            payload.clone(),
+            Some(duration_ms),
+            root_id.as_deref(),
+        )
+        .await;
+        let mut progress = payload;
+        progress["total"] = total.into();
+        progress["status"] = if outcome.success { "done" } else { "failed" }.into();
+        sink.emit(events::PLAN_PROGRESS, progress);
+
+        let failed = !outcome.success;
+        outcomes.push(outcome);
+        if failed {
+            break;
+        }
+    }
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let success = outcomes.len() == total && outcomes.iter().all(|o| o.success);
+    record_debug(
+        sink.as_ref(),
+        state,
+        DebugEventType::ExecutionComplete,
+        if success {
+            "Plan completed"
+        } else {
+            "Plan failed"
+        },
+        serde_json::json!({ "success": success, "stepsRun": outcomes.len(), "steps": total }),
+        Some(duration_ms),
+        root_id.as_deref(),
+    )
+    .await;
+    if let Some(root_id) = &root_id {
+        finish_debug(sink.as_ref(), state, root_id, duration_ms).await;
+    }
+
+    PlanResult {
+        success,
+        steps: outcomes,
+        reply: context.reply,
+        duration_ms,
+    }
+}
+
+/// Run one step, returning a short description of its result
+async fn run_step(
+    state: &AppState,
+    step: &PlanStep,
+    context: &mut PlanContext,
+) -> Result<String, HawkeyeError> {
+    match step {
+        PlanStep::Screenshot => {
+            state.ensure_capture_enabled().await?;
+            let options = CaptureOptions::from_config(&*state.config.read().await);
+            let (data, width, height) = perception::screen::capture_screenshot(options).await?;
+            context.screenshot = Some(data);
+            Ok(format!("{}x{}", width, height))
+        }
+        PlanStep::Ocr => {
+            let screenshot = context
+                .screenshot
+                .as_deref()
+                .ok_or("No screenshot to OCR")?;
+            if !*state.ocr_available.read().await {
+                return Err(perception::ocr::MISSING_HELPER_HINT.into());
+            }
+            let options = state.ocr_options().await;
+            let result = perception::ocr::run_ocr(screenshot, &options).await?;
+            let preview: String = result.text.chars().take(200).collect();
+            context.ocr_text = Some(result.text);
+            Ok(preview)
+        }
+        PlanStep::Chat {
+            prompt,
+            include_screenshot,
+            include_ocr,
+        } => {
+            let provider = state.ai_provider().await?;
+            let mut content = prompt.clone();
+            if let Some(text) = context.ocr_text.as_deref().filter(|_| *include_ocr) {
+                let text: String = text.chars().take(MAX_CONTEXT_CHARS).collect();
+                content.push_str(&format!("\n\nText on screen:\n{}", text));
+            }
+            let messages = vec![ChatMessage {
+                role: "user".to_string(),
+                content,
+            }];
+
+            if !rate_limit::acquire(
+                state,
+                provider.provider_name(),
+                RequestPriority::Interactive,
+            )
+            .await
+            {
+                return Err(rate_limit::limited_error(provider.provider_name()));
+            }
+            let response = match context
+                .screenshot
+                .as_deref()
+                .filter(|_| *include_screenshot)
+            {
+                Some(image) => provider.chat_with_vision(messages, image).await?,
+                None => provider.chat(messages).await?,
+            };
+            context.reply = Some(response.text.clone());
+            Ok(response.text)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chat(include_screenshot: bool, include_ocr: bool) -> PlanStep {
+        PlanStep::Chat {
+            prompt: "What am I doing?".to_string(),
+            include_screenshot,
+            include_ocr,
+        }
+    }
+
+    #[test]
+    fn steps_parse_and_inputs_are_checked() {
+        let steps: Vec<PlanStep> = serde_json::from_value(serde_json::json!([
+            { "action": "screenshot" },
+            { "action": "ocr" },
+            { "action": "chat", "prompt": "Summarize", "includeOcr": true }
+        ]))
+        .unwrap();
+        assert!(validate(&steps).is_ok());
+
+        assert!(validate(&[]).is_err());
+        assert!(validate(&[PlanStep::Ocr])
+            .unwrap_err()
+            .contains("screenshot"));
+        assert!(validate(&[chat(true, false)]).is_err());
+        assert!(validate(&[PlanStep::Screenshot, chat(true, true)])
+            .unwrap_err()
+            .contains("OCR"));
+        assert!(validate(&[PlanStep::Screenshot, chat(true, false)]).is_ok());
+        assert!(validate(&vec![PlanStep::Screenshot; MAX_PLAN_STEPS + 1]).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+//! Persisted chat conversations
+//!
+//! One JSON file per conversation under `<data_dir>/conversations/`. The
+//! `chat` command appends each exchange when it is given a conversation id,
+//! which also accumulates that conversation's token usage.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::types::{ChatMessage, ChatResponse};
+
+/// A message as stored, with when it was appended (ms)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredMessage {
+    #[serde(flatten)]
+    pub message: ChatMessage,
+    pub timestamp: u64,
+}
+
+/// Token usage summed over a conversation's responses
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationUsage {
+    pub requests: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Conversation {
+    pub id: String,
+    pub created_at: u64,
+    pub updated_at: u64,
+    pub messages: Vec<StoredMessage>,
+    #[serde(default)]
+    pub usage: ConversationUsage,
+}
+
+/// File-backed conversation store
+pub struct ConversationStore {
+    dir: PathBuf,
+}
+
+impl Default for ConversationStore {
+    fn default() -> Self {
+        Self::with_dir(crate::paths::data_dir().join("conversations"))
+    }
+}
+
+impl ConversationStore {
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Load a conversation; `None` if it has never been written
+    pub fn load(&self, id: &str) -> Result<Option<Conversation>> {
+        let path = self.path(id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    /// Append the latest user message (if `user` is given) and the model's
+    /// reply to conversation `id`, creating it on first use
+    pub fn append_exchange(
+        &self,
+        id: &str,
+        user: Option<&ChatMessage>,
+        response: &ChatResponse,
+        now: u64,
+    ) -> Result<Conversation> {
+        let mut conversation = self.load(id)?.unwrap_or_else(|| Conversation {
+            id: id.to_string(),
+            created_at: now,
+            updated_at: now,
+            messages: Vec::new(),
+            usage: ConversationUsage::default(),
+        });
+
+        if let Some(user) = user {
+            conversation.messages.push(StoredMessage {
+                message: user.clone(),
+                timestamp: now,
+            });
+        }
+        conversation.messages.push(StoredMessage {
+            message: ChatMessage {
+                role: "assistant".to_string(),
+                content: response.text.clone(),
+            },
+            timestamp: now,
+        });
+        conversation.updated_at = now;
+        conversation.usage.requests += 1;
+        if let Some(usage) = &response.usage {
+            conversation.usage.prompt_tokens += usage.prompt_tokens as u64;
+            conversation.usage.completion_tokens += usage.completion_tokens as u64;
+        }
+
+        self.save(&conversation)?;
+        Ok(conversation)
+    }
+
+    /// Every stored conversation. Unreadable files are skipped.
+    pub fn list(&self) -> Result<Vec<Conversation>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut conversations = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            match std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| Ok(serde_json::from_str::<Conversation>(&json)?))
+            {
+                Ok(conversation) => conversations.push(conversation),
+                Err(e) => log::warn!("[Chat] Skipping {}: {}", path.display(), e),
+            }
+        }
+        Ok(conversations)
+    }
+
+    /// Write a conversation, replacing any stored copy
+    pub fn save(&self, conversation: &Conversation) -> Result<()> {
+        let path = self.path(&conversation.id)?;
+        std::fs::create_dir_all(&self.dir)?;
+        // Temp file + rename so a crash can't truncate the history
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(conversation)?)?;
+        std::fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    /// Directory holding one file per conversation
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File name for conversation `id`. Ids become file names, so only a
+    /// safe character set is allowed.
+    pub fn file_name(id: &str) -> Result<String> {
+        let valid = !id.is_empty()
+            && id.len() <= 64
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(anyhow!("Invalid conversation id: {:?}", id));
+        }
+        Ok(format!("{}.json", id))
+    }
+
+    fn path(&self, id: &str) -> Result<PathBuf> {
+        Ok(self.dir.join(Self::file_name(id)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::types::UsageInfo;
+
+    #[test]
+    fn exchanges_accumulate_messages_and_usage() {
+        let dir = std::env::temp_dir().join(format!("hawkeye-conv-{}", uuid::Uuid::new_v4()));
+        let store = ConversationStore::with_dir(dir.clone());
+        let response = |text: &str| ChatResponse {
+            text: text.to_string(),
+            model: "test".to_string(),
+            duration_ms: 1,
+            usage: Some(UsageInfo { prompt_tokens: 10, completion_tokens: 5, total_tokens: 15 }),
+        };
+        let user = |text: &str| ChatMessage { role: "user".to_string(), content: text.to_string() };
+
+        store.append_exchange("c1", Some(&user("hi")), &response("hello"), 1).unwrap();
+        let conversation =
+            store.append_exchange("c1", Some(&user("again")), &response("sure"), 2).unwrap();
+
+        assert_eq!(conversation.messages.len(), 4);
+        assert_eq!(conversation.created_at, 1);
+        assert_eq!(conversation.usage.prompt_tokens, 20);
+        assert_eq!(store.load("c1").unwrap().unwrap().messages[3].message.content, "sure");
+        assert!(store.load("../etc").is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
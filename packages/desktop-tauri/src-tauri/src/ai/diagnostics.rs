@@ -0,0 +1,96 @@
+//! Per-provider request health for the diagnostics view
+//!
+//! `chat` records every request's latency and outcome here so the settings
+//! page can say "Gemini: healthy, 420ms" or show the last failure without
+//! the user digging through logs. In memory only.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::error::HawkeyeError;
+
+/// Request history of one provider
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderStats {
+    pub request_count: u64,
+    pub error_count: u64,
+    /// Duration of the most recent request, successful or not
+    pub last_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+    /// `HawkeyeError` code of `last_error`, e.g. `rate_limited`
+    pub last_error_code: Option<String>,
+    pub last_error_at: Option<u64>,
+    pub last_success_at: Option<u64>,
+}
+
+impl ProviderStats {
+    /// Healthy until a request fails, and again after the next success
+    pub fn healthy(&self) -> bool {
+        match (self.last_error_at, self.last_success_at) {
+            (Some(failed), Some(succeeded)) => succeeded > failed,
+            (Some(_), None) => false,
+            _ => true,
+        }
+    }
+}
+
+/// Stats keyed by provider name
+#[derive(Debug, Default)]
+pub struct AiDiagnostics {
+    providers: HashMap<String, ProviderStats>,
+}
+
+impl AiDiagnostics {
+    /// Record one finished request at `now` (ms)
+    pub fn record(
+        &mut self,
+        provider: &str,
+        latency_ms: u64,
+        outcome: Result<(), &HawkeyeError>,
+        now: u64,
+    ) {
+        let stats = self.providers.entry(provider.to_string()).or_default();
+        stats.request_count += 1;
+        stats.last_latency_ms = Some(latency_ms);
+        match outcome {
+            Ok(()) => stats.last_success_at = Some(now),
+            Err(e) => {
+                stats.error_count += 1;
+                stats.last_error = Some(e.to_string());
+                stats.last_error_code = Some(e.code().to_string());
+                stats.last_error_at = Some(now);
+            }
+        }
+    }
+
+    /// Stats for `provider`; empty when it hasn't been used yet
+    pub fn stats(&self, provider: &str) -> ProviderStats {
+        self.providers.get(provider).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_latency_errors_and_recovery() {
+        let mut diagnostics = AiDiagnostics::default();
+        assert!(diagnostics.stats("gemini").healthy());
+
+        diagnostics.record("gemini", 420, Ok(()), 1_000);
+        let limited = HawkeyeError::RateLimited("Gemini quota exceeded".to_string());
+        diagnostics.record("gemini", 90, Err(&limited), 2_000);
+
+        let stats = diagnostics.stats("gemini");
+        assert_eq!((stats.request_count, stats.error_count), (2, 1));
+        assert_eq!(stats.last_latency_ms, Some(90));
+        assert_eq!(stats.last_error_code.as_deref(), Some("rate_limited"));
+        assert!(!stats.healthy());
+
+        diagnostics.record("gemini", 380, Ok(()), 3_000);
+        assert!(diagnostics.stats("gemini").healthy());
+        assert_eq!(diagnostics.stats("openai"), ProviderStats::default());
+    }
+}
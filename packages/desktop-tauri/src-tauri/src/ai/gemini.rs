@@ -2,40 +2,57 @@
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use reqwest::Client;
-use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::time::Instant;
 
+use super::http::{HttpOptions, HttpRequest, HttpTransport, RequestLogger};
 use super::provider::AiProvider;
 use super::types::*;
 
 const DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+const EMBEDDING_MODEL: &str = "gemini-embedding-001";
+
+/// Auth header; keeps the key out of URLs, which end up in logs and proxies
+const API_KEY_HEADER: &str = "x-goog-api-key";
+
+/// Finish reasons meaning the reply was withheld rather than completed
+const BLOCKED_FINISH_REASONS: &[&str] = &[
+    "SAFETY",
+    "RECITATION",
+    "BLOCKLIST",
+    "PROHIBITED_CONTENT",
+    "SPII",
+];
 
 /// Gemini HTTP client
 #[derive(Debug, Clone)]
 pub struct GeminiClient {
-    client: Client,
+    transport: Arc<dyn HttpTransport>,
     api_key: String,
     model: String,
     base_url: String,
+    log: RequestLogger,
 }
 
 impl GeminiClient {
-    /// Create a new Gemini client
-    pub fn new(api_key: String, model: Option<String>, base_url: Option<String>) -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(60))
-                .connect_timeout(Duration::from_secs(10))
-                .build()
-                .unwrap_or_else(|_| Client::new()),
+    /// Create a new Gemini client. Fails on a malformed `base_url`.
+    pub fn new(
+        api_key: String,
+        model: Option<String>,
+        base_url: Option<String>,
+        http: &HttpOptions,
+    ) -> Result<Self> {
+        Ok(Self {
+            transport: http.transport(),
             api_key,
             model: model.unwrap_or_else(|| "gemini-2.5-flash-preview-05-20".to_string()),
-            base_url: base_url.unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
-        }
+            base_url: super::normalize_base_url(base_url.as_deref(), DEFAULT_BASE_URL)?,
+            log: http.request_logger(),
+        })
     }
 
-    /// Send a chat request (internal)
-    async fn do_chat(&self, messages: Vec<ChatMessage>) -> Result<ChatResponse> {
+    /// Send a chat request (internal); `json` requests a JSON-only reply
+    async fn do_chat(&self, messages: Vec<ChatMessage>, json: bool) -> Result<ChatResponse> {
         let start = Instant::now();
         let contents = self.convert_messages(messages);
 
@@ -44,25 +61,44 @@ impl GeminiClient {
             generation_config: Some(GeminiGenerationConfig {
                 max_output_tokens: Some(8192),
                 temperature: Some(0.7),
+                response_mime_type: json.then(|| "application/json".to_string()),
             }),
             tools: None,
             tool_config: None,
         };
 
         let gemini_response = self.post_generate_content(&request).await?;
+        self.chat_response(gemini_response, start)
+    }
 
-        let text = gemini_response
-            .candidates
+    /// Reply text and usage of a `generateContent` response. A prompt or
+    /// reply withheld by Gemini's safety filters is an error, not an empty
+    /// reply.
+    fn chat_response(&self, response: GeminiResponse, start: Instant) -> Result<ChatResponse> {
+        if let Some(reason) = response
+            .prompt_feedback
             .as_ref()
-            .and_then(|c| c.first())
+            .and_then(|f| f.block_reason.as_deref())
+        {
+            return Err(anyhow!("Gemini blocked the prompt ({})", reason));
+        }
+
+        let candidate = response.candidates.as_ref().and_then(|c| c.first());
+        let text = candidate
             .and_then(|c| c.content.as_ref())
             .and_then(|c| c.parts.as_ref())
             .and_then(|p| p.first())
             .and_then(|p| p.text.as_ref())
             .cloned()
             .unwrap_or_default();
+        if let Some(reason) = candidate
+            .and_then(|c| c.finish_reason.as_deref())
+            .filter(|r| text.is_empty() && BLOCKED_FINISH_REASONS.contains(r))
+        {
+            return Err(anyhow!("Gemini withheld the response ({})", reason));
+        }
 
-        let usage = gemini_response.usage_metadata.map(|u| UsageInfo {
+        let usage = response.usage_metadata.map(|u| UsageInfo {
             prompt_tokens: u.prompt_token_count.unwrap_or(0),
             completion_tokens: u.candidates_token_count.unwrap_or(0),
             total_tokens: u.total_token_count.unwrap_or(0),
@@ -76,63 +112,28 @@ impl GeminiClient {
         })
     }
 
-    /// Send a chat request with a vision image (internal)
+    /// Send a chat request with images on one or more user turns (internal)
     async fn do_chat_with_vision(
         &self,
         messages: Vec<ChatMessage>,
-        image_base64: &str,
+        images: &[(usize, String)],
     ) -> Result<ChatResponse> {
         let start = Instant::now();
-        let mut contents = self.convert_messages(messages);
-
-        // Add image to the last user message
-        if let Some(last) = contents.last_mut() {
-            last.parts.push(GeminiPart {
-                text: None,
-                inline_data: Some(GeminiInlineData {
-                    mime_type: "image/png".to_string(),
-                    data: image_base64.to_string(),
-                }),
-                function_call: None,
-                function_response: None,
-            });
-        }
+        let contents = self.convert_messages_with_images(messages, images);
 
         let request = GeminiRequest {
             contents,
             generation_config: Some(GeminiGenerationConfig {
                 max_output_tokens: Some(8192),
                 temperature: Some(0.7),
+                response_mime_type: None,
             }),
             tools: None,
             tool_config: None,
         };
 
         let gemini_response = self.post_generate_content(&request).await?;
-
-        let text = gemini_response
-            .candidates
-            .as_ref()
-            .and_then(|c| c.first())
-            .and_then(|c| c.content.as_ref())
-            .and_then(|c| c.parts.as_ref())
-            .and_then(|p| p.first())
-            .and_then(|p| p.text.as_ref())
-            .cloned()
-            .unwrap_or_default();
-
-        let usage = gemini_response.usage_metadata.map(|u| UsageInfo {
-            prompt_tokens: u.prompt_token_count.unwrap_or(0),
-            completion_tokens: u.candidates_token_count.unwrap_or(0),
-            total_tokens: u.total_token_count.unwrap_or(0),
-        });
-
-        Ok(ChatResponse {
-            text,
-            model: self.model.clone(),
-            duration_ms: start.elapsed().as_millis() as u64,
-            usage,
-        })
+        self.chat_response(gemini_response, start)
     }
 
     /// Tool-using single-turn chat.
@@ -164,6 +165,7 @@ impl GeminiClient {
             generation_config: Some(GeminiGenerationConfig {
                 max_output_tokens: Some(2048),
                 temperature: Some(0.4),
+                response_mime_type: None,
             }),
             tools: tools_payload,
             tool_config,
@@ -208,57 +210,115 @@ impl GeminiClient {
         }
     }
 
+    /// Embed texts with one `batchEmbedContents` call
+    async fn do_embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let model = format!("models/{}", EMBEDDING_MODEL);
+        let request = GeminiBatchEmbedRequest {
+            requests: texts
+                .into_iter()
+                .map(|text| GeminiEmbedRequest {
+                    model: model.clone(),
+                    content: GeminiContent {
+                        role: "user".to_string(),
+                        parts: vec![GeminiPart {
+                            text: Some(text),
+                            inline_data: None,
+                            function_call: None,
+                            function_response: None,
+                        }],
+                    },
+                })
+                .collect(),
+        };
+
+        let url = endpoint(&self.base_url, &format!("{}:batchEmbedContents", model));
+        self.log.request(
+            "gemini",
+            &url,
+            serde_json::json!({ "model": model, "texts": request.requests.len() }),
+        );
+
+        let start = Instant::now();
+        let request = HttpRequest::post_json(&url, &request)?.header(API_KEY_HEADER, &self.api_key);
+        let response = self.transport.send(request).await?;
+
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        self.log.response("gemini", &url, response.status, &response.body, elapsed_ms);
+        if !response.is_success() {
+            return Err(GeminiApiError::new(response.status, response.body).into());
+        }
+
+        let parsed: GeminiBatchEmbedResponse = serde_json::from_str(&response.body)
+            .map_err(|e| anyhow!("Failed to parse response: {}", e))?;
+
+        Ok(parsed.embeddings.into_iter().map(|e| e.values).collect())
+    }
+
     /// Validate the API key by making a test request
     async fn do_validate(&self) -> Result<()> {
-        let url = format!("{}/models?key={}", self.base_url, self.api_key);
+        let url = endpoint(&self.base_url, "models");
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow!("Validation request failed: {}", e))?;
+        let request = HttpRequest::get(&url).header(API_KEY_HEADER, &self.api_key);
+        let response = self.transport.send(request).await?;
 
-        if response.status().is_success() {
+        if response.is_success() {
             Ok(())
         } else {
-            let body = response.text().await.unwrap_or_default();
-            Err(anyhow!("API key validation failed: {}", body))
+            Err(anyhow!("API key validation failed: {}", response.body))
         }
     }
 
     async fn post_generate_content(&self, request: &GeminiRequest) -> Result<GeminiResponse> {
-        let url = format!(
-            "{}/models/{}:generateContent?key={}",
-            self.base_url, self.model, self.api_key
+        let url = endpoint(&self.base_url, &format!("models/{}:generateContent", self.model));
+        self.log.request(
+            "gemini",
+            &url,
+            serde_json::json!({
+                "model": self.model,
+                "contents": request.contents.len(),
+                "tools": request.tools.as_ref().map_or(0, |t| t.len()),
+                "responseMimeType": request
+                    .generation_config
+                    .as_ref()
+                    .and_then(|c| c.response_mime_type.as_deref()),
+            }),
         );
 
-        let response = self
-            .client
-            .post(&url)
-            .json(request)
-            .send()
-            .await
-            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+        let start = Instant::now();
+        let request = HttpRequest::post_json(&url, request)?.header(API_KEY_HEADER, &self.api_key);
+        let response = self.transport.send(request).await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow!("Gemini API error ({}): {}", status, body));
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        self.log.response("gemini", &url, response.status, &response.body, elapsed_ms);
+        if !response.is_success() {
+            return Err(GeminiApiError::new(response.status, response.body).into());
         }
 
-        response
-            .json::<GeminiResponse>()
-            .await
+        serde_json::from_str::<GeminiResponse>(&response.body)
             .map_err(|e| anyhow!("Failed to parse response: {}", e))
     }
 
     /// Convert ChatMessages to Gemini format
     fn convert_messages(&self, messages: Vec<ChatMessage>) -> Vec<GeminiContent> {
-        let mut contents = Vec::new();
+        self.convert_messages_with_images(messages, &[])
+    }
+
+    /// Convert ChatMessages to Gemini format, attaching each `(index, image)`
+    /// as inline data on that user message
+    fn convert_messages_with_images(
+        &self,
+        messages: Vec<ChatMessage>,
+        images: &[(usize, String)],
+    ) -> Vec<GeminiContent> {
+        let mut contents: Vec<GeminiContent> = Vec::new();
         let mut system_prefix: Option<String> = None;
 
-        for msg in messages {
+        for (i, msg) in messages.into_iter().enumerate() {
+            let is_user = msg.role != "system" && msg.role != "assistant";
             match msg.role.as_str() {
                 "system" => {
                     // Gemini doesn't support system role — prepend to first user message
@@ -304,6 +364,20 @@ impl GeminiClient {
                     });
                 }
             }
+
+            if let Some(content) = contents.last_mut().filter(|_| is_user) {
+                content.parts.extend(images.iter().filter(|(index, _)| *index == i).map(
+                    |(_, image)| GeminiPart {
+                        text: None,
+                        inline_data: Some(GeminiInlineData {
+                            mime_type: "image/png".to_string(),
+                            data: image.clone(),
+                        }),
+                        function_call: None,
+                        function_response: None,
+                    },
+                ));
+            }
         }
 
         contents
@@ -399,10 +473,94 @@ impl GeminiClient {
     }
 }
 
+/// A non-success reply from the Gemini API. Displays as an actionable
+/// message; the raw body stays available through `Debug` and `raw_body`.
+#[derive(Debug)]
+pub struct GeminiApiError {
+    pub http_status: u16,
+    /// `error.status` from the envelope, e.g. `RESOURCE_EXHAUSTED`
+    pub status: Option<String>,
+    /// `error.message` from the envelope
+    pub message: Option<String>,
+    pub raw_body: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GeminiErrorEnvelope {
+    error: GeminiErrorBody,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct GeminiErrorBody {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+impl GeminiApiError {
+    fn new(http_status: u16, raw_body: String) -> Self {
+        let envelope = serde_json::from_str::<GeminiErrorEnvelope>(&raw_body).ok();
+        let (status, message) =
+            envelope.map_or((None, None), |e| (e.error.status, e.error.message));
+        Self {
+            http_status,
+            status,
+            message,
+            raw_body,
+        }
+    }
+}
+
+impl std::fmt::Display for GeminiApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = self.message.as_deref().unwrap_or_default();
+        let lower = message.to_lowercase();
+        match (self.status.as_deref(), self.http_status) {
+            (Some("FAILED_PRECONDITION"), _) if lower.contains("location") => write!(
+                f,
+                "The Gemini API is not available in your region. Use a proxy base URL or switch to another provider."
+            ),
+            (Some("RESOURCE_EXHAUSTED"), _) | (_, 429) => {
+                write!(f, "Gemini quota exceeded (rate limit); try again later.")
+            }
+            (Some("INVALID_ARGUMENT"), _) if lower.contains("api key") => {
+                write!(f, "The Gemini API key is invalid. Check it in Settings.")
+            }
+            (Some("PERMISSION_DENIED"), _) | (_, 403) => write!(
+                f,
+                "The Gemini API key doesn't have permission for this request: {}",
+                message
+            ),
+            (Some("NOT_FOUND"), _) | (_, 404) => write!(f, "Gemini model not found: {}", message),
+            (Some("UNAVAILABLE"), _) | (_, 503) => write!(
+                f,
+                "Gemini is temporarily unavailable (server overloaded or network issue); try again shortly."
+            ),
+            _ if self.message.is_some() => {
+                write!(f, "Gemini API error ({}): {}", self.http_status, message)
+            }
+            _ => write!(f, "Gemini API error ({}): {}", self.http_status, self.raw_body),
+        }
+    }
+}
+
+impl std::error::Error for GeminiApiError {}
+
+/// Full URL for an API path. Never includes the key; it is sent in the
+/// `x-goog-api-key` header.
+fn endpoint(base_url: &str, path: &str) -> String {
+    format!("{}/{}", base_url.trim_end_matches('/'), path)
+}
+
 #[async_trait]
 impl AiProvider for GeminiClient {
     async fn chat(&self, messages: Vec<ChatMessage>) -> Result<ChatResponse> {
-        self.do_chat(messages).await
+        self.do_chat(messages, false).await
+    }
+
+    async fn chat_structured(&self, messages: Vec<ChatMessage>) -> Result<ChatResponse> {
+        self.do_chat(messages, true).await
     }
 
     async fn chat_with_vision(
@@ -410,7 +568,17 @@ impl AiProvider for GeminiClient {
         messages: Vec<ChatMessage>,
         image_base64: &str,
     ) -> Result<ChatResponse> {
-        self.do_chat_with_vision(messages, image_base64).await
+        let last = messages.len().saturating_sub(1);
+        self.do_chat_with_vision(messages, &[(last, image_base64.to_string())])
+            .await
+    }
+
+    async fn chat_with_images(
+        &self,
+        messages: Vec<ChatMessage>,
+        images: Vec<(usize, String)>,
+    ) -> Result<ChatResponse> {
+        self.do_chat_with_vision(messages, &images).await
     }
 
     async fn chat_with_tools(
@@ -425,6 +593,20 @@ impl AiProvider for GeminiClient {
         true
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            // Only the retired 1.0 Pro text model lacks image input
+            vision: !matches!(self.model.as_str(), "gemini-pro" | "gemini-1.0-pro"),
+            embeddings: true,
+            streaming: false,
+            json_mode: true,
+        }
+    }
+
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        self.do_embed(texts).await
+    }
+
     async fn validate(&self) -> Result<()> {
         self.do_validate().await
     }
@@ -436,4 +618,160 @@ impl AiProvider for GeminiClient {
     fn model_name(&self) -> &str {
         &self.model
     }
+
+    fn with_model(&self, model: &str) -> Option<Arc<dyn AiProvider>> {
+        Some(Arc::new(Self {
+            model: model.to_string(),
+            ..self.clone()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::http::mock::MockTransport;
+
+    fn client_with(replies: &[(u16, &str)]) -> (GeminiClient, Arc<MockTransport>) {
+        let transport = MockTransport::new(replies);
+        let http = HttpOptions::default().with_transport(transport.clone());
+        let client = GeminiClient::new(
+            "test-key".to_string(),
+            Some("gemini-2.5-flash".to_string()),
+            None,
+            &http,
+        )
+        .unwrap();
+        (client, transport)
+    }
+
+    fn ask() -> Vec<ChatMessage> {
+        vec![ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }]
+    }
+
+    #[tokio::test]
+    async fn chat_replies_are_parsed_from_canned_responses() {
+        let ok = r#"{
+            "candidates": [{"content": {"parts": [{"text": "Hello!"}]}, "finishReason": "STOP"}],
+            "usageMetadata": {"promptTokenCount": 3, "candidatesTokenCount": 2, "totalTokenCount": 5}
+        }"#;
+        let (client, transport) = client_with(&[(200, ok)]);
+        let reply = client.chat(ask()).await.unwrap();
+        assert_eq!(reply.text, "Hello!");
+        assert_eq!(reply.model, "gemini-2.5-flash");
+        assert_eq!(reply.usage.unwrap().total_tokens, 5);
+
+        let sent = &transport.requests()[0];
+        assert!(sent.url.ends_with("/models/gemini-2.5-flash:generateContent"));
+        assert!(sent.headers.contains(&(API_KEY_HEADER.to_string(), "test-key".to_string())));
+        assert_eq!(sent.body.as_ref().unwrap()["contents"][0]["parts"][0]["text"], "hi");
+
+        let quota = r#"{"error": {"code": 429, "message": "Resource has been exhausted", "status": "RESOURCE_EXHAUSTED"}}"#;
+        let (client, _) = client_with(&[(429, quota)]);
+        let err: crate::error::HawkeyeError = client.chat(ask()).await.unwrap_err().into();
+        assert_eq!(err.code(), "rate_limited");
+
+        let (client, _) = client_with(&[(200, "{\"candidates\": [")]);
+        let err = client.chat(ask()).await.unwrap_err();
+        assert!(err.to_string().starts_with("Failed to parse response"));
+
+        let blocked_prompt = r#"{"promptFeedback": {"blockReason": "SAFETY"}}"#;
+        let blocked_reply = r#"{"candidates": [{"finishReason": "SAFETY"}]}"#;
+        let (client, _) = client_with(&[(200, blocked_prompt), (200, blocked_reply)]);
+        let err = client.chat(ask()).await.unwrap_err();
+        assert_eq!(err.to_string(), "Gemini blocked the prompt (SAFETY)");
+        let err = client.chat(ask()).await.unwrap_err();
+        assert_eq!(err.to_string(), "Gemini withheld the response (SAFETY)");
+
+        // Replies exhausted: the transport fails like an unreachable host
+        let err: crate::error::HawkeyeError = client.chat(ask()).await.unwrap_err().into();
+        assert_eq!(err.code(), "network");
+    }
+
+    #[tokio::test]
+    async fn switching_models_keeps_the_key_and_skips_validation() {
+        let ok = r#"{"candidates": [{"content": {"parts": [{"text": "Hi"}]}}]}"#;
+        let (client, transport) = client_with(&[(200, ok)]);
+        let switched = client.with_model("gemini-2.5-pro").unwrap();
+        assert_eq!(switched.model_name(), "gemini-2.5-pro");
+        assert_eq!(client.model_name(), "gemini-2.5-flash");
+
+        assert_eq!(switched.chat(ask()).await.unwrap().model, "gemini-2.5-pro");
+        let sent = transport.requests();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].url.ends_with("/models/gemini-2.5-pro:generateContent"));
+        assert!(sent[0].headers.contains(&(API_KEY_HEADER.to_string(), "test-key".to_string())));
+    }
+
+    #[tokio::test]
+    async fn embeddings_and_validation_go_through_the_transport() {
+        let embeddings = r#"{"embeddings": [{"values": [0.1, 0.2]}, {"values": [0.3, 0.4]}]}"#;
+        let (client, transport) = client_with(&[(200, embeddings), (400, "bad key")]);
+        let vectors = client.embed(vec!["a".to_string(), "b".to_string()]).await.unwrap();
+        assert_eq!(vectors, vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+
+        let err = client.validate().await.unwrap_err();
+        assert_eq!(err.to_string(), "API key validation failed: bad key");
+        let requests = transport.requests();
+        assert_eq!(requests[1].method, crate::ai::http::HttpMethod::Get);
+        assert!(!requests[1].url.contains("key="));
+    }
+
+    #[test]
+    fn error_envelopes_become_actionable_messages() {
+        let region = r#"{"error": {"code": 400, "message": "User location is not supported for the API use.", "status": "FAILED_PRECONDITION"}}"#;
+        let err = GeminiApiError::new(400, region.to_string());
+        assert!(err.to_string().contains("not available in your region"));
+        assert_eq!(err.raw_body, region);
+
+        let quota = r#"{"error": {"code": 429, "message": "Resource has been exhausted", "status": "RESOURCE_EXHAUSTED"}}"#;
+        let err: crate::error::HawkeyeError =
+            anyhow::Error::from(GeminiApiError::new(429, quota.to_string())).into();
+        assert_eq!(err.code(), "rate_limited");
+
+        let other =
+            r#"{"error": {"code": 500, "message": "Internal error", "status": "INTERNAL"}}"#;
+        assert_eq!(
+            GeminiApiError::new(500, other.to_string()).to_string(),
+            "Gemini API error (500): Internal error"
+        );
+        assert_eq!(
+            GeminiApiError::new(502, "<html>Bad Gateway</html>".to_string()).to_string(),
+            "Gemini API error (502): <html>Bad Gateway</html>"
+        );
+    }
+
+    #[test]
+    fn images_follow_their_turns_past_the_system_prompt() {
+        let client =
+            GeminiClient::new("key".to_string(), None, None, &HttpOptions::default()).unwrap();
+        let message = |role: &str, content: &str| ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        };
+        let messages = vec![
+            message("system", "be brief"),
+            message("user", "first screenshot"),
+            message("assistant", "ok"),
+            message("user", "and now?"),
+        ];
+        let images = vec![(1, "AAA".to_string()), (3, "BBB".to_string())];
+
+        let contents = client.convert_messages_with_images(messages, &images);
+
+        assert_eq!(contents.len(), 3);
+        let data = |c: &GeminiContent| -> Vec<String> {
+            c.parts
+                .iter()
+                .filter_map(|p| p.inline_data.as_ref())
+                .map(|d| d.data.clone())
+                .collect()
+        };
+        assert_eq!(data(&contents[0]), vec!["AAA"]);
+        assert!(data(&contents[1]).is_empty());
+        assert_eq!(data(&contents[2]), vec!["BBB"]);
+    }
 }
@@ -0,0 +1,125 @@
+//! Conversation history trimming to fit a model's context window
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::provider::AiProvider;
+use super::tokens;
+use super::types::ChatMessage;
+
+/// Tokens kept free for the model's reply
+pub const RESPONSE_RESERVE_TOKENS: usize = 8192;
+
+/// Upper bound on transcript text sent for summarization (keeps the tail)
+const MAX_SUMMARY_INPUT_CHARS: usize = 48_000;
+
+/// What to do with turns that no longer fit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrimStrategy {
+    #[default]
+    DropOldest,
+    /// Drop, then fold the dropped turns into a summary on the system prompt
+    SummarizeOldest,
+}
+
+/// Prompt budget for `model`, or `None` when its context size is unknown
+pub fn prompt_budget(model: &str) -> Option<usize> {
+    tokens::context_limit(model).map(|limit| limit.saturating_sub(RESPONSE_RESERVE_TOKENS))
+}
+
+/// Remove the oldest turns until `messages` fits `budget` tokens. Leading
+/// system messages and the latest message are always kept. Returns the
+/// removed turns, oldest first.
+pub fn drop_oldest(messages: &mut Vec<ChatMessage>, model: &str, budget: usize) -> Vec<ChatMessage> {
+    let keep_head = messages.iter().take_while(|m| m.role == "system").count();
+    let costs: Vec<usize> = messages
+        .iter()
+        .map(|m| tokens::estimate(std::slice::from_ref(m), model).tokens)
+        .collect();
+
+    let mut total: usize = costs.iter().sum();
+    let mut drop_count = 0;
+    while total > budget && keep_head + drop_count + 1 < messages.len() {
+        total -= costs[keep_head + drop_count];
+        drop_count += 1;
+    }
+
+    messages.drain(keep_head..keep_head + drop_count).collect()
+}
+
+/// Summarize `removed` with one provider call and append the summary to the
+/// system prompt (creating one if needed)
+pub async fn summarize_into(
+    messages: &mut Vec<ChatMessage>,
+    removed: &[ChatMessage],
+    provider: &dyn AiProvider,
+) -> Result<()> {
+    let transcript = removed
+        .iter()
+        .map(|m| format!("{}: {}", m.role, m.content))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let skip = transcript.chars().count().saturating_sub(MAX_SUMMARY_INPUT_CHARS);
+    let transcript: String = transcript.chars().skip(skip).collect();
+
+    let response = provider
+        .chat(vec![ChatMessage {
+            role: "user".to_string(),
+            content: format!(
+                "Summarize this earlier part of a conversation in a few sentences. \
+                 Keep facts, decisions, and open questions.\n\n{}",
+                transcript
+            ),
+        }])
+        .await?;
+
+    let note = format!("Summary of earlier conversation:\n{}", response.text.trim());
+    // Gemini only keeps one system message, so extend the existing one
+    match messages.first_mut().filter(|m| m.role == "system") {
+        Some(system) => system.content = format!("{}\n\n{}", system.content, note),
+        None => messages.insert(
+            0,
+            ChatMessage {
+                role: "system".to_string(),
+                content: note,
+            },
+        ),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, content: &str) -> ChatMessage {
+        ChatMessage { role: role.into(), content: content.into() }
+    }
+
+    #[test]
+    fn drop_oldest_keeps_system_and_latest_turn() {
+        let filler = "x".repeat(400); // ~100 heuristic tokens
+        let mut messages = vec![
+            msg("system", "be brief"),
+            msg("user", &filler),
+            msg("assistant", &filler),
+            msg("user", "latest"),
+        ];
+
+        let removed = drop_oldest(&mut messages, "gemini-2.5-flash", 20);
+
+        assert_eq!(removed.len(), 2);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, "system");
+        assert_eq!(messages[1].content, "latest");
+    }
+
+    #[test]
+    fn drop_oldest_is_noop_within_budget() {
+        let mut messages = vec![msg("user", "hi")];
+        assert!(drop_oldest(&mut messages, "gemini-2.5-flash", 100).is_empty());
+        assert_eq!(messages.len(), 1);
+    }
+}
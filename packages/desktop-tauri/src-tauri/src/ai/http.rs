@@ -0,0 +1,394 @@
+//! Shared HTTP client construction for AI providers
+//!
+//! Provider clients send through an [`HttpTransport`] rather than a
+//! `reqwest::Client` directly, so tests can inject canned replies.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::{Client, Url};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::AppConfig;
+
+/// Default total request timeout for AI provider calls
+pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Connect timeout — kept short so an unreachable host fails fast
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+
+/// Response bodies are cut to this many characters when logged
+const MAX_LOGGED_BODY_CHARS: usize = 2_000;
+
+/// Query parameters that carry credentials and are never logged
+const SECRET_QUERY_PARAMS: &[&str] = &["key", "api_key", "apikey", "access_token", "token"];
+
+/// Network settings applied to every AI provider client
+#[derive(Debug, Clone, Default)]
+pub struct HttpOptions {
+    /// Total request timeout in seconds (`None` → [`DEFAULT_TIMEOUT_SECS`])
+    pub timeout_secs: Option<u64>,
+    /// Proxy URL applied to all schemes (e.g. "http://proxy.corp:8080")
+    pub proxy: Option<String>,
+    /// Log each request's redacted endpoint and shape, and the response
+    /// status and (truncated) body
+    pub log_requests: bool,
+    /// Also forwards those records, e.g. to the debug timeline
+    pub tracer: Option<AiTracer>,
+    /// Send through this instead of a reqwest client built from the
+    /// settings above (tests)
+    pub transport: Option<Arc<dyn HttpTransport>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+/// A provider request: no client state, so it can be inspected in tests
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<serde_json::Value>,
+}
+
+impl HttpRequest {
+    pub fn get(url: &str) -> Self {
+        Self {
+            method: HttpMethod::Get,
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    pub fn post_json(url: &str, body: &impl serde::Serialize) -> Result<Self> {
+        Ok(Self {
+            method: HttpMethod::Post,
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: Some(serde_json::to_value(body)?),
+        })
+    }
+
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+}
+
+/// Status and body text of a reply, whatever the status
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// No reply arrived. Keeps the `reqwest::Error` as its source so callers
+/// can classify the failure by type.
+#[derive(Debug, thiserror::Error)]
+pub enum TransportError {
+    #[error("HTTP request failed: {0}")]
+    Send(#[source] reqwest::Error),
+    #[error("Failed to read response: {0}")]
+    Read(#[source] reqwest::Error),
+}
+
+/// A non-success reply, keeping the HTTP status for classification
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct HttpStatusError {
+    pub status: u16,
+    pub message: String,
+}
+
+/// Sends provider requests. Errors only when no reply arrived; non-2xx
+/// statuses are returned for the client to interpret.
+#[async_trait]
+pub trait HttpTransport: Send + Sync + std::fmt::Debug {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+/// The real transport
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport(Client);
+
+#[async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut builder = match request.method {
+            HttpMethod::Get => self.0.get(&request.url),
+            HttpMethod::Post => self.0.post(&request.url),
+        };
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &request.body {
+            builder = builder.json(body);
+        }
+
+        let response = builder.send().await.map_err(TransportError::Send)?;
+        let status = response.status().as_u16();
+        let body = response.text().await.map_err(TransportError::Read)?;
+        Ok(HttpResponse { status, body })
+    }
+}
+
+/// Which half of an exchange an [`AiTrace`] describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AiTraceKind {
+    Request,
+    Response,
+}
+
+/// A redacted request or response record. `data` never contains API keys
+/// or auth headers.
+#[derive(Debug, Clone)]
+pub struct AiTrace {
+    pub kind: AiTraceKind,
+    pub label: String,
+    pub data: serde_json::Value,
+    pub duration_ms: Option<u64>,
+}
+
+/// Callback receiving [`AiTrace`] records while request logging is on
+#[derive(Clone)]
+pub struct AiTracer(Arc<dyn Fn(AiTrace) + Send + Sync>);
+
+impl AiTracer {
+    pub fn new(f: impl Fn(AiTrace) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl std::fmt::Debug for AiTracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AiTracer")
+    }
+}
+
+/// Per-client request logging, built from [`HttpOptions::request_logger`].
+/// Only ever sees redacted URLs; headers are never logged.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestLogger {
+    enabled: bool,
+    tracer: Option<AiTracer>,
+}
+
+impl RequestLogger {
+    /// Record an outgoing request. `shape` describes it (model, message
+    /// count, ...) without the prompt text.
+    pub fn request(&self, provider: &str, url: &str, shape: serde_json::Value) {
+        if !self.enabled {
+            return;
+        }
+        let endpoint = redact_url(url);
+        log::debug!("[AI] {} request → {} {}", provider, endpoint, shape);
+        self.trace(AiTrace {
+            kind: AiTraceKind::Request,
+            label: format!("{} request", provider),
+            data: serde_json::json!({ "provider": provider, "endpoint": endpoint, "request": shape }),
+            duration_ms: None,
+        });
+    }
+
+    /// Record a response and its body, truncated
+    pub fn response(&self, provider: &str, url: &str, status: u16, body: &str, duration_ms: u64) {
+        if !self.enabled {
+            return;
+        }
+        let endpoint = redact_url(url);
+        let body = truncate_body(body);
+        log::debug!(
+            "[AI] {} response ← {} {} ({}ms): {}",
+            provider,
+            endpoint,
+            status,
+            duration_ms,
+            body
+        );
+        self.trace(AiTrace {
+            kind: AiTraceKind::Response,
+            label: format!("{} response ({})", provider, status),
+            data: serde_json::json!({
+                "provider": provider,
+                "endpoint": endpoint,
+                "status": status,
+                "body": body,
+            }),
+            duration_ms: Some(duration_ms),
+        });
+    }
+
+    fn trace(&self, trace: AiTrace) {
+        if let Some(tracer) = &self.tracer {
+            (tracer.0)(trace);
+        }
+    }
+}
+
+/// `url` with credential query parameters replaced by `REDACTED`. A URL
+/// that doesn't parse loses its whole query string instead.
+pub fn redact_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.split('?').next().unwrap_or_default().to_string();
+    };
+    if parsed.query().is_none() {
+        return parsed.to_string();
+    }
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(k, v)| {
+            let secret = SECRET_QUERY_PARAMS.iter().any(|s| k.eq_ignore_ascii_case(s));
+            let value = if secret { "REDACTED".to_string() } else { v.into_owned() };
+            (k.into_owned(), value)
+        })
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+fn truncate_body(body: &str) -> String {
+    match body.char_indices().nth(MAX_LOGGED_BODY_CHARS) {
+        Some((cut, _)) => format!("{}… ({} bytes total)", &body[..cut], body.len()),
+        None => body.to_string(),
+    }
+}
+
+impl HttpOptions {
+    /// Read network settings from the app config
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            timeout_secs: config.request_timeout_secs,
+            proxy: config.http_proxy.clone(),
+            log_requests: config.log_ai_requests,
+            tracer: None,
+            transport: None,
+        }
+    }
+
+    /// Forward request/response records to `tracer` when logging is on
+    pub fn with_tracer(mut self, tracer: AiTracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// Send through `transport` instead of the network
+    pub fn with_transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// The injected transport, or a reqwest one built from these settings
+    pub(crate) fn transport(&self) -> Arc<dyn HttpTransport> {
+        match &self.transport {
+            Some(transport) => transport.clone(),
+            None => Arc::new(ReqwestTransport(self.build_client())),
+        }
+    }
+
+    pub(crate) fn request_logger(&self) -> RequestLogger {
+        RequestLogger {
+            enabled: self.log_requests,
+            tracer: self.tracer.clone().filter(|_| self.log_requests),
+        }
+    }
+
+    /// Build a reqwest client. An unparseable proxy URL is logged and
+    /// ignored rather than failing client construction.
+    pub fn build_client(&self) -> Client {
+        let timeout = self
+            .timeout_secs
+            .filter(|s| *s > 0)
+            .unwrap_or(DEFAULT_TIMEOUT_SECS);
+
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(timeout))
+            .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS));
+
+        if let Some(url) = self.proxy.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+            match reqwest::Proxy::all(url) {
+                Ok(proxy) => builder = builder.proxy(proxy),
+                Err(e) => log::warn!("[AI] Ignoring invalid HTTP proxy '{}': {}", url, e),
+            }
+        }
+
+        builder.build().unwrap_or_else(|_| Client::new())
+    }
+}
+
+/// Canned-reply transport for provider client tests
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    pub struct MockTransport {
+        replies: Mutex<VecDeque<HttpResponse>>,
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl MockTransport {
+        /// Answer successive requests with these `(status, body)` replies;
+        /// once they run out, requests fail as if the host were unreachable
+        pub fn new(replies: &[(u16, &str)]) -> Arc<Self> {
+            let replies = replies
+                .iter()
+                .map(|(status, body)| HttpResponse {
+                    status: *status,
+                    body: body.to_string(),
+                })
+                .collect();
+            Arc::new(Self {
+                replies: Mutex::new(replies),
+                requests: Mutex::default(),
+            })
+        }
+
+        /// Requests sent so far, oldest first
+        pub fn requests(&self) -> Vec<HttpRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl HttpTransport for MockTransport {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+            self.requests.lock().unwrap().push(request);
+            self.replies.lock().unwrap().pop_front().ok_or_else(|| {
+                crate::error::HawkeyeError::Network(
+                    "HTTP request failed: connection refused".to_string(),
+                )
+                .into()
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_credentials_in_urls() {
+        let url = "https://example.com/v1/models/m:generateContent?key=sk-secret&alt=sse";
+        let redacted = redact_url(url);
+        assert!(!redacted.contains("sk-secret"));
+        assert!(redacted.contains("key=REDACTED"));
+        assert!(redacted.contains("alt=sse"));
+
+        assert_eq!(redact_url("not a url?key=sk-secret"), "not a url");
+        assert_eq!(redact_url("https://example.com/v1/models"), "https://example.com/v1/models");
+    }
+}
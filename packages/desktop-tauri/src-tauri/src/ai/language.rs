@@ -0,0 +1,70 @@
+//! Reply language for AI prompts
+//!
+//! The summary, intent and experiment prompts end with an instruction line
+//! naming the reply language. English and Chinese get a native-language
+//! instruction, which models follow more reliably than an English one;
+//! anything else is passed through as "Respond in {language}."
+
+/// Language the AI features answer in, from `AppConfig::language`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ReplyLanguage {
+    #[default]
+    English,
+    /// Simplified Chinese
+    Chinese,
+    /// Any other language, by name (e.g. "Japanese")
+    Other(String),
+}
+
+impl ReplyLanguage {
+    /// Parse a configured language name or code. Blank or missing → English.
+    pub fn from_config(language: Option<&str>) -> Self {
+        let Some(language) = language.map(str::trim).filter(|l| !l.is_empty()) else {
+            return Self::English;
+        };
+        match language.to_lowercase().as_str() {
+            "en" | "en-us" | "en-gb" | "english" => Self::English,
+            "zh" | "zh-cn" | "zh-hans" | "cn" | "chinese" | "中文" | "简体中文" => Self::Chinese,
+            _ => Self::Other(language.to_string()),
+        }
+    }
+
+    /// Instruction for free-text replies
+    pub fn instruction(&self) -> String {
+        match self {
+            Self::English => "Respond in English.".to_string(),
+            Self::Chinese => "请用简体中文回答。".to_string(),
+            Self::Other(language) => format!("Respond in {}.", language),
+        }
+    }
+
+    /// Instruction for JSON replies: values are localized, keys are not
+    pub fn json_instruction(&self) -> String {
+        match self {
+            Self::English => "Write the JSON string values in English.".to_string(),
+            Self::Chinese => "JSON 的字符串值请用简体中文书写，键名保持英文不变。".to_string(),
+            Self::Other(language) => format!(
+                "Write the JSON string values in {}; keep the keys in English.",
+                language
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_codes_and_names() {
+        assert_eq!(ReplyLanguage::from_config(None), ReplyLanguage::English);
+        assert_eq!(ReplyLanguage::from_config(Some(" ")), ReplyLanguage::English);
+        assert_eq!(ReplyLanguage::from_config(Some("zh-CN")), ReplyLanguage::Chinese);
+        assert_eq!(ReplyLanguage::from_config(Some("中文")), ReplyLanguage::Chinese);
+        assert_eq!(
+            ReplyLanguage::from_config(Some("Japanese")).instruction(),
+            "Respond in Japanese."
+        );
+        assert_eq!(ReplyLanguage::Chinese.instruction(), "请用简体中文回答。");
+    }
+}
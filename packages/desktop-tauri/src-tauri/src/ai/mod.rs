@@ -1,13 +1,139 @@
 //! AI module — multi-provider chat (Gemini, OpenAI-compatible, local llama.cpp)
 
+pub mod conversations;
+pub mod diagnostics;
 pub mod gemini;
+pub mod history;
+pub mod http;
+pub mod language;
 pub mod local;
 pub mod openai;
+pub mod persona;
 pub mod provider;
+pub mod rate_limit;
+pub mod tokens;
 pub mod types;
 
 pub use gemini::GeminiClient;
+pub use http::HttpOptions;
+pub use language::ReplyLanguage;
 pub use local::LocalProvider;
 pub use openai::OpenAiClient;
+pub use persona::Persona;
 pub use provider::AiProvider;
-pub use types::{ChatMessage, ChatResponse};
+pub use types::{Capabilities, ChatMessage, ChatResponse};
+
+/// Clean up a user-supplied API base URL: surrounding whitespace and
+/// trailing slashes are dropped, so endpoint paths can be appended with a
+/// single `/`. Blank means the provider's `default`. Errors when the URL
+/// doesn't parse, isn't http(s), or carries a query or fragment that would
+/// end up in the middle of every request URL.
+pub fn normalize_base_url(base_url: Option<&str>, default: &str) -> anyhow::Result<String> {
+    let Some(raw) = base_url.map(str::trim).filter(|u| !u.is_empty()) else {
+        return Ok(default.to_string());
+    };
+    let url = reqwest::Url::parse(raw)
+        .map_err(|e| anyhow::anyhow!("Invalid API base URL '{}': {}", raw, e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("API base URL '{}' must start with http:// or https://", raw);
+    }
+    if url.host_str().map_or(true, str::is_empty) {
+        anyhow::bail!("API base URL '{}' has no host", raw);
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        anyhow::bail!("API base URL '{}' must not contain a query or fragment", raw);
+    }
+    Ok(url.as_str().trim_end_matches('/').to_string())
+}
+
+/// Find the JSON object in a model reply: the first balanced `{...}` that
+/// parses, looking inside a ```` ```json ```` fence first when there is one.
+/// Braces in string literals don't count, so leading prose (even prose with
+/// braces of its own) and trailing commentary are skipped.
+pub fn extract_json(text: &str) -> Option<&str> {
+    let search_from = text.find("```json").map(|i| i + "```json".len()).unwrap_or(0);
+    let rest = &text[search_from..];
+    rest.match_indices('{')
+        .filter_map(|(start, _)| balanced_object(&rest[start..]))
+        .find(|candidate| serde_json::from_str::<serde::de::IgnoredAny>(candidate).is_ok())
+}
+
+/// The `{...}` opening `text`, up to its matching close brace
+fn balanced_object(text: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&text[..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_json, normalize_base_url};
+
+    #[test]
+    fn normalizes_base_urls() {
+        let default = "https://api.example.com/v1";
+        assert_eq!(normalize_base_url(None, default).unwrap(), default);
+        assert_eq!(normalize_base_url(Some("  "), default).unwrap(), default);
+        assert_eq!(
+            normalize_base_url(Some(" https://openrouter.ai/api/v1/ "), default).unwrap(),
+            "https://openrouter.ai/api/v1"
+        );
+        assert_eq!(
+            normalize_base_url(Some("http://localhost:11434/v1//"), default).unwrap(),
+            "http://localhost:11434/v1"
+        );
+        assert_eq!(
+            normalize_base_url(Some("https://proxy.example.com/"), default).unwrap(),
+            "https://proxy.example.com"
+        );
+
+        for bad in [
+            "api.openai.com/v1",
+            "localhost:8080",
+            "ftp://example.com",
+            "https://",
+            "https://example.com/v1?key=abc",
+            "https://exa mple.com",
+        ] {
+            assert!(normalize_base_url(Some(bad), default).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn extracts_fenced_and_embedded_objects() {
+        let fenced = "Here you go:\n```json\n{\"a\": {\"b\": 1}}\n```\nEnjoy!";
+        assert_eq!(extract_json(fenced), Some("{\"a\": {\"b\": 1}}"));
+
+        let prose = "Sure {thing}. Result: {\"title\": \"use } sparingly\"} — hope it helps";
+        assert_eq!(extract_json(prose), Some("{\"title\": \"use } sparingly\"}"));
+
+        let trailing = "{\"title\": \"a \\\"quoted\\\" }\"} and some commentary {x}";
+        assert_eq!(extract_json(trailing), Some("{\"title\": \"a \\\"quoted\\\" }\"}"));
+
+        assert_eq!(extract_json("{\"unterminated\": 1"), None);
+        assert_eq!(extract_json("no json"), None);
+    }
+}
@@ -2,22 +2,25 @@
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::time::Instant;
 
+use super::http::{HttpOptions, HttpRequest, HttpStatusError, HttpTransport, RequestLogger};
 use super::provider::AiProvider;
 use super::types::*;
 
 const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const EMBEDDING_MODEL: &str = "text-embedding-3-small";
 
 /// OpenAI-compatible HTTP client
 #[derive(Debug, Clone)]
 pub struct OpenAiClient {
-    client: Client,
+    transport: Arc<dyn HttpTransport>,
     api_key: String,
     model: String,
     base_url: String,
+    log: RequestLogger,
 }
 
 // --- OpenAI API wire types ---
@@ -30,6 +33,15 @@ struct OpenAiRequest {
     max_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<OpenAiResponseFormat>,
+}
+
+/// `{ "type": "json_object" }` enables JSON mode
+#[derive(Debug, Serialize)]
+struct OpenAiResponseFormat {
+    #[serde(rename = "type")]
+    format_type: &'static str,
 }
 
 #[derive(Debug, Serialize)]
@@ -69,6 +81,8 @@ struct OpenAiResponse {
 #[derive(Debug, Deserialize)]
 struct OpenAiChoice {
     message: Option<OpenAiResponseMessage>,
+    /// `stop`, `length`, `content_filter`, ...
+    finish_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -83,6 +97,23 @@ struct OpenAiUsage {
     total_tokens: Option<u32>,
 }
 
+#[derive(Debug, Serialize)]
+struct OpenAiEmbeddingRequest {
+    model: String,
+    input: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbeddingResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
 #[derive(Debug, Deserialize)]
 struct OpenAiModelsResponse {
     data: Option<Vec<OpenAiModel>>,
@@ -94,20 +125,20 @@ struct OpenAiModel {
 }
 
 impl OpenAiClient {
-    /// Create a new OpenAI-compatible client
-    pub fn new(api_key: String, model: Option<String>, base_url: Option<String>) -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(60))
-                .connect_timeout(Duration::from_secs(10))
-                .build()
-                .unwrap_or_else(|_| Client::new()),
+    /// Create a new OpenAI-compatible client. Fails on a malformed `base_url`.
+    pub fn new(
+        api_key: String,
+        model: Option<String>,
+        base_url: Option<String>,
+        http: &HttpOptions,
+    ) -> Result<Self> {
+        Ok(Self {
+            transport: http.transport(),
             api_key,
             model: model.unwrap_or_else(|| "gpt-4o".to_string()),
-            base_url: base_url
-                .map(|u| u.trim_end_matches('/').to_string())
-                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
-        }
+            base_url: super::normalize_base_url(base_url.as_deref(), DEFAULT_BASE_URL)?,
+            log: http.request_logger(),
+        })
     }
 
     /// Convert ChatMessages to OpenAI format
@@ -121,43 +152,60 @@ impl OpenAiClient {
             .collect()
     }
 
-    /// Convert ChatMessages to OpenAI format with vision support
+    /// Convert ChatMessages to OpenAI format with vision support: the image
+    /// goes on the last message when it is the user's
     fn convert_messages_with_vision(
         &self,
         messages: Vec<ChatMessage>,
         image_base64: &str,
     ) -> Vec<OpenAiMessage> {
-        let mut result: Vec<OpenAiMessage> = Vec::new();
-
-        for (i, msg) in messages.iter().enumerate() {
-            if msg.role == "user" && i == messages.len() - 1 {
-                // Last user message — add image
-                result.push(OpenAiMessage {
-                    role: msg.role.clone(),
-                    content: OpenAiContent::Parts(vec![
-                        OpenAiContentPart::Text {
-                            text: msg.content.clone(),
-                        },
-                        OpenAiContentPart::ImageUrl {
-                            image_url: OpenAiImageUrl {
-                                url: format!("data:image/png;base64,{}", image_base64),
-                            },
-                        },
-                    ]),
-                });
-            } else {
-                result.push(OpenAiMessage {
-                    role: msg.role.clone(),
-                    content: OpenAiContent::Text(msg.content.clone()),
-                });
-            }
-        }
+        let last = messages.len().saturating_sub(1);
+        self.convert_messages_with_images(messages, &[(last, image_base64.to_string())])
+    }
 
-        result
+    /// Convert ChatMessages to OpenAI format, attaching each `(index, image)`
+    /// to that user message. Several images may share a message.
+    fn convert_messages_with_images(
+        &self,
+        messages: Vec<ChatMessage>,
+        images: &[(usize, String)],
+    ) -> Vec<OpenAiMessage> {
+        messages
+            .into_iter()
+            .enumerate()
+            .map(|(i, msg)| {
+                let attached: Vec<&String> = images
+                    .iter()
+                    .filter(|(index, _)| *index == i)
+                    .map(|(_, image)| image)
+                    .collect();
+                if msg.role != "user" || attached.is_empty() {
+                    return OpenAiMessage {
+                        role: msg.role,
+                        content: OpenAiContent::Text(msg.content),
+                    };
+                }
+
+                let mut parts = vec![OpenAiContentPart::Text { text: msg.content }];
+                parts.extend(attached.into_iter().map(|image| OpenAiContentPart::ImageUrl {
+                    image_url: OpenAiImageUrl {
+                        url: format!("data:image/png;base64,{}", image),
+                    },
+                }));
+                OpenAiMessage {
+                    role: msg.role,
+                    content: OpenAiContent::Parts(parts),
+                }
+            })
+            .collect()
     }
 
-    /// Make a chat completion request
-    async fn do_chat(&self, openai_messages: Vec<OpenAiMessage>) -> Result<ChatResponse> {
+    /// Make a chat completion request; `json` turns on JSON mode
+    async fn do_chat(
+        &self,
+        openai_messages: Vec<OpenAiMessage>,
+        json: bool,
+    ) -> Result<ChatResponse> {
         let start = Instant::now();
 
         let request = OpenAiRequest {
@@ -165,39 +213,49 @@ impl OpenAiClient {
             messages: openai_messages,
             max_tokens: Some(8192),
             temperature: Some(0.7),
+            response_format: json.then_some(OpenAiResponseFormat {
+                format_type: "json_object",
+            }),
         };
 
         let url = format!("{}/chat/completions", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow!("OpenAI API error ({}): {}", status, body));
+        self.log.request(
+            "openai",
+            &url,
+            serde_json::json!({
+                "model": request.model,
+                "messages": request.messages.len(),
+                "maxTokens": request.max_tokens,
+                "jsonMode": request.response_format.is_some(),
+            }),
+        );
+
+        let request = HttpRequest::post_json(&url, &request)?
+            .header("Authorization", format!("Bearer {}", self.api_key));
+        let response = self.transport.send(request).await?;
+
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        self.log.response("openai", &url, response.status, &response.body, elapsed_ms);
+        if !response.is_success() {
+            return Err(api_error(response.status, &response.body));
         }
 
-        let openai_response: OpenAiResponse = response
-            .json()
-            .await
+        let openai_response: OpenAiResponse = serde_json::from_str(&response.body)
             .map_err(|e| anyhow!("Failed to parse response: {}", e))?;
 
-        let text = openai_response
-            .choices
-            .as_ref()
-            .and_then(|c| c.first())
+        let choice = openai_response.choices.as_ref().and_then(|c| c.first());
+        let text = choice
             .and_then(|c| c.message.as_ref())
             .and_then(|m| m.content.as_ref())
             .cloned()
             .unwrap_or_default();
+        // A filtered reply comes back as a 200 with no content
+        if let Some(reason) = choice
+            .and_then(|c| c.finish_reason.as_deref())
+            .filter(|r| text.is_empty() && *r == "content_filter")
+        {
+            return Err(anyhow!("OpenAI withheld the response ({})", reason));
+        }
 
         let usage = openai_response.usage.map(|u| UsageInfo {
             prompt_tokens: u.prompt_tokens.unwrap_or(0),
@@ -216,13 +274,82 @@ impl OpenAiClient {
             usage,
         })
     }
+
+    /// Embed texts via `/embeddings`
+    async fn do_embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let request = OpenAiEmbeddingRequest {
+            model: EMBEDDING_MODEL.to_string(),
+            input: texts,
+        };
+
+        let url = format!("{}/embeddings", self.base_url);
+        self.log.request(
+            "openai",
+            &url,
+            serde_json::json!({ "model": request.model, "texts": request.input.len() }),
+        );
+
+        let start = Instant::now();
+        let request = HttpRequest::post_json(&url, &request)?
+            .header("Authorization", format!("Bearer {}", self.api_key));
+        let response = self.transport.send(request).await?;
+
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+        self.log.response("openai", &url, response.status, &response.body, elapsed_ms);
+        if !response.is_success() {
+            return Err(api_error(response.status, &response.body));
+        }
+
+        let mut parsed: OpenAiEmbeddingResponse = serde_json::from_str(&response.body)
+            .map_err(|e| anyhow!("Failed to parse response: {}", e))?;
+
+        // The API documents `index` as the input position; don't rely on order
+        parsed.data.sort_by_key(|e| e.index);
+        Ok(parsed.data.into_iter().map(|e| e.embedding).collect())
+    }
+}
+
+/// A non-success reply. Carries the status so a 429 classifies as
+/// `rate_limited` whatever the body holds.
+fn api_error(status: u16, body: &str) -> anyhow::Error {
+    let message = if status == 429 {
+        format!("OpenAI API rate limit exceeded (429): {}", body)
+    } else {
+        format!("OpenAI API error ({}): {}", status, body)
+    };
+    HttpStatusError { status, message }.into()
+}
+
+/// Whether a model behind an OpenAI-compatible endpoint takes images. The
+/// API doesn't say, so this goes by well-known names: OpenAI's multimodal
+/// families and the usual markers on open-weight vision models.
+fn model_has_vision(model: &str) -> bool {
+    let model = model.to_lowercase();
+    // Strip an OpenRouter-style `vendor/` prefix
+    let name = model.rsplit('/').next().unwrap_or(&model);
+    const FAMILIES: &[&str] = &["gpt-4o", "gpt-4.1", "gpt-4-turbo", "gpt-5", "o1", "o3", "o4"];
+    const MARKERS: &[&str] = &["vision", "-vl", "llava", "pixtral", "gemini", "claude-3"];
+    if name.starts_with("o1-mini") || name.starts_with("o3-mini") {
+        return false;
+    }
+    FAMILIES.iter().any(|family| name.starts_with(family))
+        || MARKERS.iter().any(|marker| name.contains(marker))
 }
 
 #[async_trait]
 impl AiProvider for OpenAiClient {
     async fn chat(&self, messages: Vec<ChatMessage>) -> Result<ChatResponse> {
         let openai_messages = self.convert_messages(messages);
-        self.do_chat(openai_messages).await
+        self.do_chat(openai_messages, false).await
+    }
+
+    async fn chat_structured(&self, messages: Vec<ChatMessage>) -> Result<ChatResponse> {
+        let openai_messages = self.convert_messages(messages);
+        self.do_chat(openai_messages, true).await
     }
 
     async fn chat_with_vision(
@@ -231,25 +358,42 @@ impl AiProvider for OpenAiClient {
         image_base64: &str,
     ) -> Result<ChatResponse> {
         let openai_messages = self.convert_messages_with_vision(messages, image_base64);
-        self.do_chat(openai_messages).await
+        self.do_chat(openai_messages, false).await
+    }
+
+    async fn chat_with_images(
+        &self,
+        messages: Vec<ChatMessage>,
+        images: Vec<(usize, String)>,
+    ) -> Result<ChatResponse> {
+        let openai_messages = self.convert_messages_with_images(messages, &images);
+        self.do_chat(openai_messages, false).await
+    }
+
+    async fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        self.do_embed(texts).await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            vision: model_has_vision(&self.model),
+            embeddings: true,
+            streaming: false,
+            json_mode: true,
+        }
     }
 
     async fn validate(&self) -> Result<()> {
         let url = format!("{}/models", self.base_url);
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .send()
-            .await
-            .map_err(|e| anyhow!("Validation request failed: {}", e))?;
+        let request =
+            HttpRequest::get(&url).header("Authorization", format!("Bearer {}", self.api_key));
+        let response = self.transport.send(request).await?;
 
-        if response.status().is_success() {
+        if response.is_success() {
             Ok(())
         } else {
-            let body = response.text().await.unwrap_or_default();
-            Err(anyhow!("API key validation failed: {}", body))
+            Err(anyhow!("API key validation failed: {}", response.body))
         }
     }
 
@@ -260,4 +404,122 @@ impl AiProvider for OpenAiClient {
     fn model_name(&self) -> &str {
         &self.model
     }
+
+    fn with_model(&self, model: &str) -> Option<Arc<dyn AiProvider>> {
+        Some(Arc::new(Self {
+            model: model.to_string(),
+            ..self.clone()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::http::mock::MockTransport;
+
+    fn client_with(replies: &[(u16, &str)]) -> (OpenAiClient, Arc<MockTransport>) {
+        let transport = MockTransport::new(replies);
+        let http = HttpOptions::default().with_transport(transport.clone());
+        let client = OpenAiClient::new(
+            "sk-test".to_string(),
+            None,
+            Some("https://llm.example/v1/".to_string()),
+            &http,
+        )
+        .unwrap();
+        (client, transport)
+    }
+
+    fn ask() -> Vec<ChatMessage> {
+        vec![ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        }]
+    }
+
+    #[tokio::test]
+    async fn chat_replies_are_parsed_from_canned_responses() {
+        let ok = r#"{
+            "model": "gpt-4o-2024-08-06",
+            "choices": [{"message": {"role": "assistant", "content": "Hello!"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 3, "completion_tokens": 2, "total_tokens": 5}
+        }"#;
+        let (client, transport) = client_with(&[(200, ok)]);
+        let reply = client.chat_structured(ask()).await.unwrap();
+        assert_eq!(reply.text, "Hello!");
+        assert_eq!(reply.model, "gpt-4o-2024-08-06");
+        assert_eq!(reply.usage.unwrap().prompt_tokens, 3);
+
+        let sent = &transport.requests()[0];
+        assert_eq!(sent.url, "https://llm.example/v1/chat/completions");
+        assert!(sent
+            .headers
+            .contains(&("Authorization".to_string(), "Bearer sk-test".to_string())));
+        let body = sent.body.as_ref().unwrap();
+        assert_eq!(body["model"], "gpt-4o");
+        assert_eq!(body["response_format"]["type"], "json_object");
+
+        let limited = r#"{"error": {"message": "Too many requests", "type": "requests"}}"#;
+        let (client, _) = client_with(&[(429, limited)]);
+        let err: crate::error::HawkeyeError = client.chat(ask()).await.unwrap_err().into();
+        assert_eq!(err.code(), "rate_limited");
+
+        let (client, _) = client_with(&[(200, "<html>gateway</html>")]);
+        let err = client.chat(ask()).await.unwrap_err();
+        assert!(err.to_string().starts_with("Failed to parse response"));
+
+        let filtered = r#"{"choices": [{"message": {"content": null}, "finish_reason": "content_filter"}]}"#;
+        let (client, _) = client_with(&[(200, filtered)]);
+        let err = client.chat(ask()).await.unwrap_err();
+        assert_eq!(err.to_string(), "OpenAI withheld the response (content_filter)");
+    }
+
+    #[tokio::test]
+    async fn embeddings_are_returned_in_input_order() {
+        let embeddings = r#"{"data": [
+            {"index": 1, "embedding": [0.3, 0.4]},
+            {"index": 0, "embedding": [0.1, 0.2]}
+        ]}"#;
+        let (client, _) = client_with(&[(200, embeddings), (500, "oops")]);
+        let vectors = client.embed(vec!["a".to_string(), "b".to_string()]).await.unwrap();
+        assert_eq!(vectors, vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+
+        let err = client.embed(vec!["c".to_string()]).await.unwrap_err();
+        assert_eq!(err.to_string(), "OpenAI API error (500): oops");
+    }
+
+    #[test]
+    fn vision_is_reported_per_model() {
+        assert!(model_has_vision("gpt-4o-mini"));
+        assert!(model_has_vision("openai/gpt-4.1"));
+        assert!(model_has_vision("qwen2.5-vl-7b-instruct"));
+        assert!(!model_has_vision("gpt-3.5-turbo"));
+        assert!(!model_has_vision("o3-mini"));
+        assert!(!model_has_vision("llama-3.1-8b-instruct"));
+    }
+
+    #[test]
+    fn images_attach_to_their_user_turns() {
+        let client =
+            OpenAiClient::new("sk".to_string(), None, None, &HttpOptions::default()).unwrap();
+        let message = |role: &str, content: &str| ChatMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+        };
+        let messages = vec![
+            message("user", "what's this?"),
+            message("assistant", "a chart"),
+            message("user", "compare it to this one"),
+        ];
+        let images = vec![(0, "AAA".to_string()), (1, "BBB".to_string()), (2, "CCC".to_string())];
+
+        let converted = client.convert_messages_with_images(messages, &images);
+        let json = serde_json::to_value(&converted).unwrap();
+
+        assert_eq!(json[0]["content"][1]["image_url"]["url"], "data:image/png;base64,AAA");
+        // Images on assistant turns are dropped
+        assert_eq!(json[1]["content"], "a chart");
+        assert_eq!(json[2]["content"][1]["image_url"]["url"], "data:image/png;base64,CCC");
+    }
 }
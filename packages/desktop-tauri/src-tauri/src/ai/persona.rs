@@ -0,0 +1,189 @@
+//! User-configured assistant persona
+//!
+//! `system_prompt`, `personality` and, when set, the `language` instruction
+//! lead every `chat` as its system message; `personality` is also appended to the
+//! summary, intent and experiment prompts, so every AI feature answers in
+//! the same voice. Those prompts template `language` into their own
+//! instruction lines (see [`ReplyLanguage`]).
+
+use super::language::ReplyLanguage;
+use super::types::ChatMessage;
+use crate::config::AppConfig;
+
+#[derive(Debug, Clone, Default)]
+pub struct Persona {
+    pub system_prompt: Option<String>,
+    /// Tone / character, e.g. "concise and friendly"
+    pub personality: Option<String>,
+    /// Reply language, e.g. "Chinese"
+    pub language: ReplyLanguage,
+    /// `language` was configured rather than defaulted to English. Chat
+    /// only names a language the user chose, so it otherwise follows the
+    /// language they write in.
+    pub language_set: bool,
+}
+
+impl Persona {
+    pub fn from_config(config: &AppConfig) -> Self {
+        let set = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            system_prompt: set(&config.system_prompt),
+            personality: set(&config.personality),
+            language: ReplyLanguage::from_config(config.language.as_deref()),
+            language_set: set(&config.language).is_some(),
+        }
+    }
+
+    /// Lead the chat with the system prompt, personality and configured
+    /// reply language. A system message the caller sent is kept, with these
+    /// appended to it.
+    pub fn apply_to_chat(&self, mut messages: Vec<ChatMessage>) -> Vec<ChatMessage> {
+        let mut instructions: Vec<String> = self.system_prompt.iter().cloned().collect();
+        if let Some(personality) = &self.personality {
+            instructions.push(format!("Personality: {}.", personality));
+        }
+        if self.language_set {
+            instructions.push(self.language.instruction());
+        }
+        if instructions.is_empty() {
+            return messages;
+        }
+        let instructions = instructions.join("\n\n");
+
+        match messages.first_mut().filter(|m| m.role == "system") {
+            Some(system) => system.content = format!("{}\n\n{}", system.content, instructions),
+            None => messages.insert(
+                0,
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: instructions,
+                },
+            ),
+        }
+        messages
+    }
+
+    /// `prompt` followed by the personality instruction
+    pub fn style_prompt(&self, prompt: String) -> String {
+        match &self.personality {
+            Some(personality) => format!("{}\n\nPersonality: {}.", prompt, personality),
+            None => prompt,
+        }
+    }
+
+    /// [`Self::style_prompt`] applied to the last message of a built prompt
+    pub fn style_messages(&self, mut messages: Vec<ChatMessage>) -> Vec<ChatMessage> {
+        if let Some(last) = messages.last_mut() {
+            last.content = self.style_prompt(std::mem::take(&mut last.content));
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_prompt_and_style_come_from_config() {
+        let persona = Persona::from_config(&AppConfig {
+            system_prompt: Some("You are Kiki.".to_string()),
+            personality: Some("  ".to_string()),
+            language: Some("Chinese".to_string()),
+            ..AppConfig::default()
+        });
+        assert!(persona.personality.is_none());
+        assert_eq!(persona.language, ReplyLanguage::Chinese);
+
+        let user = ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        };
+        let messages = persona.apply_to_chat(vec![user.clone()]);
+        assert_eq!(messages[0].role, "system");
+        assert_eq!(messages[0].content, "You are Kiki.\n\n请用简体中文回答。");
+        assert_eq!(messages[1].content, "hi");
+
+        let witty = Persona {
+            personality: Some("witty".to_string()),
+            ..Persona::default()
+        };
+        assert_eq!(witty.style_prompt("Summarize.".to_string()), "Summarize.\n\nPersonality: witty.");
+        assert_eq!(Persona::default().style_prompt("x".to_string()), "x");
+    }
+
+    #[test]
+    fn personality_leads_the_chat() {
+        let witty = Persona {
+            personality: Some("witty".to_string()),
+            ..Persona::default()
+        };
+        let user = ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        };
+        let messages = witty.apply_to_chat(vec![user]);
+        assert_eq!(messages[0].role, "system");
+        assert_eq!(messages[0].content, "Personality: witty.");
+    }
+
+    #[test]
+    fn reply_language_reaches_the_chat() {
+        let user = ChatMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        };
+        // Unset (or blank): no instruction, so replies follow the user
+        for language in [None, Some("  ".to_string())] {
+            let persona = Persona::from_config(&AppConfig {
+                language,
+                ..AppConfig::default()
+            });
+            assert_eq!(persona.apply_to_chat(vec![user.clone()]).len(), 1);
+        }
+
+        let english = Persona::from_config(&AppConfig {
+            language: Some("en".to_string()),
+            ..AppConfig::default()
+        });
+        let messages = english.apply_to_chat(vec![user.clone()]);
+        assert_eq!(messages[0].content, "Respond in English.");
+
+        let japanese = Persona::from_config(&AppConfig {
+            language: Some("Japanese".to_string()),
+            ..AppConfig::default()
+        });
+        let messages = japanese.apply_to_chat(vec![user]);
+        assert_eq!(messages[0].content, "Respond in Japanese.");
+    }
+
+    #[test]
+    fn persona_is_merged_into_a_callers_system_message() {
+        let persona = Persona {
+            system_prompt: Some("You are Kiki.".to_string()),
+            personality: Some("witty".to_string()),
+            ..Persona::default()
+        };
+        let messages = persona.apply_to_chat(vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: "Answer from the screen.".to_string(),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: "hi".to_string(),
+            },
+        ]);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0].content,
+            "Answer from the screen.\n\nYou are Kiki.\n\nPersonality: witty."
+        );
+    }
+}
@@ -2,8 +2,12 @@
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
 
-use super::types::{ChatMessage, ChatResponse, FunctionDeclaration, ToolMessage, ToolTurn};
+use super::types::{
+    Capabilities, ChatMessage, ChatResponse, FunctionDeclaration, ToolMessage, ToolTurn,
+};
 
 /// Trait for AI chat providers
 #[async_trait]
@@ -18,6 +22,29 @@ pub trait AiProvider: Send + Sync + std::fmt::Debug {
         image_base64: &str,
     ) -> Result<ChatResponse>;
 
+    /// Chat with base64 PNGs attached to several turns: each `(index, image)`
+    /// pair attaches to `messages[index]`, so earlier screenshots stay in
+    /// context for follow-up questions. Images on non-user messages are
+    /// ignored. The default keeps only the newest image, via
+    /// [`Self::chat_with_vision`].
+    async fn chat_with_images(
+        &self,
+        messages: Vec<ChatMessage>,
+        images: Vec<(usize, String)>,
+    ) -> Result<ChatResponse> {
+        match images.into_iter().max_by_key(|(index, _)| *index) {
+            Some((_, image)) => self.chat_with_vision(messages, &image).await,
+            None => self.chat(messages).await,
+        }
+    }
+
+    /// Chat with the reply constrained to a single JSON object. Providers
+    /// without a JSON mode fall back to plain `chat`; use [`chat_json`] to
+    /// parse the result either way.
+    async fn chat_structured(&self, messages: Vec<ChatMessage>) -> Result<ChatResponse> {
+        self.chat(messages).await
+    }
+
     /// Tool-using chat. Returns the next single turn from the model. The
     /// caller is responsible for executing any returned tool calls and
     /// feeding results back via the next invocation.
@@ -40,6 +67,21 @@ pub trait AiProvider: Send + Sync + std::fmt::Debug {
         false
     }
 
+    /// Embed each text as a vector, in input order. Default implementation
+    /// returns an unsupported error for providers without an embeddings API.
+    async fn embed(&self, _texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        Err(anyhow!(
+            "Provider '{}' does not support embeddings",
+            self.provider_name()
+        ))
+    }
+
+    /// Features of the current model. The default claims nothing beyond
+    /// plain chat, which is right for text-only local models.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
     /// Validate the API key / connectivity
     async fn validate(&self) -> Result<()>;
 
@@ -48,4 +90,52 @@ pub trait AiProvider: Send + Sync + std::fmt::Debug {
 
     /// Get the current model name
     fn model_name(&self) -> &str;
+
+    /// A copy of this client that talks to `model`, with the same key,
+    /// endpoint and transport. `None` when switching needs a full re-init,
+    /// e.g. a local model that has to be loaded.
+    fn with_model(&self, _model: &str) -> Option<Arc<dyn AiProvider>> {
+        None
+    }
+}
+
+/// Request structured output and deserialize it into `T`.
+///
+/// JSON mode makes the reply parse directly; for providers without it the
+/// object is pulled out of the text with [`super::extract_json`].
+pub async fn chat_json<T: DeserializeOwned>(
+    provider: &dyn AiProvider,
+    messages: Vec<ChatMessage>,
+) -> Result<T> {
+    let response = provider.chat_structured(messages).await?;
+    parse_json(&response.text)
+}
+
+fn parse_json<T: DeserializeOwned>(text: &str) -> Result<T> {
+    let text = text.trim();
+    let json = super::extract_json(text).unwrap_or(text);
+    serde_json::from_str(json)
+        .map_err(|e| anyhow!("Failed to parse AI response: {}. Raw: {}", e, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Proposal {
+        title: String,
+    }
+
+    #[test]
+    fn parses_plain_and_fenced_json() {
+        let plain: Proposal = parse_json(r#"{"title": "Walk"}"#).unwrap();
+        assert_eq!(plain.title, "Walk");
+
+        let fenced: Proposal =
+            parse_json("Sure!\n```json\n{\"title\": \"Read\"}\n```").unwrap();
+        assert_eq!(fenced.title, "Read");
+
+        assert!(parse_json::<Proposal>("no json here").is_err());
+    }
 }
@@ -0,0 +1,132 @@
+//! Global AI request rate limiting
+//!
+//! One token bucket per provider, sized from
+//! `AppConfig::ai_requests_per_minute`. Every AI call site takes a token
+//! before sending so observe-driven background work can't burn through a
+//! provider's per-minute quota and starve interactive chat with 429s.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::error::HawkeyeError;
+use crate::state::AppState;
+
+/// Longest an interactive request waits for a token before giving up
+pub const INTERACTIVE_MAX_WAIT: Duration = Duration::from_secs(10);
+
+/// Who is asking — decides whether an empty bucket means wait or skip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestPriority {
+    /// User-initiated (chat, agent turns); may wait up to [`INTERACTIVE_MAX_WAIT`]
+    Interactive,
+    /// Automatic work (summaries, intent enhancement); skipped when limited
+    Background,
+}
+
+struct Bucket {
+    tokens: f64,
+    rpm: u32,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn new(rpm: u32) -> Self {
+        Self {
+            tokens: rpm as f64,
+            rpm,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let per_sec = self.rpm as f64 / 60.0;
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_sec).min(self.rpm as f64);
+        self.refilled_at = now;
+    }
+}
+
+/// Per-provider token buckets
+#[derive(Default)]
+pub struct RateLimiter {
+    buckets: HashMap<String, Bucket>,
+}
+
+impl RateLimiter {
+    /// Take a token for `provider` limited to `rpm` requests per minute. On
+    /// failure returns how long until the next token is available.
+    pub fn try_acquire(&mut self, provider: &str, rpm: u32) -> Result<(), Duration> {
+        let bucket = self
+            .buckets
+            .entry(provider.to_string())
+            .or_insert_with(|| Bucket::new(rpm));
+
+        // A changed limit takes effect immediately
+        if bucket.rpm != rpm {
+            bucket.rpm = rpm;
+            bucket.tokens = bucket.tokens.min(rpm as f64);
+        }
+
+        bucket.refill();
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let missing = 1.0 - bucket.tokens;
+            Err(Duration::from_secs_f64(missing * 60.0 / rpm as f64))
+        }
+    }
+}
+
+/// Reserve one request to `provider`. Returns `false` when the request
+/// should not be sent: immediately for background callers, or after
+/// [`INTERACTIVE_MAX_WAIT`] for interactive ones. Providers without a
+/// configured limit always pass.
+pub async fn acquire(state: &AppState, provider: &str, priority: RequestPriority) -> bool {
+    let rpm = match state.config.read().await.ai_requests_per_minute.get(provider) {
+        Some(&rpm) if rpm > 0 => rpm,
+        _ => return true,
+    };
+
+    let deadline = Instant::now() + INTERACTIVE_MAX_WAIT;
+    loop {
+        let wait = match state.ai_rate_limiter.write().await.try_acquire(provider, rpm) {
+            Ok(()) => return true,
+            Err(wait) => wait,
+        };
+
+        if priority == RequestPriority::Background || Instant::now() + wait > deadline {
+            log::warn!("[AI] Rate limit reached for {} ({} req/min)", provider, rpm);
+            return false;
+        }
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Error for callers that surface a limited request to the user
+pub fn limited_error(provider: &str) -> HawkeyeError {
+    HawkeyeError::RateLimited(format!(
+        "Rate limit reached for {}; try again in a moment or raise the requests-per-minute limit in settings",
+        provider
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_up_to_limit_then_reports_wait() {
+        let mut limiter = RateLimiter::default();
+        for _ in 0..3 {
+            assert!(limiter.try_acquire("gemini", 3).is_ok());
+        }
+
+        let wait = limiter.try_acquire("gemini", 3).unwrap_err();
+        assert!(wait > Duration::from_secs(19) && wait <= Duration::from_secs(20));
+
+        // Buckets are per provider
+        assert!(limiter.try_acquire("openai", 3).is_ok());
+    }
+}
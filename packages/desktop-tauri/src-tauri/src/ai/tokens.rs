@@ -0,0 +1,106 @@
+//! Pre-send prompt token estimation
+//!
+//! Exact counts only come back in `UsageInfo` after a call. This gives the UI
+//! a close-enough figure up front: tiktoken for OpenAI-family models, a
+//! chars/4 heuristic for everything else.
+
+use serde::Serialize;
+use std::sync::OnceLock;
+use tiktoken_rs::CoreBPE;
+
+use super::types::ChatMessage;
+
+/// Per-message framing overhead in the OpenAI chat format
+const TOKENS_PER_MESSAGE: usize = 4;
+
+/// Token estimate for a prompt
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenEstimate {
+    pub tokens: usize,
+    /// Known context window of the model, if recognized
+    pub context_limit: Option<usize>,
+    /// "tiktoken" or "heuristic"
+    pub method: String,
+}
+
+fn o200k() -> Option<&'static CoreBPE> {
+    static BPE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+    BPE.get_or_init(|| tiktoken_rs::o200k_base().ok()).as_ref()
+}
+
+/// Whether a model is tokenized with OpenAI's BPE vocabularies
+fn is_openai_family(model: &str) -> bool {
+    let model = model.rsplit('/').next().unwrap_or(model);
+    model.starts_with("gpt-") || model.starts_with("o1") || model.starts_with("o3") || model.starts_with("o4")
+}
+
+/// Known context window for a model name
+pub fn context_limit(model: &str) -> Option<usize> {
+    let model = model.rsplit('/').next().unwrap_or(model).to_lowercase();
+    let limit = if model.starts_with("gemini") {
+        1_048_576
+    } else if model.starts_with("gpt-4.1") {
+        1_047_576
+    } else if model.starts_with("gpt-4o") || model.starts_with("gpt-4-turbo") {
+        128_000
+    } else if model.starts_with("o1") || model.starts_with("o3") || model.starts_with("o4") {
+        200_000
+    } else if model.starts_with("gpt-5") {
+        400_000
+    } else if model.starts_with("claude") {
+        200_000
+    } else if model.starts_with("gpt-3.5") {
+        16_385
+    } else {
+        return None;
+    };
+    Some(limit)
+}
+
+/// Estimate prompt tokens for `messages` sent to `model`
+pub fn estimate(messages: &[ChatMessage], model: &str) -> TokenEstimate {
+    let bpe = if is_openai_family(model) { o200k() } else { None };
+
+    let tokens = messages
+        .iter()
+        .map(|m| {
+            let content = match bpe {
+                Some(bpe) => bpe.encode_ordinary(&m.content).len(),
+                None => (m.content.chars().count() + 3) / 4,
+            };
+            content + TOKENS_PER_MESSAGE
+        })
+        .sum();
+
+    TokenEstimate {
+        tokens,
+        context_limit: context_limit(model),
+        method: if bpe.is_some() { "tiktoken" } else { "heuristic" }.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(content: &str) -> ChatMessage {
+        ChatMessage { role: "user".into(), content: content.into() }
+    }
+
+    #[test]
+    fn heuristic_counts_chars_over_four() {
+        let est = estimate(&[msg("abcdefgh"), msg("abc")], "gemini-2.5-flash");
+        assert_eq!(est.method, "heuristic");
+        assert_eq!(est.tokens, 2 + 1 + 2 * TOKENS_PER_MESSAGE);
+        assert_eq!(est.context_limit, Some(1_048_576));
+    }
+
+    #[test]
+    fn openai_models_use_tiktoken() {
+        let est = estimate(&[msg("hello world")], "openai/gpt-4o-mini");
+        assert_eq!(est.method, "tiktoken");
+        assert_eq!(est.tokens, 2 + TOKENS_PER_MESSAGE);
+        assert_eq!(est.context_limit, Some(128_000));
+    }
+}
@@ -31,6 +31,21 @@ pub struct UsageInfo {
     pub total_tokens: u32,
 }
 
+/// What the active provider and model can do, so the UI can hide features
+/// (screenshot chat, semantic search, ...) that would only fail
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    /// Accepts images in chat
+    pub vision: bool,
+    /// Has an embeddings API
+    pub embeddings: bool,
+    /// Can stream replies incrementally
+    pub streaming: bool,
+    /// Can constrain replies to a JSON object
+    pub json_mode: bool,
+}
+
 // --- Tool / function-calling types ---
 
 /// A tool the model may call. Shape mirrors Gemini's `FunctionDeclaration`
@@ -168,6 +183,9 @@ pub(crate) struct GeminiGenerationConfig {
     pub max_output_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
+    /// `application/json` constrains the reply to JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_mime_type: Option<String>,
 }
 
 // --- Gemini API response types ---
@@ -177,11 +195,23 @@ pub(crate) struct GeminiResponse {
     pub candidates: Option<Vec<GeminiCandidate>>,
     #[serde(rename = "usageMetadata")]
     pub usage_metadata: Option<GeminiUsageMetadata>,
+    /// Present when the prompt itself was blocked
+    #[serde(rename = "promptFeedback")]
+    pub prompt_feedback: Option<GeminiPromptFeedback>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GeminiPromptFeedback {
+    pub block_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub(crate) struct GeminiCandidate {
     pub content: Option<GeminiResponseContent>,
+    /// `STOP`, `MAX_TOKENS`, `SAFETY`, ...
+    pub finish_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -203,6 +233,31 @@ pub(crate) struct GeminiResponseFunctionCall {
     pub args: Value,
 }
 
+// --- Gemini embedding types ---
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GeminiBatchEmbedRequest {
+    pub requests: Vec<GeminiEmbedRequest>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GeminiEmbedRequest {
+    /// Fully-qualified model, e.g. "models/gemini-embedding-001"
+    pub model: String,
+    pub content: GeminiContent,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GeminiBatchEmbedResponse {
+    #[serde(default)]
+    pub embeddings: Vec<GeminiEmbedding>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GeminiEmbedding {
+    pub values: Vec<f32>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct GeminiUsageMetadata {
@@ -11,10 +11,12 @@ use clap::{Parser, Subcommand};
 
 use hawkeye_lib::{
     agent::{run_user_turn, CuaDriverClient, DaemonSupervisor},
-    ai::{AiProvider, ChatMessage, GeminiClient, OpenAiClient},
+    ai::{AiProvider, ChatMessage, GeminiClient, HttpOptions, OpenAiClient},
     config,
     event_sink::{EventSink, SharedSink, StdoutSink},
+    life_tree,
     observe::ObserveLoop,
+    perception,
     state::AppState,
 };
 
@@ -77,10 +79,17 @@ async fn main() -> anyhow::Result<()> {
             change_threshold,
         } => {
             let sink: SharedSink = Arc::new(StdoutSink);
+            if let Err(e) = perception::init(&state).await {
+                eprintln!("[hawkeye-cli] perception init failed: {}", e);
+            }
+            tokio::spawn(life_tree::autosave::run(state.clone()));
             let _loop = ObserveLoop::start(sink, state.clone(), interval_ms, change_threshold);
             eprintln!("[hawkeye-cli] observe loop running — Ctrl-C to stop");
             tokio::signal::ctrl_c().await?;
             eprintln!("[hawkeye-cli] shutting down");
+            if let Err(e) = state.life_tree.write().await.flush() {
+                eprintln!("[hawkeye-cli] failed to save life tree: {}", e);
+            }
         }
 
         Cmd::Chat { text } => {
@@ -132,6 +141,7 @@ async fn main() -> anyhow::Result<()> {
 async fn build_provider(state: &Arc<AppState>) -> anyhow::Result<Arc<dyn AiProvider>> {
     let cfg = state.config.read().await;
     let provider = cfg.ai_provider.clone();
+    let http = HttpOptions::from_config(&cfg);
 
     match provider.as_str() {
         "openai" => {
@@ -143,7 +153,8 @@ async fn build_provider(state: &Arc<AppState>) -> anyhow::Result<Arc<dyn AiProvi
                 key,
                 cfg.openai_model.clone(),
                 cfg.openai_base_url.clone(),
-            )))
+                &http,
+            )?))
         }
 
         "local" | "llama-cpp" => {
@@ -160,7 +171,8 @@ async fn build_provider(state: &Arc<AppState>) -> anyhow::Result<Arc<dyn AiProvi
                 key,
                 cfg.gemini_model.clone(),
                 cfg.gemini_base_url.clone(),
-            )))
+                &http,
+            )?))
         }
     }
 }
@@ -0,0 +1,76 @@
+//! Time source for time-dependent state
+//!
+//! `LifeTree`, `AdaptiveRefresh` and `IntentRecognizer` read the time through
+//! a [`Clock`] instead of the global clock, so tests can substitute a
+//! [`MockClock`] and step time deterministically.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of wall-clock and monotonic time
+pub trait Clock: Send + Sync + Debug {
+    /// Wall-clock time in milliseconds since the Unix epoch
+    fn now_ms(&self) -> u64;
+    /// Monotonic time, for measuring elapsed durations
+    fn instant(&self) -> Instant;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real system clock
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Shared handle to the system clock, the default for all clock users
+pub fn system() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Manually advanced clock for tests. Both readings move together.
+#[cfg(test)]
+#[derive(Debug)]
+pub struct MockClock {
+    start_ms: u64,
+    start_instant: Instant,
+    elapsed: std::sync::Mutex<std::time::Duration>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub fn new(start_ms: u64) -> Arc<Self> {
+        Arc::new(Self {
+            start_ms,
+            start_instant: Instant::now(),
+            elapsed: std::sync::Mutex::new(std::time::Duration::ZERO),
+        })
+    }
+
+    pub fn advance(&self, by: std::time::Duration) {
+        *self.elapsed.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now_ms(&self) -> u64 {
+        self.start_ms + self.elapsed.lock().unwrap().as_millis() as u64
+    }
+
+    fn instant(&self) -> Instant {
+        self.start_instant + *self.elapsed.lock().unwrap()
+    }
+}
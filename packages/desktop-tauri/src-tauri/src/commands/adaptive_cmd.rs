@@ -3,7 +3,8 @@
 use std::sync::Arc;
 use tauri::{command, State};
 
-use crate::observe::adaptive_refresh::{ActivityEventType, AdaptiveRefreshStatus};
+use crate::error::HawkeyeError;
+use crate::observe::adaptive_refresh::{ActivityEventType, ActivityLevel, AdaptiveRefreshStatus};
 use crate::state::AppState;
 
 /// Record an activity event to influence the adaptive refresh rate
@@ -11,7 +12,7 @@ use crate::state::AppState;
 pub async fn record_activity(
     event_type: ActivityEventType,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
+) -> Result<(), HawkeyeError> {
     let mut ar = state.adaptive_refresh.write().await;
     ar.record_activity(event_type);
     Ok(())
@@ -21,7 +22,31 @@ pub async fn record_activity(
 #[command]
 pub async fn get_refresh_status(
     state: State<'_, Arc<AppState>>,
-) -> Result<AdaptiveRefreshStatus, String> {
+) -> Result<AdaptiveRefreshStatus, HawkeyeError> {
     let mut ar = state.adaptive_refresh.write().await;
     Ok(ar.status())
 }
+
+/// Pin the observe interval to `level`'s band (e.g. `very_high` for a
+/// "turbo" button) until `clear_forced_level`. Activity is still scored
+/// underneath.
+#[command]
+pub async fn set_activity_level(
+    level: ActivityLevel,
+    state: State<'_, Arc<AppState>>,
+) -> Result<AdaptiveRefreshStatus, HawkeyeError> {
+    let mut ar = state.adaptive_refresh.write().await;
+    ar.force_level(level);
+    log::info!("[Adaptive] Level forced to {:?}", level);
+    Ok(ar.status())
+}
+
+/// Return to score-driven intervals
+#[command]
+pub async fn clear_forced_level(
+    state: State<'_, Arc<AppState>>,
+) -> Result<AdaptiveRefreshStatus, HawkeyeError> {
+    let mut ar = state.adaptive_refresh.write().await;
+    ar.clear_forced_level();
+    Ok(ar.status())
+}
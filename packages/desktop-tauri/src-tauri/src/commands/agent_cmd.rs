@@ -3,9 +3,12 @@
 use std::sync::Arc;
 use tauri::{command, AppHandle, Emitter, State};
 
+use crate::agent::plan::{self, PlanResult, PlanStep};
 use crate::agent::{run_user_turn, AgentTurnResult, CuaDriverClient};
 use crate::ai::types::{FunctionResult, ToolMessage};
+use crate::ai::rate_limit::{self, RequestPriority};
 use crate::ai::ChatMessage;
+use crate::error::HawkeyeError;
 use crate::event_sink::{SharedSink, TauriSink};
 use crate::events;
 use crate::state::AppState;
@@ -22,7 +25,7 @@ pub struct AgentStatus {
 
 /// Returns the current cua-driver status.
 #[command]
-pub async fn get_agent_status(state: State<'_, Arc<AppState>>) -> Result<AgentStatus, String> {
+pub async fn get_agent_status(state: State<'_, Arc<AppState>>) -> Result<AgentStatus, HawkeyeError> {
     let supervisor = state.agent_supervisor.read().await;
     let supervisor = supervisor
         .as_ref()
@@ -42,7 +45,7 @@ pub async fn get_agent_status(state: State<'_, Arc<AppState>>) -> Result<AgentSt
 pub async fn start_agent(
     state: State<'_, Arc<AppState>>,
     app: AppHandle,
-) -> Result<bool, String> {
+) -> Result<bool, HawkeyeError> {
     let supervisor = state.agent_supervisor.read().await;
     let supervisor = supervisor
         .as_ref()
@@ -56,8 +59,8 @@ pub async fn start_agent(
         Err(e) => {
             let msg = e.to_string();
             log::error!("[agent] start_agent failed: {}", msg);
-            let _ = app.emit(events::AGENT_DAEMON_ERROR, msg.clone());
-            Err(msg)
+            let _ = app.emit(events::AGENT_DAEMON_ERROR, msg);
+            Err(e.into())
         }
     }
 }
@@ -70,14 +73,14 @@ pub async fn chat_with_agent(
     user_input: String,
     state: State<'_, Arc<AppState>>,
     app: AppHandle,
-) -> Result<AgentTurnResult, String> {
+) -> Result<AgentTurnResult, HawkeyeError> {
     // Resolve provider.
-    let provider = {
-        let ai = state.ai_client.read().await;
-        ai.as_ref()
-            .cloned()
-            .ok_or_else(|| "AI not initialized. Call init_ai first.".to_string())?
-    };
+    let provider = state.ai_provider().await?;
+
+    // One token per user turn; tool rounds within the turn aren't metered
+    if !rate_limit::acquire(&state, provider.provider_name(), RequestPriority::Interactive).await {
+        return Err(rate_limit::limited_error(provider.provider_name()));
+    }
 
     // Resolve cua-driver client (may be unavailable; that's a soft error).
     let driver_client: Option<CuaDriverClient> = {
@@ -107,7 +110,28 @@ pub async fn chat_with_agent(
 
     run_user_turn(sink, provider, driver_client, tool_history, user_input)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(HawkeyeError::from)
+}
+
+/// Run a fixed sequence of screenshot / OCR / chat steps. Progress is
+/// emitted as `plan:progress` and the run is traced on the debug timeline;
+/// a failing step stops the plan and is reported in the result.
+#[command]
+pub async fn execute_plan(
+    steps: Vec<PlanStep>,
+    state: State<'_, Arc<AppState>>,
+    app: AppHandle,
+) -> Result<PlanResult, HawkeyeError> {
+    plan::validate(&steps)?;
+
+    let sink: SharedSink = state
+        .event_sink
+        .read()
+        .await
+        .clone()
+        .unwrap_or_else(|| -> SharedSink { Arc::new(TauriSink::new(app)) });
+
+    Ok(plan::execute(&state, &sink, &steps).await)
 }
 
 // --- Direct passthrough for debugging -------------------------------------
@@ -119,14 +143,14 @@ pub async fn invoke_cua_tool(
     name: String,
     args: serde_json::Value,
     state: State<'_, Arc<AppState>>,
-) -> Result<FunctionResult, String> {
+) -> Result<FunctionResult, HawkeyeError> {
     let sup = state.agent_supervisor.read().await;
     let sup = sup
         .as_ref()
         .ok_or_else(|| "agent supervisor not initialized".to_string())?;
 
     if !crate::agent::tools::is_allowed(&name) {
-        return Err(format!("tool '{}' not in allow-list", name));
+        return Err(format!("tool '{}' not in allow-list", name).into());
     }
 
     let args_map = args
@@ -134,11 +158,7 @@ pub async fn invoke_cua_tool(
         .map(|m| m.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
         .unwrap_or_default();
 
-    let result = sup
-        .client()
-        .call(&name, args_map)
-        .await
-        .map_err(|e| e.to_string())?;
+    let result = sup.client().call(&name, args_map).await?;
 
     Ok(FunctionResult {
         name: name.clone(),
@@ -1,9 +1,22 @@
 //! Chat commands — AI chat via pluggable providers (Gemini, OpenAI, etc.)
 
+use serde::Serialize;
 use std::sync::Arc;
 use tauri::{command, AppHandle, Emitter, State};
 
-use crate::ai::{ChatMessage, ChatResponse, GeminiClient, LocalProvider, OpenAiClient};
+use crate::ai::conversations::Conversation;
+use crate::ai::diagnostics::ProviderStats;
+use crate::ai::history::{self, TrimStrategy};
+use crate::ai::rate_limit::{self, RequestPriority};
+use crate::ai::tokens::{self, TokenEstimate};
+use crate::ai::{
+    AiProvider, Capabilities, ChatMessage, ChatResponse, GeminiClient, HttpOptions, LocalProvider,
+    OpenAiClient, Persona,
+};
+use crate::clock::{Clock, SystemClock};
+use crate::commands::debug_cmd::{ai_tracer, record_debug, DebugEventType};
+use crate::error::HawkeyeError;
+use crate::event_sink::{NoopSink, SharedSink};
 use crate::events;
 use crate::state::AppState;
 
@@ -12,7 +25,7 @@ use crate::state::AppState;
 pub async fn init_ai(
     state: State<'_, Arc<AppState>>,
     app: AppHandle,
-) -> Result<bool, String> {
+) -> Result<bool, HawkeyeError> {
     let config = state.config.read().await;
 
     let provider_type = config.ai_provider.as_str();
@@ -30,7 +43,10 @@ pub async fn init_ai(
 
             let mgr = state.model_manager.read().await;
             let model_path = mgr.model_path(&model_id).ok_or_else(|| {
-                format!("Local model '{}' not downloaded. Download it first.", model_id)
+                HawkeyeError::NotFound(format!(
+                    "Local model '{}' not downloaded. Download it first.",
+                    model_id
+                ))
             })?;
 
             let provider = LocalProvider::load(model_path, Some(model_id))
@@ -46,11 +62,14 @@ pub async fn init_ai(
                     return Ok(false);
                 }
             };
-            Arc::new(OpenAiClient::new(
+            let client = OpenAiClient::new(
                 api_key,
                 config.openai_model.clone(),
                 config.openai_base_url.clone(),
-            ))
+                &HttpOptions::from_config(&config).with_tracer(ai_tracer(&state)),
+            )?;
+            drop(config); // `ai_client` ranks above `config` in the lock order
+            Arc::new(client)
         }
         _ => {
             // Default: Gemini
@@ -61,11 +80,14 @@ pub async fn init_ai(
                     return Ok(false);
                 }
             };
-            Arc::new(GeminiClient::new(
+            let client = GeminiClient::new(
                 api_key,
                 config.gemini_model.clone(),
                 config.gemini_base_url.clone(),
-            ))
+                &HttpOptions::from_config(&config).with_tracer(ai_tracer(&state)),
+            )?;
+            drop(config);
+            Arc::new(client)
         }
     };
 
@@ -79,7 +101,9 @@ pub async fn init_ai(
             );
             let mut ai = state.ai_client.write().await;
             *ai = Some(client);
+            drop(ai);
             let _ = app.emit(events::AI_INITIALIZED, true);
+            run_deferred_summary(&state, &app).await;
             Ok(true)
         }
         Err(e) => {
@@ -90,21 +114,271 @@ pub async fn init_ai(
     }
 }
 
-/// Chat with AI (no tools).
+/// Switch the configured provider to `model` and persist it. When the
+/// active client is already that provider, it is swapped for a copy using
+/// the new model without another validation round-trip; otherwise (no
+/// client yet, or the provider setting changed) this runs [`init_ai`].
+/// Returns whether a client is ready on the new model. As with
+/// `set_active_profile`, the model is saved before that fallback validates
+/// it, so `false` means the model is saved but no client is running on it.
+#[command]
+pub async fn set_active_model(
+    model: String,
+    state: State<'_, Arc<AppState>>,
+    app: AppHandle,
+) -> Result<bool, HawkeyeError> {
+    let model = model.trim();
+    if model.is_empty() {
+        return Err("Model name is empty".into());
+    }
+
+    let mut ai = state.ai_client.write().await;
+    let provider = {
+        let mut current = state.config.write().await;
+        let mut updated = current.clone();
+        updated.set_model(model)?;
+        crate::config::save_config(&updated)?;
+        *current = updated;
+        current.ai_provider.clone()
+    };
+
+    let switched = ai
+        .as_ref()
+        .filter(|client| client.provider_name() == provider)
+        .and_then(|client| client.with_model(model));
+    match switched {
+        Some(client) => {
+            log::info!("[AI] {} model switched to {}", provider, model);
+            *ai = Some(client);
+            drop(ai);
+            let _ = app.emit(events::AI_INITIALIZED, true);
+            Ok(true)
+        }
+        None => {
+            drop(ai); // `init_ai` takes the config and client locks itself
+            init_ai(state, app).await
+        }
+    }
+}
+
+/// Generate a summary that `generate_summary` deferred while no provider was
+/// configured, delivering it as `activity:summary-generated`
+async fn run_deferred_summary(state: &Arc<AppState>, app: &AppHandle) {
+    if !std::mem::take(&mut *state.deferred_summary.write().await) {
+        return;
+    }
+    let state = state.clone();
+    let app = app.clone();
+    tokio::spawn(async move {
+        match crate::commands::summarizer_cmd::summarize_pending(&state).await {
+            Ok(summaries) => {
+                let _ = app.emit(events::SUMMARY_GENERATED, summaries);
+            }
+            Err(e) => log::warn!("[Summarizer] Deferred summary failed: {}", e),
+        }
+    });
+}
+
+/// Estimate prompt tokens for `messages` against the active model, so the
+/// UI can trim history before hitting the context limit.
+#[command]
+pub async fn estimate_tokens(
+    state: State<'_, Arc<AppState>>,
+    messages: Vec<ChatMessage>,
+) -> Result<TokenEstimate, HawkeyeError> {
+    let active_model = state
+        .ai_client
+        .read()
+        .await
+        .as_ref()
+        .map(|c| c.model_name().to_string());
+
+    let model = match active_model {
+        Some(model) => model,
+        None => {
+            let config = state.config.read().await;
+            config.model().map(str::to_string).unwrap_or_default()
+        }
+    };
+
+    Ok(tokens::estimate(&messages, &model))
+}
+
+/// Embed texts with the active provider (one vector per input)
+#[command]
+pub async fn embed_texts(
+    texts: Vec<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<Vec<f32>>, HawkeyeError> {
+    let provider = state.ai_provider().await?;
+
+    if !rate_limit::acquire(&state, provider.provider_name(), RequestPriority::Interactive).await {
+        return Err(rate_limit::limited_error(provider.provider_name()));
+    }
+
+    provider.embed(texts).await.map_err(HawkeyeError::from)
+}
+
+/// `chat` reply, tagged with the conversation it was stored in (absent when
+/// no id was given or storing failed)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationChatResponse {
+    #[serde(flatten)]
+    pub response: ChatResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<String>,
+}
+
+/// Chat with AI (no tools). The configured system prompt, personality and
+/// reply language lead the chat, appended to `messages`' own system message
+/// if it starts with one. With `conversation_id`, the newest user
+/// message and the reply are appended to that persisted conversation.
 #[command]
 pub async fn chat(
     messages: Vec<ChatMessage>,
+    conversation_id: Option<String>,
     state: State<'_, Arc<AppState>>,
-) -> Result<ChatResponse, String> {
-    let provider = {
-        let ai = state.ai_client.read().await;
-        ai.as_ref()
-            .cloned()
-            .ok_or_else(|| "AI not initialized. Call init_ai first.".to_string())?
+) -> Result<ConversationChatResponse, HawkeyeError> {
+    let provider = state.ai_provider().await?;
+
+    let latest_user = messages.last().filter(|m| m.role == "user").cloned();
+    let persona = Persona::from_config(&*state.config.read().await);
+    let messages = persona.apply_to_chat(messages);
+    let messages = fit_to_context(&state, provider.as_ref(), messages).await;
+
+    if !rate_limit::acquire(&state, provider.provider_name(), RequestPriority::Interactive).await {
+        return Err(rate_limit::limited_error(provider.provider_name()));
+    }
+
+    let started = std::time::Instant::now();
+    let result = provider.chat(messages).await.map_err(HawkeyeError::from);
+    state.ai_diagnostics.write().await.record(
+        provider.provider_name(),
+        started.elapsed().as_millis() as u64,
+        result.as_ref().map(|_| ()),
+        SystemClock.now_ms(),
+    );
+    let response = result?;
+
+    // A storage failure shouldn't cost the user a reply they already paid
+    // for; the missing id in the response tells the UI it wasn't stored
+    let mut stored_in = None;
+    if let Some(id) = conversation_id {
+        let now = SystemClock.now_ms();
+        let store = state.conversations.write().await;
+        match store.append_exchange(&id, latest_user.as_ref(), &response, now) {
+            Ok(_) => stored_in = Some(id),
+            Err(e) => log::warn!("[Chat] Failed to store exchange in {}: {}", id, e),
+        }
+    }
+
+    Ok(ConversationChatResponse {
+        response,
+        conversation_id: stored_in,
+    })
+}
+
+/// What the active provider and model support
+#[command]
+pub async fn get_provider_capabilities(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Capabilities, HawkeyeError> {
+    Ok(state.ai_provider().await?.capabilities())
+}
+
+/// Health of the active provider for the diagnostics view
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiDiagnosticsReport {
+    pub provider: String,
+    pub model: String,
+    pub healthy: bool,
+    #[serde(flatten)]
+    pub stats: ProviderStats,
+}
+
+/// Last latency, last error and request counts of the active provider, as
+/// seen by `chat`
+#[command]
+pub async fn get_ai_diagnostics(
+    state: State<'_, Arc<AppState>>,
+) -> Result<AiDiagnosticsReport, HawkeyeError> {
+    let provider = state.ai_provider().await?;
+    let stats = state.ai_diagnostics.read().await.stats(provider.provider_name());
+    Ok(AiDiagnosticsReport {
+        provider: provider.provider_name().to_string(),
+        model: provider.model_name().to_string(),
+        healthy: stats.healthy(),
+        stats,
+    })
+}
+
+/// A persisted conversation with its accumulated token usage
+#[command]
+pub async fn get_conversation(
+    conversation_id: String,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Option<Conversation>, HawkeyeError> {
+    Ok(state.conversations.read().await.load(&conversation_id)?)
+}
+
+/// Trim history that would overflow the model's context window, per the
+/// configured strategy, and note it on the debug timeline
+async fn fit_to_context(
+    state: &AppState,
+    provider: &dyn AiProvider,
+    mut messages: Vec<ChatMessage>,
+) -> Vec<ChatMessage> {
+    let model = provider.model_name().to_string();
+    let Some(budget) = history::prompt_budget(&model) else {
+        return messages;
     };
 
-    provider
-        .chat(messages)
+    let removed = history::drop_oldest(&mut messages, &model, budget);
+    if removed.is_empty() {
+        return messages;
+    }
+
+    let strategy = state.config.read().await.history_trim_strategy;
+    let mut removed_turns = removed.len();
+    let mut summarized = false;
+    // The summary costs an extra request; when over budget just drop
+    let can_summarize = strategy == TrimStrategy::SummarizeOldest
+        && rate_limit::acquire(state, provider.provider_name(), RequestPriority::Background).await;
+    if can_summarize {
+        match history::summarize_into(&mut messages, &removed, provider).await {
+            Ok(()) => {
+                summarized = true;
+                // The summary itself may tip the prompt back over budget
+                removed_turns += history::drop_oldest(&mut messages, &model, budget).len();
+            }
+            Err(e) => log::warn!("[Chat] History summarization failed, dropping instead: {}", e),
+        }
+    }
+
+    log::info!("[Chat] Trimmed {} turn(s) to fit {} tokens", removed_turns, budget);
+    let sink: SharedSink = state
+        .event_sink
+        .read()
         .await
-        .map_err(|e| e.to_string())
+        .clone()
+        .unwrap_or_else(|| Arc::new(NoopSink));
+    record_debug(
+        sink.as_ref(),
+        state,
+        DebugEventType::System,
+        "Chat history trimmed",
+        serde_json::json!({
+            "removedTurns": removed_turns,
+            "summarized": summarized,
+            "budgetTokens": budget,
+            "model": model,
+        }),
+        None,
+        None,
+    )
+    .await;
+
+    messages
 }
@@ -1,32 +1,122 @@
 //! Configuration commands
 
 use std::sync::Arc;
-use tauri::{command, State};
+use tauri::{command, AppHandle, State};
 
 use crate::config::AppConfig;
+use crate::error::HawkeyeError;
+use crate::perception::ocr::OcrFilter;
 use crate::state::AppState;
 
 /// Load application configuration
 #[command]
-pub async fn load_config(state: State<'_, Arc<AppState>>) -> Result<AppConfig, String> {
+pub async fn load_config(state: State<'_, Arc<AppState>>) -> Result<AppConfig, HawkeyeError> {
     let config = state.config.read().await;
     Ok(config.clone())
 }
 
-/// Save application configuration
+/// Save application configuration. `models_dir_override` and
+/// `observation_enabled` keep their current values; they only change through
+/// `set_models_dir`, which moves the models, and `set_capture_enabled`.
 #[command]
 pub async fn save_config(
-    config: AppConfig,
+    mut config: AppConfig,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
+) -> Result<(), HawkeyeError> {
+    // Refuse bad patterns here so the observe loop never sees one
+    OcrFilter::validate(&config.ocr_filter_patterns)?;
+
     // Hold write lock during both operations to prevent concurrent save races
     let mut current = state.config.write().await;
+    config.models_dir_override = current.models_dir_override.clone();
+    config.observation_enabled = current.observation_enabled;
 
     // Persist to disk first (if this fails, memory stays unchanged)
-    crate::config::save_config(&config).map_err(|e| e.to_string())?;
+    crate::config::save_config(&config)?;
 
     // Update in-memory state
+    state
+        .life_tree
+        .write()
+        .await
+        .set_phase_thresholds(config.experiment_phase_thresholds);
+    *state.ocr_filter.write().await = OcrFilter::from_config(&config);
     *current = config;
 
     Ok(())
 }
+
+/// Activate a saved provider profile: copy it into the current provider
+/// settings, persist, and re-initialize the AI client. Returns whether the
+/// new client validated.
+#[command]
+pub async fn set_active_profile(
+    name: String,
+    state: State<'_, Arc<AppState>>,
+    app: AppHandle,
+) -> Result<bool, HawkeyeError> {
+    {
+        let mut current = state.config.write().await;
+        let mut updated = current.clone();
+        updated.apply_profile(&name)?;
+
+        crate::config::save_config(&updated)?;
+        *current = updated;
+    }
+
+    log::info!("[Config] Switched to provider profile '{}'", name);
+    super::chat_cmd::init_ai(state, app).await
+}
+
+/// Set the system prompt prepended to every chat. `None` or blank clears it.
+#[command]
+pub async fn set_system_prompt(
+    system_prompt: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), HawkeyeError> {
+    let mut current = state.config.write().await;
+    let mut updated = current.clone();
+    updated.system_prompt = system_prompt.filter(|p| !p.trim().is_empty());
+
+    crate::config::save_config(&updated)?;
+    *current = updated;
+    Ok(())
+}
+
+/// Register or unregister Hawkeye to start (tray-only) at login, and
+/// remember the choice in `launch_at_login`
+#[command]
+pub async fn set_launch_at_login(
+    enabled: bool,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), HawkeyeError> {
+    crate::login_item::set_enabled(enabled)?;
+    sync_launch_at_login(&state, enabled).await?;
+    log::info!("[Config] Launch at login {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+/// Whether Hawkeye starts at login. Reads the OS entry, which the user may
+/// have changed outside Hawkeye, and syncs the config to it.
+#[command]
+pub async fn get_launch_at_login(
+    state: State<'_, Arc<AppState>>,
+) -> Result<bool, HawkeyeError> {
+    let enabled = crate::login_item::is_enabled()?;
+    sync_launch_at_login(&state, enabled).await?;
+    Ok(enabled)
+}
+
+/// Persist `launch_at_login` when it differs from the OS state
+pub async fn sync_launch_at_login(state: &AppState, enabled: bool) -> Result<(), HawkeyeError> {
+    let mut current = state.config.write().await;
+    if current.launch_at_login == enabled {
+        return Ok(());
+    }
+    let mut updated = current.clone();
+    updated.launch_at_login = enabled;
+
+    crate::config::save_config(&updated)?;
+    *current = updated;
+    Ok(())
+}
@@ -2,11 +2,15 @@
 
 use std::collections::VecDeque;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 use tauri::{command, State};
 
+use crate::ai::http::{AiTraceKind, AiTracer};
+use crate::clock::{Clock, SystemClock};
+use crate::error::HawkeyeError;
+use crate::event_sink::{EventSink, NoopSink};
+use crate::events;
 use crate::state::AppState;
 
 /// Debug event types matching the Electron implementation
@@ -99,10 +103,7 @@ impl DebugTimeline {
         }
 
         self.counter += 1;
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
+        let now = SystemClock.now_ms();
 
         let event = DebugEvent {
             id: format!("dbg-{}", self.counter),
@@ -139,6 +140,23 @@ impl DebugTimeline {
         }
     }
 
+    /// Direct children of `parent_id`, oldest first
+    pub fn get_children(&self, parent_id: &str) -> Vec<DebugEvent> {
+        self.events
+            .iter()
+            .filter(|e| e.parent_id.as_deref() == Some(parent_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Set the duration of an event pushed before its work finished (e.g. a
+    /// parent spanning a whole observe cycle). Returns the updated event.
+    pub fn set_duration(&mut self, id: &str, duration_ms: u64) -> Option<DebugEvent> {
+        let event = self.events.iter_mut().rev().find(|e| e.id == id)?;
+        event.duration_ms = Some(duration_ms);
+        Some(event.clone())
+    }
+
     /// Get events since a timestamp
     pub fn get_since(&self, since_ms: u64) -> Vec<DebugEvent> {
         self.events
@@ -171,6 +189,76 @@ impl DebugTimeline {
     }
 }
 
+/// Push an event onto the debug timeline and forward it to the host.
+/// Returns the new event's id (`None` while the timeline is paused).
+pub async fn record_debug(
+    sink: &dyn EventSink,
+    state: &AppState,
+    event_type: DebugEventType,
+    label: &str,
+    data: serde_json::Value,
+    duration_ms: Option<u64>,
+    parent_id: Option<&str>,
+) -> Option<String> {
+    let event = state.debug_timeline.write().await.push(
+        event_type,
+        label.to_string(),
+        data,
+        duration_ms,
+        parent_id.map(str::to_string),
+    )?;
+
+    if let Ok(payload) = serde_json::to_value(&event) {
+        sink.emit(events::DEBUG_EVENT, payload);
+    }
+    Some(event.id)
+}
+
+/// Tracer that mirrors AI request/response records (already redacted by
+/// the provider client) onto the debug timeline
+pub fn ai_tracer(state: &Arc<AppState>) -> AiTracer {
+    let state = Arc::downgrade(state);
+    AiTracer::new(move |trace| {
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        tokio::spawn(async move {
+            let sink = state
+                .event_sink
+                .read()
+                .await
+                .clone()
+                .unwrap_or_else(|| Arc::new(NoopSink));
+            let event_type = match trace.kind {
+                AiTraceKind::Request => DebugEventType::LlmInput,
+                AiTraceKind::Response => DebugEventType::LlmOutput,
+            };
+            record_debug(
+                sink.as_ref(),
+                &state,
+                event_type,
+                &trace.label,
+                trace.data,
+                trace.duration_ms,
+                None,
+            )
+            .await;
+        });
+    })
+}
+
+/// Close out an event opened with no duration and re-send it to the host,
+/// which replaces its copy by id
+pub async fn finish_debug(sink: &dyn EventSink, state: &AppState, id: &str, duration_ms: u64) {
+    let event = state.debug_timeline.write().await.set_duration(id, duration_ms);
+
+    if let Some(event) = event {
+        if let Ok(payload) = serde_json::to_value(&event) {
+            sink.emit(events::DEBUG_EVENT, payload);
+        }
+    }
+}
+
 // --- Tauri Commands ---
 
 /// Get debug events (newest first)
@@ -179,7 +267,7 @@ pub async fn get_debug_events(
     state: State<'_, Arc<AppState>>,
     event_types: Option<Vec<DebugEventType>>,
     limit: Option<usize>,
-) -> Result<Vec<DebugEvent>, String> {
+) -> Result<Vec<DebugEvent>, HawkeyeError> {
     let timeline = state.debug_timeline.read().await;
     Ok(timeline.get_events(event_types.as_deref(), limit))
 }
@@ -189,7 +277,7 @@ pub async fn get_debug_events(
 pub async fn get_debug_events_since(
     state: State<'_, Arc<AppState>>,
     since_ms: u64,
-) -> Result<Vec<DebugEvent>, String> {
+) -> Result<Vec<DebugEvent>, HawkeyeError> {
     let timeline = state.debug_timeline.read().await;
     Ok(timeline.get_since(since_ms))
 }
@@ -199,11 +287,21 @@ pub async fn get_debug_events_since(
 pub async fn search_debug_events(
     state: State<'_, Arc<AppState>>,
     query: String,
-) -> Result<Vec<DebugEvent>, String> {
+) -> Result<Vec<DebugEvent>, HawkeyeError> {
     let timeline = state.debug_timeline.read().await;
     Ok(timeline.search(&query))
 }
 
+/// Get the events grouped under `parent_id` (e.g. one observe cycle)
+#[command]
+pub async fn get_debug_children(
+    state: State<'_, Arc<AppState>>,
+    parent_id: String,
+) -> Result<Vec<DebugEvent>, HawkeyeError> {
+    let timeline = state.debug_timeline.read().await;
+    Ok(timeline.get_children(&parent_id))
+}
+
 /// Push a debug event (from frontend or other commands)
 #[command]
 pub async fn push_debug_event(
@@ -212,7 +310,7 @@ pub async fn push_debug_event(
     label: String,
     data: serde_json::Value,
     duration_ms: Option<u64>,
-) -> Result<Option<DebugEvent>, String> {
+) -> Result<Option<DebugEvent>, HawkeyeError> {
     let mut timeline = state.debug_timeline.write().await;
     Ok(timeline.push(event_type, label, data, duration_ms, None))
 }
@@ -221,7 +319,7 @@ pub async fn push_debug_event(
 #[command]
 pub async fn get_debug_status(
     state: State<'_, Arc<AppState>>,
-) -> Result<DebugStatus, String> {
+) -> Result<DebugStatus, HawkeyeError> {
     let timeline = state.debug_timeline.read().await;
     Ok(timeline.status())
 }
@@ -230,7 +328,7 @@ pub async fn get_debug_status(
 #[command]
 pub async fn pause_debug(
     state: State<'_, Arc<AppState>>,
-) -> Result<bool, String> {
+) -> Result<bool, HawkeyeError> {
     let mut timeline = state.debug_timeline.write().await;
     timeline.paused = true;
     Ok(true)
@@ -240,7 +338,7 @@ pub async fn pause_debug(
 #[command]
 pub async fn resume_debug(
     state: State<'_, Arc<AppState>>,
-) -> Result<bool, String> {
+) -> Result<bool, HawkeyeError> {
     let mut timeline = state.debug_timeline.write().await;
     timeline.paused = false;
     Ok(true)
@@ -250,7 +348,7 @@ pub async fn resume_debug(
 #[command]
 pub async fn clear_debug_events(
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
+) -> Result<(), HawkeyeError> {
     let mut timeline = state.debug_timeline.write().await;
     timeline.clear();
     Ok(())
@@ -0,0 +1,60 @@
+//! Focus session commands — flag activity outside the chosen life stages
+
+use std::sync::Arc;
+use tauri::{command, State};
+
+use crate::clock::{Clock, SystemClock};
+use crate::error::HawkeyeError;
+use crate::life_tree::LifeStage;
+use crate::observe::focus::{FocusSession, FocusSummary};
+use crate::state::AppState;
+
+/// Start a focus session of `duration_minutes`. While it runs, every frame
+/// the observe loop records outside `allowed_stages` counts as distraction,
+/// and each switch off-task emits `focus:distraction`. Needs the observe
+/// loop running to see anything.
+#[command]
+pub async fn start_focus_session(
+    duration_minutes: u32,
+    allowed_stages: Vec<LifeStage>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), HawkeyeError> {
+    if duration_minutes == 0 {
+        return Err("Focus session duration must be at least one minute".into());
+    }
+    if allowed_stages.is_empty() {
+        return Err("Pick at least one life stage to focus on".into());
+    }
+
+    let mut session = state.focus_session.write().await;
+    if session.is_some() {
+        return Err("A focus session is already running; end it first".into());
+    }
+    *session = Some(FocusSession::new(
+        SystemClock.now_ms(),
+        duration_minutes as u64 * 60_000,
+        allowed_stages,
+    ));
+    log::info!("[Focus] Session started ({} min)", duration_minutes);
+    Ok(())
+}
+
+/// End the running focus session and return its focus vs distraction time
+#[command]
+pub async fn end_focus_session(
+    state: State<'_, Arc<AppState>>,
+) -> Result<FocusSummary, HawkeyeError> {
+    let session = state
+        .focus_session
+        .write()
+        .await
+        .take()
+        .ok_or_else(|| HawkeyeError::NotFound("No focus session is running".into()))?;
+    let summary = session.finish(SystemClock.now_ms());
+    log::info!(
+        "[Focus] Session ended: {}s focused, {}s distracted",
+        summary.focus_ms / 1000,
+        summary.distraction_ms / 1000
+    );
+    Ok(summary)
+}
@@ -3,7 +3,9 @@
 use std::sync::Arc;
 use tauri::{command, AppHandle, Emitter, State};
 
+use crate::error::HawkeyeError;
 use crate::events;
+use crate::perception::screen::CaptureOptions;
 use crate::state::AppState;
 
 /// Gesture action types
@@ -65,7 +67,7 @@ pub async fn handle_gesture(
     app: AppHandle,
     event: GestureEvent,
     state: State<'_, Arc<AppState>>,
-) -> Result<bool, String> {
+) -> Result<bool, HawkeyeError> {
     let config = state.gesture_config.read().await;
     if !config.enabled {
         return Ok(false);
@@ -85,8 +87,13 @@ pub async fn handle_gesture(
     // Handle specific actions
     match event.action {
         GestureAction::Screenshot => {
-            // Trigger a screenshot capture
-            let capture = crate::perception::screen::capture_screenshot().await;
+            // Trigger a screenshot capture, unless the user turned capture off
+            if let Err(e) = state.ensure_capture_enabled().await {
+                log::info!("[Gesture] Screenshot skipped: {}", e);
+                return Ok(true);
+            }
+            let options = CaptureOptions::from_config(&*state.config.read().await);
+            let capture = crate::perception::screen::capture_screenshot(options).await;
             match capture {
                 Ok((base64, _w, _h)) => {
                     let _ = app.emit(events::GESTURE_SCREENSHOT, &base64);
@@ -120,7 +127,7 @@ pub async fn handle_gesture(
 #[command]
 pub async fn get_gesture_status(
     state: State<'_, Arc<AppState>>,
-) -> Result<GestureConfig, String> {
+) -> Result<GestureConfig, HawkeyeError> {
     let config = state.gesture_config.read().await;
     Ok(config.clone())
 }
@@ -130,7 +137,7 @@ pub async fn get_gesture_status(
 pub async fn set_gesture_config(
     new_config: GestureConfig,
     state: State<'_, Arc<AppState>>,
-) -> Result<GestureConfig, String> {
+) -> Result<GestureConfig, HawkeyeError> {
     let mut config = state.gesture_config.write().await;
     *config = new_config.clone();
     Ok(new_config)
@@ -141,7 +148,7 @@ pub async fn set_gesture_config(
 pub async fn set_gesture_enabled(
     enabled: bool,
     state: State<'_, Arc<AppState>>,
-) -> Result<bool, String> {
+) -> Result<bool, HawkeyeError> {
     let mut config = state.gesture_config.write().await;
     config.enabled = enabled;
     log::info!("[Gesture] {}", if enabled { "Enabled" } else { "Disabled" });
@@ -3,6 +3,8 @@
 use std::sync::Arc;
 use tauri::{command, State};
 
+use crate::ai::rate_limit::{self, RequestPriority};
+use crate::error::HawkeyeError;
 use crate::observe::intent::{IntentRecognizer, RecognitionInput, UserIntent};
 use crate::state::AppState;
 
@@ -10,7 +12,7 @@ use crate::state::AppState;
 #[command]
 pub async fn recognize_intent(
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<UserIntent>, String> {
+) -> Result<Vec<UserIntent>, HawkeyeError> {
     // Build input from last observation
     let input = {
         let obs = state.last_observation.read().await;
@@ -21,7 +23,7 @@ pub async fn recognize_intent(
                 ocr_text: o.ocr_text.clone(),
                 clipboard: None,
             },
-            None => return Err("No observation available. Start observe first.".to_string()),
+            None => return Err("No observation available. Start observe first.".into()),
         }
     };
 
@@ -34,7 +36,7 @@ pub async fn recognize_intent(
 #[command]
 pub async fn recognize_intent_ai(
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<UserIntent>, String> {
+) -> Result<Vec<UserIntent>, HawkeyeError> {
     // Build input from last observation
     let input = {
         let obs = state.last_observation.read().await;
@@ -45,7 +47,7 @@ pub async fn recognize_intent_ai(
                 ocr_text: o.ocr_text.clone(),
                 clipboard: None,
             },
-            None => return Err("No observation available. Start observe first.".to_string()),
+            None => return Err("No observation available. Start observe first.".into()),
         }
     };
 
@@ -56,13 +58,20 @@ pub async fn recognize_intent_ai(
     };
 
     // AI enhancement
-    let ai = state.ai_client.read().await;
-    let client = match ai.as_ref() {
-        Some(c) => c,
-        None => return Ok(rule_intents), // Fallback to rule-based only
+    let client = match state.ai_provider().await {
+        Ok(c) => c,
+        Err(_) => return Ok(rule_intents), // Fallback to rule-based only
     };
+    if !rate_limit::acquire(&state, client.provider_name(), RequestPriority::Background).await {
+        return Ok(rule_intents);
+    }
 
-    let prompt = IntentRecognizer::build_ai_prompt(&input, &rule_intents);
+    let persona = crate::ai::Persona::from_config(&*state.config.read().await);
+    let prompt = persona.style_prompt(IntentRecognizer::build_ai_prompt(
+        &input,
+        &rule_intents,
+        &persona.language,
+    ));
     let messages = vec![crate::ai::ChatMessage {
         role: "user".to_string(),
         content: prompt,
@@ -84,7 +93,7 @@ pub async fn recognize_intent_ai(
 #[command]
 pub async fn get_recent_intents(
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<UserIntent>, String> {
+) -> Result<Vec<UserIntent>, HawkeyeError> {
     let recognizer = state.intent_recognizer.read().await;
     Ok(recognizer.recent_intents().to_vec())
 }
@@ -1,8 +1,13 @@
 //! Life Tree commands — view, update, and manage the life tree
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{command, State};
 
+use crate::ai::rate_limit::{self, RequestPriority};
+use crate::ai::{provider, ChatMessage, Persona};
+use crate::error::HawkeyeError;
+use crate::life_tree::insights::{self, LifeTreeInsights};
 use crate::life_tree::types::*;
 use crate::state::AppState;
 
@@ -10,7 +15,7 @@ use crate::state::AppState;
 #[command]
 pub async fn get_life_tree(
     state: State<'_, Arc<AppState>>,
-) -> Result<LifeTreeSnapshot, String> {
+) -> Result<LifeTreeSnapshot, HawkeyeError> {
     let tree = state.life_tree.read().await;
     Ok(tree.snapshot())
 }
@@ -19,90 +24,181 @@ pub async fn get_life_tree(
 #[command]
 pub async fn rebuild_life_tree(
     state: State<'_, Arc<AppState>>,
-) -> Result<LifeTreeSnapshot, String> {
+) -> Result<LifeTreeSnapshot, HawkeyeError> {
     let mut tree = state.life_tree.write().await;
     tree.rebuild();
     Ok(tree.snapshot())
 }
 
+/// Re-file the tree's tasks under the current classification rules. Counts,
+/// experiments and their outcomes move with their tasks; nothing is lost.
+#[command]
+pub async fn reclassify_life_tree(
+    state: State<'_, Arc<AppState>>,
+) -> Result<LifeTreeSnapshot, HawkeyeError> {
+    let mut tree = state.life_tree.write().await;
+    let moved = tree.reclassify()?;
+    log::info!("[LifeTree] Reclassified tree; {} tasks moved", moved);
+    Ok(tree.snapshot())
+}
+
 /// Propose an experiment for a node (AI-powered)
 #[command]
 pub async fn propose_experiment(
     node_id: String,
     state: State<'_, Arc<AppState>>,
-) -> Result<ExperimentProposal, String> {
+) -> Result<ExperimentProposal, HawkeyeError> {
+    let language = Persona::from_config(&*state.config.read().await).language;
     let messages = {
         let tree = state.life_tree.read().await;
-        tree.build_experiment_prompt(&node_id)?
+        tree.build_experiment_prompt(&node_id, &language)?
     };
 
-    let ai = state.ai_client.read().await;
-    let client = ai
-        .as_ref()
-        .ok_or_else(|| "AI not initialized".to_string())?;
-
-    let response = client.chat(messages).await.map_err(|e| e.to_string())?;
-
-    // Parse AI response as JSON
-    let text = response.text.trim();
-    // Try to extract JSON from potential markdown code blocks
-    let json_str = if text.contains("```") {
-        text.lines()
-            .skip_while(|l| !l.starts_with('{'))
-            .take_while(|l| !l.starts_with("```"))
-            .collect::<Vec<_>>()
-            .join("\n")
-    } else {
-        text.to_string()
+    request_proposal(&state, messages).await
+}
+
+/// Propose an experiment for a whole life stage, scoped to the highest
+/// unlocked phase. Pass the returned `nodeId` and `phase` to
+/// `start_experiment`.
+#[command]
+pub async fn propose_stage_experiment(
+    stage: LifeStage,
+    state: State<'_, Arc<AppState>>,
+) -> Result<StageExperimentProposal, HawkeyeError> {
+    let language = Persona::from_config(&*state.config.read().await).language;
+    let (node_id, phase, messages) = {
+        let tree = state.life_tree.read().await;
+        tree.build_stage_experiment_prompt(&stage, &language)?
     };
 
-    serde_json::from_str(&json_str).map_err(|e| {
-        format!("Failed to parse AI response: {}. Raw: {}", e, text)
+    let proposal = request_proposal(&state, messages).await?;
+    Ok(StageExperimentProposal {
+        proposal,
+        node_id,
+        phase,
     })
 }
 
-/// Start an experiment
+/// Send an experiment prompt and parse the JSON proposal
+async fn request_proposal(
+    state: &AppState,
+    messages: Vec<ChatMessage>,
+) -> Result<ExperimentProposal, HawkeyeError> {
+    let client = state.ai_provider().await?;
+    let messages = Persona::from_config(&*state.config.read().await).style_messages(messages);
+
+    if !rate_limit::acquire(state, client.provider_name(), RequestPriority::Interactive).await {
+        return Err(rate_limit::limited_error(client.provider_name()));
+    }
+
+    // JSON mode where the provider has it, so the proposal parses reliably
+    Ok(provider::chat_json(client.as_ref(), messages).await?)
+}
+
+/// Start an experiment; `duration_days` (from the proposal) sets its due date
 #[command]
 pub async fn start_experiment(
     node_id: String,
     title: String,
     description: String,
     phase: ExperimentPhase,
+    duration_days: Option<u32>,
     state: State<'_, Arc<AppState>>,
-) -> Result<String, String> {
+) -> Result<String, HawkeyeError> {
     let mut tree = state.life_tree.write().await;
-    tree.create_experiment(&node_id, title, description, phase)
+    tree.create_experiment(&node_id, title, description, phase, duration_days)
 }
 
-/// Conclude an experiment
+/// Conclude an experiment, optionally recording notes and metrics
 #[command]
 pub async fn conclude_experiment(
     experiment_id: String,
     succeeded: bool,
+    notes: Option<String>,
+    metrics: Option<HashMap<String, f64>>,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
+) -> Result<(), HawkeyeError> {
     let mut tree = state.life_tree.write().await;
-    tree.conclude_experiment(&experiment_id, succeeded)
+    tree.conclude_experiment(&experiment_id, succeeded, notes, metrics.unwrap_or_default())
+}
+
+/// Concluded experiments with their outcomes, most recent first
+#[command]
+pub async fn get_experiment_history(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<LifeTreeNode>, HawkeyeError> {
+    let tree = state.life_tree.read().await;
+    Ok(tree.experiment_history().into_iter().cloned().collect())
 }
 
 /// Get the max unlocked experiment phase
 #[command]
 pub async fn get_unlocked_phase(
     state: State<'_, Arc<AppState>>,
-) -> Result<ExperimentPhase, String> {
+) -> Result<ExperimentPhase, HawkeyeError> {
     let tree = state.life_tree.read().await;
     Ok(tree.unlocked_phase())
 }
 
+/// Completions per phase and how many more unlock the next one
+#[command]
+pub async fn get_phase_progress(
+    state: State<'_, Arc<AppState>>,
+) -> Result<PhaseProgress, HawkeyeError> {
+    let tree = state.life_tree.read().await;
+    Ok(tree.phase_progress())
+}
+
 /// Get all experiments
 #[command]
 pub async fn get_experiments(
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<LifeTreeNode>, String> {
+) -> Result<Vec<LifeTreeNode>, HawkeyeError> {
     let tree = state.life_tree.read().await;
     Ok(tree.experiments().into_iter().cloned().collect())
 }
 
+/// Active experiments that are overdue or due within `within_hours`
+/// (default 24), soonest first
+#[command]
+pub async fn get_due_experiments(
+    within_hours: Option<u32>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<LifeTreeNode>, HawkeyeError> {
+    let within_ms = within_hours.unwrap_or(24) as u64 * 60 * 60 * 1000;
+    let tree = state.life_tree.read().await;
+    Ok(tree
+        .due_experiments(tree.now(), within_ms)
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+/// Daily observation counts for one stage over the last `days` days
+/// (default 7, at most 30), oldest first
+#[command]
+pub async fn get_stage_timeline(
+    stage: LifeStage,
+    days: Option<u32>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<DailyCount>, HawkeyeError> {
+    let today = chrono::Local::now().date_naive();
+    let tree = state.life_tree.read().await;
+    Ok(tree.stage_timeline(&stage, days.unwrap_or(7), today))
+}
+
+/// Correlate daily activity across stages over the last `days` days
+/// (default and max 30) and report the strongest pairs
+#[command]
+pub async fn analyze_life_tree(
+    days: Option<u32>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<LifeTreeInsights, HawkeyeError> {
+    let today = chrono::Local::now().date_naive();
+    let tree = state.life_tree.read().await;
+    Ok(insights::analyze(&tree, days.unwrap_or(STAGE_HISTORY_DAYS), today))
+}
+
 /// AI experiment proposal response
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -111,3 +207,14 @@ pub struct ExperimentProposal {
     pub description: String,
     pub duration_days: u32,
 }
+
+/// Stage-wide experiment proposal, with where and at which phase to start it
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageExperimentProposal {
+    #[serde(flatten)]
+    pub proposal: ExperimentProposal,
+    /// Stage node to attach the experiment to
+    pub node_id: String,
+    pub phase: ExperimentPhase,
+}
@@ -4,10 +4,13 @@ pub mod adaptive_cmd;
 pub mod agent_cmd;
 pub mod chat_cmd;
 pub mod config_cmd;
+pub mod focus_cmd;
 pub mod intent_cmd;
 pub mod life_tree_cmd;
 pub mod observe_cmd;
 pub mod perception_cmd;
+pub mod reset_cmd;
+pub mod snapshot_cmd;
 pub mod status;
 pub mod summarizer_cmd;
 pub mod util_cmd;
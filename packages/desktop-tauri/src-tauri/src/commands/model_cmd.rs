@@ -1,10 +1,14 @@
 //! Model management commands — list, download, delete local AI models
 
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{command, Emitter, State, AppHandle};
 
+use crate::error::HawkeyeError;
 use crate::events;
-use crate::models::manager::{DownloadProgress, LocalModel};
+use crate::models::manager::{
+    self, DownloadProgress, LocalModel, ModelManager, PartialCleanup, RelocateReport,
+};
 use crate::models::registry::{self, ModelInfo, ModelType};
 use crate::state::AppState;
 
@@ -12,18 +16,83 @@ use crate::state::AppState;
 #[command]
 pub async fn get_models_dir(
     state: State<'_, Arc<AppState>>,
-) -> Result<String, String> {
+) -> Result<String, HawkeyeError> {
     let mgr = state.model_manager.read().await;
     Ok(mgr.models_dir().to_string_lossy().to_string())
 }
 
+/// Move model storage to `path` (e.g. an external disk), or back to the
+/// default directory when `path` is empty. Downloaded models are moved
+/// along unless `move_models` is false; models already in `path` are kept.
+/// Waits for a running download to finish.
+#[command]
+pub async fn set_models_dir(
+    path: Option<String>,
+    move_models: Option<bool>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<RelocateReport, HawkeyeError> {
+    let dir = path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+    if dir.as_ref().is_some_and(|d| d.is_relative()) {
+        return Err("Models directory must be an absolute path".into());
+    }
+    let target = dir.clone().unwrap_or_else(ModelManager::default_models_dir);
+
+    // Moving across disks copies gigabytes; keep it off the async workers
+    // and hold only the manager meanwhile, so config readers don't stall
+    let move_models = move_models.unwrap_or(true);
+    let (report, previous_dir) = {
+        let mut mgr = state.model_manager.write().await;
+        let previous_dir = mgr.models_dir().clone();
+        let report = tokio::task::block_in_place(|| mgr.relocate(&target, move_models))?;
+        (report, previous_dir)
+    };
+
+    let saved = {
+        let mut config = state.config.write().await;
+        let mut updated = config.clone();
+        updated.models_dir_override = dir;
+        crate::config::save_config(&updated).map(|()| *config = updated)
+    };
+    if let Err(e) = saved {
+        // The saved config still names the old directory; move back to it
+        let mut mgr = state.model_manager.write().await;
+        let undone = tokio::task::block_in_place(|| mgr.relocate(&previous_dir, move_models));
+        let message = match undone {
+            Ok(_) => format!(
+                "Failed to save config: {}; models are back in {}",
+                e,
+                previous_dir.display()
+            ),
+            Err(undo) => format!(
+                "Failed to save config: {}; moving models back to {} also failed: {}",
+                e,
+                previous_dir.display(),
+                undo
+            ),
+        };
+        log::error!("[Models] {}", message);
+        return Err(message.into());
+    }
+
+    log::info!(
+        "[Models] Models directory set to {} ({} moved, {} already there)",
+        report.models_dir,
+        report.moved,
+        report.already_present
+    );
+    Ok(report)
+}
+
 /// List all downloaded models
 #[command]
 pub async fn list_models(
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<LocalModel>, String> {
+) -> Result<Vec<LocalModel>, HawkeyeError> {
     let mgr = state.model_manager.read().await;
-    mgr.list_models()
+    Ok(mgr.list_models()?)
 }
 
 /// Get recommended models from registry
@@ -38,32 +107,51 @@ pub fn get_models_by_type(model_type: ModelType) -> Vec<ModelInfo> {
     registry::get_models_by_type(&model_type)
 }
 
+/// Get recommended models matching any of `model_types` (one round-trip
+/// for e.g. text + vision LLMs)
+#[command]
+pub fn get_models_by_types(model_types: Vec<ModelType>) -> Vec<ModelInfo> {
+    registry::get_models_by_types(&model_types)
+}
+
+/// Search recommended models by name or description
+#[command]
+pub fn search_models(query: String) -> Vec<ModelInfo> {
+    registry::search_models(&query)
+}
+
 /// Check if a model exists locally
 #[command]
 pub async fn model_exists(
     model_id: String,
     state: State<'_, Arc<AppState>>,
-) -> Result<bool, String> {
+) -> Result<bool, HawkeyeError> {
     let mgr = state.model_manager.read().await;
     Ok(mgr.model_exists(&model_id))
 }
 
-/// Download a model by ID from the registry
+/// Download a model by ID from the registry. The model manager isn't
+/// locked during the transfer, so `cancel_model_download` can reach it.
 #[command]
 pub async fn download_model(
     model_id: String,
     app: AppHandle,
     state: State<'_, Arc<AppState>>,
-) -> Result<LocalModel, String> {
+) -> Result<LocalModel, HawkeyeError> {
     let model_info = registry::get_model_by_id(&model_id)
-        .ok_or_else(|| format!("Unknown model ID: {}", model_id))?;
+        .ok_or_else(|| HawkeyeError::NotFound(format!("Unknown model ID: {}", model_id)))?;
 
-    let app_clone = app.clone();
-    let mut mgr = state.model_manager.write().await;
+    let hf_token = state.config.read().await.hf_token.clone();
 
-    mgr.download_model(&model_info, move |progress: DownloadProgress| {
-        let _ = app_clone.emit(events::MODEL_DOWNLOAD_PROGRESS, &progress);
-    })
+    let app_clone = app.clone();
+    ModelManager::download_model(
+        &state.model_manager,
+        &model_info,
+        hf_token.as_deref(),
+        move |progress: DownloadProgress| {
+            let _ = app_clone.emit(events::MODEL_DOWNLOAD_PROGRESS, &progress);
+        },
+    )
     .await
 }
 
@@ -71,18 +159,31 @@ pub async fn download_model(
 #[command]
 pub async fn cancel_model_download(
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    let mut mgr = state.model_manager.write().await;
-    mgr.cancel_download();
+) -> Result<(), HawkeyeError> {
+    state.model_manager.read().await.cancel_download();
     Ok(())
 }
 
+/// Remove `.part` files abandoned by interrupted downloads (older than
+/// `older_than_secs`, default one hour) and report the space reclaimed
+#[command]
+pub async fn cleanup_partial_downloads(
+    older_than_secs: Option<u64>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<PartialCleanup, HawkeyeError> {
+    let older_than = older_than_secs
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(manager::STALE_PARTIAL_AGE);
+    let mgr = state.model_manager.read().await;
+    Ok(mgr.cleanup_partial_downloads(older_than)?)
+}
+
 /// Delete a downloaded model
 #[command]
 pub async fn delete_model(
     model_id: String,
     state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
+) -> Result<(), HawkeyeError> {
     let mgr = state.model_manager.read().await;
     mgr.delete_model(&model_id)
 }
@@ -92,7 +193,7 @@ pub async fn delete_model(
 pub async fn get_model_path(
     model_id: String,
     state: State<'_, Arc<AppState>>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, HawkeyeError> {
     let mgr = state.model_manager.read().await;
     Ok(mgr.model_path(&model_id).map(|p| p.to_string_lossy().to_string()))
 }
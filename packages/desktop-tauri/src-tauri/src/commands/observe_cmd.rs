@@ -4,8 +4,15 @@ use std::sync::Arc;
 use serde::Serialize;
 use tauri::{command, AppHandle, State};
 
+use crate::error::HawkeyeError;
 use crate::event_sink::{SharedSink, TauriSink};
-use crate::observe::ObserveLoop;
+use crate::observe::benchmark::BenchmarkReport;
+use crate::observe::change_stats::ChangeStatsSummary;
+use crate::observe::frame_diff::{self, ObservationDiff};
+use crate::observe::loop_runner::clear_recent_frames;
+use crate::observe::once::ObserveOnceResult;
+use crate::observe::recent_frames::{RecentFrame, RecentFrames};
+use crate::observe::{ObserveLoop, ObserveParams};
 use crate::state::{AppState, ObservationResult};
 
 /// Observe status response
@@ -13,6 +20,11 @@ use crate::state::{AppState, ObservationResult};
 #[serde(rename_all = "camelCase")]
 pub struct ObserveStatus {
     pub running: bool,
+    /// Seconds since the loop last iterated; `None` when not running
+    pub secs_since_last_tick: Option<f64>,
+    /// Running, alive and ticking recently. `running && !healthy` means the
+    /// loop has stalled or its task died; `start_observe` restarts a dead one.
+    pub healthy: bool,
     pub last_observation: Option<ObservationResult>,
 }
 
@@ -21,13 +33,7 @@ pub struct ObserveStatus {
 pub async fn start_observe(
     app: AppHandle,
     state: State<'_, Arc<AppState>>,
-) -> Result<bool, String> {
-    let mut loop_handle = state.observe_loop.write().await;
-
-    if loop_handle.is_some() {
-        return Ok(false); // Already running
-    }
-
+) -> Result<bool, HawkeyeError> {
     // Prefer the pre-installed sink; fall back to a fresh TauriSink so
     // command callers don't have to wait for setup-time initialization.
     let sink: SharedSink = state
@@ -37,14 +43,9 @@ pub async fn start_observe(
         .clone()
         .unwrap_or_else(|| -> SharedSink { Arc::new(TauriSink::new(app)) });
 
-    let observe = ObserveLoop::start(
-        sink,
-        Arc::clone(&state),
-        3000,  // 3s interval
-        0.05,  // 5% change threshold
-    );
-
-    *loop_handle = Some(observe);
+    if !ObserveLoop::start_if_idle(&state, sink).await {
+        return Ok(false); // Already running
+    }
     log::info!("[Observe] Started");
     Ok(true)
 }
@@ -53,28 +54,191 @@ pub async fn start_observe(
 #[command]
 pub async fn stop_observe(
     state: State<'_, Arc<AppState>>,
-) -> Result<bool, String> {
+) -> Result<bool, HawkeyeError> {
     let mut loop_handle = state.observe_loop.write().await;
 
     if let Some(observe) = loop_handle.take() {
         observe.stop();
         log::info!("[Observe] Stopped");
+        drop(loop_handle);
+        clear_recent_frames(&state).await;
         Ok(true)
     } else {
         Ok(false) // Not running
     }
 }
 
+/// Get the full-resolution screenshot (base64 PNG) of the last observation.
+/// `observe:update` events only carry a thumbnail.
+#[command]
+pub async fn get_last_full_screenshot(
+    state: State<'_, Arc<AppState>>,
+) -> Result<Option<String>, HawkeyeError> {
+    let last = state.last_observation.read().await;
+    Ok(last.as_ref().and_then(|o| o.screenshot_base64.clone()))
+}
+
+/// The last `count` recorded frames (default 10), newest first, each with
+/// its thumbnail and classification
+#[command]
+pub async fn get_recent_observations(
+    count: Option<usize>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<Vec<RecentFrame>, HawkeyeError> {
+    let frames = state.recent_frames.read().await.clone();
+    let recent = tokio::task::spawn_blocking(move || frames.recent(count.unwrap_or(10)))
+        .await
+        .map_err(|e| format!("Reading recent frames failed: {}", e))??;
+    Ok(recent)
+}
+
+/// What changed between two recorded frames: app and window, OCR lines
+/// gained and lost, hash distance and a one-line summary. Without
+/// timestamps the newest frame is compared with the one before it; a
+/// single timestamp is compared with the newest frame.
+#[command]
+pub async fn diff_observations(
+    a_timestamp: Option<u64>,
+    b_timestamp: Option<u64>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ObservationDiff, HawkeyeError> {
+    let frames = state.recent_frames.read().await.clone();
+    let (a, b) =
+        tokio::task::spawn_blocking(move || pick_frames(&frames, a_timestamp, b_timestamp))
+            .await
+            .map_err(|e| format!("Reading recent frames failed: {}", e))??;
+
+    // Always diff earlier → later
+    Ok(if a.timestamp <= b.timestamp {
+        frame_diff::diff(&a, &b)
+    } else {
+        frame_diff::diff(&b, &a)
+    })
+}
+
+/// The two frames `diff_observations` compares, in either order. Blocking.
+fn pick_frames(
+    frames: &RecentFrames,
+    a_timestamp: Option<u64>,
+    b_timestamp: Option<u64>,
+) -> Result<(RecentFrame, RecentFrame), HawkeyeError> {
+    let find = |timestamp: u64| -> Result<_, HawkeyeError> {
+        frames.get(timestamp)?.ok_or_else(|| {
+            HawkeyeError::NotFound(format!("No recorded observation at {}", timestamp))
+        })
+    };
+
+    Ok(match (a_timestamp, b_timestamp) {
+        (Some(a), Some(b)) => (find(a)?, find(b)?),
+        (Some(a), None) | (None, Some(a)) => {
+            let newest = frames
+                .recent(1)?
+                .pop()
+                .ok_or_else(|| HawkeyeError::NotFound("No recorded observations".to_string()))?;
+            (find(a)?, newest)
+        }
+        (None, None) => {
+            let mut recent = frames.recent(2)?;
+            if recent.len() < 2 {
+                return Err(HawkeyeError::NotFound(
+                    "Need two recorded observations; is recentFramesCount 0?".to_string(),
+                ));
+            }
+            let newest = recent.remove(0);
+            (recent.remove(0), newest)
+        }
+    })
+}
+
+/// Run the observe pipeline once and return what it derived (observation,
+/// intents, life stage, task label). Nothing is recorded unless `commit` is
+/// true — handy for tuning heuristics without starting the loop.
+#[command]
+pub async fn observe_once(
+    state: State<'_, Arc<AppState>>,
+    commit: Option<bool>,
+) -> Result<ObserveOnceResult, HawkeyeError> {
+    crate::observe::once::observe_once(&state, commit.unwrap_or(false))
+        .await
+        .map_err(HawkeyeError::from)
+}
+
+/// Time capture, encode, hash, OCR and window lookup over `iterations`
+/// runs (default 10, max 50) with the current settings. Leaves the live
+/// loop's state untouched.
+#[command]
+pub async fn benchmark_pipeline(
+    state: State<'_, Arc<AppState>>,
+    iterations: Option<u32>,
+) -> Result<BenchmarkReport, HawkeyeError> {
+    crate::observe::benchmark::run_benchmark(&state, iterations.unwrap_or(10))
+        .await
+        .map_err(HawkeyeError::from)
+}
+
 /// Get observe status
 #[command]
 pub async fn get_observe_status(
     state: State<'_, Arc<AppState>>,
-) -> Result<ObserveStatus, String> {
-    let running = state.observe_loop.read().await.is_some();
+) -> Result<ObserveStatus, HawkeyeError> {
+    let health = state.observe_loop.read().await.as_ref().map(ObserveLoop::health);
     let last_observation = state.last_observation.read().await.clone();
 
     Ok(ObserveStatus {
-        running,
+        running: health.is_some(),
+        secs_since_last_tick: health.map(|h| h.secs_since_last_tick),
+        healthy: health.is_some_and(|h| h.healthy),
         last_observation,
     })
 }
+
+/// Update observe parameters. Applies to a running loop on its next frame.
+/// Turning `record_stats` off clears the collected samples.
+#[command]
+pub async fn set_observe_params(
+    state: State<'_, Arc<AppState>>,
+    threshold: Option<f64>,
+    record_stats: Option<bool>,
+) -> Result<ObserveParams, HawkeyeError> {
+    if let Some(t) = threshold {
+        if !(0.0..=1.0).contains(&t) {
+            return Err(format!("Threshold must be between 0 and 1, got {}", t).into());
+        }
+    }
+
+    let mut params = state.observe_params.write().await;
+    if let Some(t) = threshold {
+        params.threshold = t;
+    }
+    if let Some(enabled) = record_stats {
+        params.record_stats = enabled;
+        if !enabled {
+            state.change_stats.write().await.clear();
+        }
+    }
+
+    Ok(*params)
+}
+
+/// Get current observe parameters
+#[command]
+pub async fn get_observe_params(
+    state: State<'_, Arc<AppState>>,
+) -> Result<ObserveParams, HawkeyeError> {
+    Ok(*state.observe_params.read().await)
+}
+
+/// Summarize recorded change ratios. `threshold` lets a settings screen
+/// preview how many frames a candidate value would trigger; it defaults to
+/// the active threshold.
+#[command]
+pub async fn get_change_stats(
+    state: State<'_, Arc<AppState>>,
+    threshold: Option<f64>,
+) -> Result<ChangeStatsSummary, HawkeyeError> {
+    let threshold = match threshold {
+        Some(t) => t,
+        None => state.observe_params.read().await.threshold,
+    };
+    Ok(state.change_stats.read().await.summary(threshold))
+}
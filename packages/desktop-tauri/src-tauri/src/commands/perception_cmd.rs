@@ -1,9 +1,23 @@
 //! Perception commands — screen capture, OCR, clipboard, window
 
-use serde::Serialize;
-use tauri::command;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{command, AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
 
+use crate::commands::debug_cmd::{record_debug, DebugEventType};
+use crate::error::HawkeyeError;
+use crate::event_sink::{EventSink, NoopSink, SharedSink};
+use crate::events;
+use crate::observe::loop_runner::clear_recent_frames;
 use crate::perception;
+use crate::perception::ocr::{OcrOptions, OcrRegion, OcrResult, RecognitionLevel};
+use crate::perception::permissions::{PermissionKind, PermissionState, PermissionStatus};
+use crate::perception::screen::{CaptureFormat, CaptureOptions};
+use crate::perception::window_capture::{capture_window, NamedWindow, WindowQuery};
+use crate::state::AppState;
 
 /// Screen capture result
 #[derive(Debug, Clone, Serialize)]
@@ -16,13 +30,27 @@ pub struct ScreenshotResult {
     pub error: Option<String>,
 }
 
-/// Capture the current screen
+/// Capture the current screen. Defaults to PNG; pass `format: "jpeg"` for a
+/// much smaller payload when the image is only displayed. Keep PNG for
+/// anything that will be OCR'd. PNG compression and the JPEG quality
+/// default to the configured capture settings.
 #[command]
-pub async fn capture_screen() -> Result<ScreenshotResult, String> {
-    match perception::screen::capture_screenshot().await {
+pub async fn capture_screen(
+    state: State<'_, Arc<AppState>>,
+    format: Option<CaptureFormat>,
+    quality: Option<u8>,
+) -> Result<ScreenshotResult, HawkeyeError> {
+    state.ensure_capture_enabled().await?;
+    let mut options = CaptureOptions::from_config(&*state.config.read().await);
+    options.format = format.unwrap_or_default();
+    if let Some(quality) = quality {
+        options.quality = quality;
+    }
+
+    match perception::screen::capture_screenshot(options).await {
         Ok((data, width, height)) => Ok(ScreenshotResult {
             success: true,
-            data_url: Some(format!("data:image/png;base64,{}", data)),
+            data_url: Some(format!("data:{};base64,{}", options.format.mime_type(), data)),
             width: Some(width),
             height: Some(height),
             error: None,
@@ -37,6 +65,47 @@ pub async fn capture_screen() -> Result<ScreenshotResult, String> {
     }
 }
 
+/// Turn the capture master switch on or off. While off every capture path
+/// refuses with `permission_denied` and the observe loop skips its frames.
+/// The switch takes effect before it is saved, so a failed save never
+/// leaves capture running against the user's choice.
+#[command]
+pub async fn set_capture_enabled(
+    enabled: bool,
+    state: State<'_, Arc<AppState>>,
+) -> Result<bool, HawkeyeError> {
+    *state.observation_enabled.write().await = enabled;
+    if !enabled {
+        clear_recent_frames(&state).await;
+    }
+    {
+        let mut current = state.config.write().await;
+        let mut updated = current.clone();
+        updated.observation_enabled = enabled;
+
+        crate::config::save_config(&updated)?;
+        *current = updated;
+    }
+    log::info!(
+        "[Perception] Screen capture {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    let sink: SharedSink = state
+        .event_sink
+        .read()
+        .await
+        .clone()
+        .unwrap_or_else(|| Arc::new(NoopSink));
+    sink.emit(events::CAPTURE_ENABLED_CHANGED, serde_json::json!(enabled));
+    Ok(enabled)
+}
+
+/// Whether the capture master switch is on
+#[command]
+pub async fn is_capture_enabled(state: State<'_, Arc<AppState>>) -> Result<bool, HawkeyeError> {
+    Ok(*state.observation_enabled.read().await)
+}
+
 /// OCR result
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -48,10 +117,33 @@ pub struct OcrResultResponse {
     pub error: Option<String>,
 }
 
-/// Run OCR on a base64-encoded image
+/// Build OCR options from config, overridden by per-call arguments
+async fn ocr_options(
+    state: &AppState,
+    languages: Option<Vec<String>>,
+    recognition_level: Option<RecognitionLevel>,
+) -> OcrOptions {
+    let mut options = state.ocr_options().await;
+    if let Some(languages) = languages {
+        options.languages = languages;
+    }
+    if let Some(level) = recognition_level {
+        options.recognition_level = level;
+    }
+    options
+}
+
+/// Run OCR on a base64-encoded image. `languages` and `recognition_level`
+/// default to the configured OCR settings.
 #[command]
-pub async fn run_ocr(image_base64: String) -> Result<OcrResultResponse, String> {
-    match perception::ocr::run_ocr(&image_base64).await {
+pub async fn run_ocr(
+    state: State<'_, Arc<AppState>>,
+    image_base64: String,
+    languages: Option<Vec<String>>,
+    recognition_level: Option<RecognitionLevel>,
+) -> Result<OcrResultResponse, HawkeyeError> {
+    let options = ocr_options(&state, languages, recognition_level).await;
+    match perception::ocr::run_ocr(&image_base64, &options).await {
         Ok(result) => Ok(OcrResultResponse {
             success: true,
             text: Some(result.text),
@@ -69,16 +161,108 @@ pub async fn run_ocr(image_base64: String) -> Result<OcrResultResponse, String>
     }
 }
 
-/// Get clipboard content
+/// Structured OCR result — every region with its bounding box
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrRegionsResponse {
+    pub success: bool,
+    pub regions: Vec<OcrRegion>,
+    pub duration_ms: u64,
+    pub backend: String,
+    pub image_width: u32,
+    pub image_height: u32,
+    pub error: Option<String>,
+}
+
+/// Run OCR on a base64-encoded image and return the individual regions
+/// (normalized bounding boxes + confidence) instead of flattened text
+#[command]
+pub async fn run_ocr_regions(
+    state: State<'_, Arc<AppState>>,
+    image_base64: String,
+    languages: Option<Vec<String>>,
+    recognition_level: Option<RecognitionLevel>,
+) -> Result<OcrRegionsResponse, HawkeyeError> {
+    let options = ocr_options(&state, languages, recognition_level).await;
+    match perception::ocr::run_ocr(&image_base64, &options).await {
+        Ok(result) => Ok(OcrRegionsResponse {
+            success: true,
+            regions: result.regions,
+            duration_ms: result.duration_ms,
+            backend: result.backend,
+            image_width: result.image_width,
+            image_height: result.image_height,
+            error: None,
+        }),
+        Err(e) => Ok(OcrRegionsResponse {
+            success: false,
+            regions: Vec::new(),
+            duration_ms: 0,
+            backend: "none".to_string(),
+            image_width: 0,
+            image_height: 0,
+            error: Some(e.to_string()),
+        }),
+    }
+}
+
+/// Re-run OCR on the last observed frame, e.g. to compare languages or
+/// recognition levels without re-capturing. `min_confidence` drops
+/// low-confidence regions from the result.
+#[command]
+pub async fn reocr_last_observation(
+    state: State<'_, Arc<AppState>>,
+    languages: Option<Vec<String>>,
+    recognition_level: Option<RecognitionLevel>,
+    min_confidence: Option<f32>,
+) -> Result<OcrResult, HawkeyeError> {
+    let screenshot = state
+        .last_observation
+        .read()
+        .await
+        .as_ref()
+        .and_then(|o| o.screenshot_base64.clone())
+        .ok_or_else(|| {
+            HawkeyeError::NotFound("No full screenshot retained from the last observation".into())
+        })?;
+
+    let options = ocr_options(&state, languages, recognition_level).await;
+    let mut result = perception::ocr::run_ocr(&screenshot, &options).await?;
+    if let Some(min) = min_confidence {
+        result.retain_confident(min);
+    }
+    Ok(result)
+}
+
+/// List connected displays in capture order, with their global origins, so
+/// normalized OCR boxes can be mapped to desktop coordinates
+#[command]
+pub async fn list_monitors() -> Result<Vec<perception::screen::MonitorInfo>, HawkeyeError> {
+    perception::screen::list_monitors().map_err(HawkeyeError::from)
+}
+
+/// Resolution, scale factor and origin of each display, without capturing
 #[command]
-pub async fn get_clipboard() -> Result<Option<String>, String> {
-    // Clipboard access via Tauri plugin — needs app handle
-    // Will be properly wired in a future step
-    Ok(None)
+pub async fn get_display_info() -> Result<Vec<perception::screen::DisplayInfo>, HawkeyeError> {
+    Ok(perception::screen::display_info()?)
+}
+
+/// Get clipboard text. Returns `None` when the clipboard is empty or holds
+/// non-text content.
+#[command]
+pub async fn get_clipboard(app: AppHandle) -> Result<Option<String>, HawkeyeError> {
+    match app.clipboard().read_text() {
+        Ok(text) if !text.is_empty() => Ok(Some(text)),
+        Ok(_) => Ok(None),
+        Err(e) => {
+            log::debug!("[Clipboard] No text available: {}", e);
+            Ok(None)
+        }
+    }
 }
 
 /// Active window info response
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WindowInfoResponse {
     pub app_name: String,
@@ -86,16 +270,159 @@ pub struct WindowInfoResponse {
     pub bundle_id: Option<String>,
 }
 
-/// Get the currently active window
+/// A window captured by [`capture_named_window`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedWindowCapture {
+    #[serde(flatten)]
+    pub window: NamedWindow,
+    /// PNG, base64-encoded
+    pub image_base64: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Capture one app's window (by app name or bundle id, optionally narrowed
+/// by a title substring) even when it is behind other windows. macOS only.
+#[command]
+pub async fn capture_named_window(
+    state: State<'_, Arc<AppState>>,
+    app: String,
+    title: Option<String>,
+) -> Result<NamedWindowCapture, HawkeyeError> {
+    state.ensure_capture_enabled().await?;
+    let (window, image) = capture_window(WindowQuery { app, title }).await?;
+    let options = CaptureOptions::from_config(&*state.config.read().await);
+    let png = perception::screen::encode_rgba(&image, options)?;
+    Ok(NamedWindowCapture {
+        window,
+        image_base64: STANDARD.encode(png),
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// Get the currently active window. Results are cached briefly; pass
+/// `force` to bypass the cache.
 #[command]
-pub async fn get_active_window() -> Result<Option<WindowInfoResponse>, String> {
-    match perception::window::get_active_window().await {
+pub async fn get_active_window(force: Option<bool>) -> Result<Option<WindowInfoResponse>, HawkeyeError> {
+    match perception::window::get_active_window(force.unwrap_or(false)).await {
         Ok(Some(info)) => Ok(Some(WindowInfoResponse {
             app_name: info.app_name,
             title: info.title,
             bundle_id: info.bundle_id,
         })),
         Ok(None) => Ok(None),
-        Err(e) => Err(e.to_string()),
+        Err(e) => Err(e.into()),
     }
 }
+
+/// Screen, OCR and active window from a single capture
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureContext {
+    pub width: u32,
+    pub height: u32,
+    /// PNG data URL; only when `include_image` was set
+    pub image_data_url: Option<String>,
+    pub ocr: Option<OcrResult>,
+    /// Why `ocr` is missing: helper unavailable or OCR failed
+    pub ocr_error: Option<String>,
+    pub window: Option<WindowInfoResponse>,
+    pub window_error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// One-shot context snapshot: capture the screen once, OCR those pixels
+/// and look up the active window concurrently, all without the image
+/// crossing IPC. OCR and window failures are reported in the result
+/// rather than failing the call; only a failed capture is an error.
+#[command]
+pub async fn capture_context(
+    state: State<'_, Arc<AppState>>,
+    include_image: Option<bool>,
+) -> Result<CaptureContext, HawkeyeError> {
+    state.ensure_capture_enabled().await?;
+    let start = Instant::now();
+    let options = CaptureOptions::from_config(&*state.config.read().await);
+    let (data, width, height) = perception::screen::capture_screenshot(options).await?;
+
+    let ocr_available = *state.ocr_available.read().await;
+    let ocr_options = state.ocr_options().await;
+    let ocr = async {
+        if ocr_available {
+            perception::ocr::run_ocr_for_monitor(&data, 0, &ocr_options).await
+        } else {
+            Err(anyhow::anyhow!(perception::ocr::MISSING_HELPER_HINT))
+        }
+    };
+    let (ocr, window) = tokio::join!(ocr, perception::window::get_active_window(false));
+    let (ocr, ocr_error) = match ocr {
+        Ok(result) => (Some(result), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let (window, window_error) = match window {
+        Ok(info) => (
+            info.map(|info| WindowInfoResponse {
+                app_name: info.app_name,
+                title: info.title,
+                bundle_id: info.bundle_id,
+            }),
+            None,
+        ),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    let context = CaptureContext {
+        width,
+        height,
+        image_data_url: include_image
+            .unwrap_or(false)
+            .then(|| format!("data:{};base64,{}", options.format.mime_type(), data)),
+        ocr,
+        ocr_error,
+        window,
+        window_error,
+        duration_ms: start.elapsed().as_millis() as u64,
+    };
+
+    let sink: SharedSink = state
+        .event_sink
+        .read()
+        .await
+        .clone()
+        .unwrap_or_else(|| Arc::new(NoopSink));
+    record_debug(
+        sink.as_ref(),
+        &state,
+        DebugEventType::Observe,
+        "Context captured",
+        serde_json::json!({
+            "width": width,
+            "height": height,
+            "ocrChars": context.ocr.as_ref().map(|o| o.text.chars().count()),
+            "ocrError": context.ocr_error,
+            "app": context.window.as_ref().map(|w| w.app_name.clone()),
+            "windowError": context.window_error,
+        }),
+        Some(context.duration_ms),
+        None,
+    )
+    .await;
+
+    Ok(context)
+}
+
+/// Check macOS privacy permissions (screen recording, accessibility,
+/// microphone, speech recognition) without prompting
+#[command]
+pub async fn check_permissions() -> Result<PermissionStatus, HawkeyeError> {
+    Ok(perception::permissions::check_permissions())
+}
+
+/// Trigger the system prompt for one permission. Returns the state at call
+/// time; re-check once the user has responded.
+#[command]
+pub async fn request_permission(kind: PermissionKind) -> Result<PermissionState, HawkeyeError> {
+    Ok(perception::permissions::request_permission(kind))
+}
@@ -0,0 +1,40 @@
+//! Granular data resets — clear one piece of in-memory state at a time, so
+//! a settings "reset data" screen (or a test run) can start from a fresh
+//! baseline without wiping everything. See also `rebuild_life_tree` and
+//! `clear_debug_events`.
+
+use std::sync::Arc;
+use tauri::{command, State};
+
+use crate::error::HawkeyeError;
+use crate::state::AppState;
+
+/// Reset the adaptive refresh score to its baseline and drop recent events
+#[command]
+pub async fn reset_adaptive(
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), HawkeyeError> {
+    state.adaptive_refresh.write().await.reset();
+    log::info!("[Reset] Adaptive refresh reset");
+    Ok(())
+}
+
+/// Drop all activity log entries and summaries
+#[command]
+pub async fn clear_activity_log(
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), HawkeyeError> {
+    state.activity_log.write().await.clear();
+    log::info!("[Reset] Activity log cleared");
+    Ok(())
+}
+
+/// Forget recently recognized intents
+#[command]
+pub async fn clear_intents(
+    state: State<'_, Arc<AppState>>,
+) -> Result<(), HawkeyeError> {
+    state.intent_recognizer.write().await.clear_recent();
+    log::info!("[Reset] Intent history cleared");
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! State snapshot commands — export/import the whole app state as a zip
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{command, State};
+
+use crate::clock::{Clock, SystemClock};
+use crate::error::HawkeyeError;
+use crate::snapshot::{self, SnapshotReport};
+use crate::state::AppState;
+
+/// Write config (credentials blanked), life tree, activity log, adaptive
+/// refresh state, model manifest and conversations to a zip at `path`
+#[command]
+pub async fn export_state(
+    state: State<'_, Arc<AppState>>,
+    path: String,
+) -> Result<SnapshotReport, HawkeyeError> {
+    let now = SystemClock.now_ms();
+    let captured = snapshot::capture(&state, now).await?;
+    let path = PathBuf::from(path);
+    let report = tokio::task::spawn_blocking(move || snapshot::write_archive(&path, &captured))
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))??;
+    log::info!("[Snapshot] Exported state to {}", report.path);
+    Ok(report)
+}
+
+/// Restore a zip written by `export_state`. The archive is fully validated
+/// first; the observe loop is stopped before anything is replaced.
+#[command]
+pub async fn import_state(
+    state: State<'_, Arc<AppState>>,
+    path: String,
+) -> Result<SnapshotReport, HawkeyeError> {
+    let path = PathBuf::from(path);
+    let read_path = path.clone();
+    let imported = tokio::task::spawn_blocking(move || snapshot::read_archive(&read_path))
+        .await
+        .map_err(|e| format!("Import task failed: {}", e))??;
+
+    let report = SnapshotReport::new(&path, &imported);
+    snapshot::restore(&state, imported).await?;
+    log::info!(
+        "[Snapshot] Imported state from {} (written by Hawkeye {})",
+        report.path,
+        report.app_version
+    );
+    Ok(report)
+}
@@ -4,6 +4,8 @@ use serde::Serialize;
 use std::sync::Arc;
 use tauri::{command, State};
 
+use crate::error::HawkeyeError;
+use crate::perception::permissions::{self, PermissionStatus};
 use crate::state::AppState;
 
 /// Application status
@@ -13,27 +15,43 @@ pub struct HawkeyeStatus {
     pub initialized: bool,
     pub ai_ready: bool,
     pub ai_provider: Option<String>,
+    /// Model of the active client, or the configured one when none is ready
+    pub ai_model: Option<String>,
     pub observe_running: bool,
+    /// TCC permission grants, so onboarding can guide the user
+    pub permissions: PermissionStatus,
+    /// `false` when the OCR helper is missing; observation runs without text
+    pub ocr_available: bool,
 }
 
 /// Get application status
 #[command]
-pub async fn get_status(state: State<'_, Arc<AppState>>) -> Result<HawkeyeStatus, String> {
-    let ai = state.ai_client.read().await;
-    let ai_ready = ai.is_some();
-    let active_provider = ai.as_ref().map(|c| c.provider_name().to_string());
-    drop(ai);
+pub async fn get_status(state: State<'_, Arc<AppState>>) -> Result<HawkeyeStatus, HawkeyeError> {
+    Ok(current_status(&state).await)
+}
+
+/// Snapshot of the application status, also sent with `status:changed`
+pub async fn current_status(state: &AppState) -> HawkeyeStatus {
+    let active_provider = state.ai_provider().await.ok();
+    let ai_ready = active_provider.is_some();
+    let active_model = active_provider.as_ref().map(|c| c.model_name().to_string());
+    let active_provider = active_provider.map(|c| c.provider_name().to_string());
 
     let config = state.config.read().await;
     let provider = active_provider.unwrap_or_else(|| config.ai_provider.clone());
+    let model = active_model.or_else(|| config.model().map(str::to_string));
     drop(config);
 
     let observe_running = state.observe_loop.read().await.is_some();
+    let ocr_available = *state.ocr_available.read().await;
 
-    Ok(HawkeyeStatus {
+    HawkeyeStatus {
         initialized: true,
         ai_ready,
         ai_provider: Some(provider),
+        ai_model: model,
         observe_running,
-    })
+        permissions: permissions::check_permissions(),
+        ocr_available,
+    }
 }
@@ -3,87 +3,141 @@
 use std::sync::Arc;
 use tauri::{command, State};
 
-use crate::ai::ChatMessage;
-use crate::observe::activity_log::ActivitySummary;
+use crate::ai::rate_limit::{self, RequestPriority};
+use crate::ai::{AiProvider, ChatMessage, Persona};
+use crate::clock::{Clock, SystemClock};
+use crate::error::HawkeyeError;
+use crate::observe::activity_export::{self, ExportFormat, ExportReport};
+use crate::observe::activity_log::{
+    batch_ranges, rank_apps, ActivityEntry, ActivitySummary, DEFAULT_SUMMARY_MAX_ENTRIES,
+    DEFAULT_SUMMARY_MIN_ENTRIES,
+};
 use crate::state::AppState;
 
-/// Generate an AI summary of pending (unsummarized) activity entries
+/// Generate AI summaries of pending (unsummarized) activity entries.
+///
+/// Fewer than `summary_min_entries` pending entries is rejected; more than
+/// `summary_max_entries` are split into several summaries, oldest first.
+/// With `defer_if_unavailable` and no AI provider yet, the request is
+/// remembered and run once `init_ai` succeeds (the result arrives as
+/// `activity:summary-generated`); the call itself still fails with
+/// `ai_not_initialized`.
 #[command]
 pub async fn generate_summary(
+    defer_if_unavailable: Option<bool>,
     state: State<'_, Arc<AppState>>,
-) -> Result<ActivitySummary, String> {
+) -> Result<Vec<ActivitySummary>, HawkeyeError> {
+    let result = summarize_pending(&state).await;
+    if matches!(result, Err(HawkeyeError::AiNotInitialized))
+        && defer_if_unavailable.unwrap_or(false)
+    {
+        log::info!("[Summarizer] No AI provider yet; deferring summary until init_ai");
+        *state.deferred_summary.write().await = true;
+    }
+    result
+}
+
+/// Summarize pending activity entries with the active provider
+pub async fn summarize_pending(state: &AppState) -> Result<Vec<ActivitySummary>, HawkeyeError> {
+    let (min_entries, max_entries) = {
+        let config = state.config.read().await;
+        let min = config.summary_min_entries.unwrap_or(DEFAULT_SUMMARY_MIN_ENTRIES).max(1);
+        let max = config.summary_max_entries.unwrap_or(DEFAULT_SUMMARY_MAX_ENTRIES);
+        (min, max.max(min))
+    };
+
     // Get pending entries
-    let (formatted_text, entry_count, period_start, period_end, top_apps) = {
+    let pending: Vec<ActivityEntry> = {
         let log = state.activity_log.read().await;
-        let pending = log.pending_entries();
+        log.pending_entries().into_iter().cloned().collect()
+    };
 
-        if pending.is_empty() {
-            return Err("No pending activity entries to summarize".to_string());
-        }
+    if pending.is_empty() {
+        return Err("No pending activity entries to summarize".into());
+    }
+    if pending.len() < min_entries {
+        return Err(format!(
+            "Only {} pending activity entries; at least {} are needed for a summary",
+            pending.len(),
+            min_entries
+        )
+        .into());
+    }
 
-        let entry_count = pending.len();
-        let period_start = pending.first().map(|e| e.timestamp).unwrap_or(0);
-        let period_end = pending.last().map(|e| e.timestamp).unwrap_or(0);
+    let client = state.ai_provider().await?;
 
-        // Count app occurrences for top_apps
-        let mut app_counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
-        for entry in &pending {
-            if let Some(app) = &entry.app_name {
-                *app_counts.entry(app.clone()).or_insert(0) += 1;
+    let mut summaries = Vec::new();
+    for range in batch_ranges(pending.len(), max_entries) {
+        let batch: Vec<&ActivityEntry> = pending[range].iter().collect();
+        match summarize_batch(state, client.as_ref(), &batch).await {
+            Ok(summary) => {
+                // Stored as we go, so a failed later batch stays pending
+                state.activity_log.write().await.add_summary(summary.clone());
+                summaries.push(summary);
+            }
+            Err(e) if summaries.is_empty() => return Err(e),
+            Err(e) => {
+                log::warn!("[Summarizer] Stopping after {} batch(es): {}", summaries.len(), e);
+                break;
             }
         }
-        let mut apps: Vec<(String, usize)> = app_counts.into_iter().collect();
-        apps.sort_by(|a, b| b.1.cmp(&a.1));
-        let top_apps: Vec<String> = apps.into_iter().take(5).map(|(name, _)| name).collect();
+    }
 
-        let formatted = log.format_for_ai(&pending);
-        (formatted, entry_count, period_start, period_end, top_apps)
-    };
+    Ok(summaries)
+}
+
+/// Summarize one batch of entries with a single AI call
+async fn summarize_batch(
+    state: &AppState,
+    client: &dyn AiProvider,
+    entries: &[&ActivityEntry],
+) -> Result<ActivitySummary, HawkeyeError> {
+    let entry_count = entries.len();
+    let period_start = entries.first().map(|e| e.timestamp).unwrap_or(0);
+    let period_end = entries.last().map(|e| e.end_timestamp).unwrap_or(0);
+
+    let app_usage = rank_apps(entries, 5);
+    let top_apps: Vec<String> = app_usage.iter().map(|u| u.app_name.clone()).collect();
 
-    // Check AI is ready
-    let ai = state.ai_client.read().await;
-    let client = ai
-        .as_ref()
-        .ok_or_else(|| "AI not initialized. Configure API key in settings.".to_string())?;
+    let formatted_text = state.activity_log.read().await.format_for_ai(entries);
+
+    // Summaries are background work: skip rather than queue when limited
+    if !rate_limit::acquire(state, client.provider_name(), RequestPriority::Background).await {
+        return Err(rate_limit::limited_error(client.provider_name()));
+    }
 
     // Build prompt
+    let persona = Persona::from_config(&*state.config.read().await);
     let prompt = format!(
         "You are Hawkeye, a desktop activity monitor. Summarize the following user activity log in 2-3 concise sentences. \
          Focus on what the user was doing, which apps they used, and any notable patterns. \
          Be specific about the content they were working on based on window titles and OCR text.\n\n\
          Activity Log ({} entries):\n{}\n\n\
-         Respond with ONLY the summary text, no headers or formatting.",
-        entry_count, formatted_text
+         Respond with ONLY the summary text, no headers or formatting. {}",
+        entry_count,
+        formatted_text,
+        persona.language.instruction()
     );
+    let prompt = persona.style_prompt(prompt);
 
     let messages = vec![ChatMessage {
         role: "user".to_string(),
         content: prompt,
     }];
 
-    let response = client.chat(messages).await.map_err(|e| e.to_string())?;
+    let response = client.chat(messages).await?;
 
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64;
+    let now = SystemClock.now_ms();
 
-    let summary = ActivitySummary {
+    Ok(ActivitySummary {
         summary: response.text,
         period_start,
         period_end,
         entry_count,
         top_apps,
+        app_usage,
         generated_at: now,
-    };
-
-    // Store the summary
-    {
-        let mut log = state.activity_log.write().await;
-        log.add_summary(summary.clone());
-    }
-
-    Ok(summary)
+    })
 }
 
 /// Get recent activity summaries
@@ -91,17 +145,63 @@ pub async fn generate_summary(
 pub async fn get_recent_summaries(
     count: Option<usize>,
     state: State<'_, Arc<AppState>>,
-) -> Result<Vec<ActivitySummary>, String> {
+) -> Result<Vec<ActivitySummary>, HawkeyeError> {
     let log = state.activity_log.read().await;
     let summaries = log.recent_summaries(count.unwrap_or(10));
     Ok(summaries.to_vec())
 }
 
+/// Page through raw activity entries, newest first. `since_ms` drops
+/// entries that ended before that timestamp.
+#[command]
+pub async fn get_activity_entries(
+    offset: usize,
+    limit: usize,
+    since_ms: Option<u64>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ActivityEntriesPage, HawkeyeError> {
+    let log = state.activity_log.read().await;
+    let (entries, total) = log.page(offset, limit, since_ms);
+    Ok(ActivityEntriesPage { entries, total })
+}
+
+/// Export activity entries (and optionally summaries) to `path` as JSON or
+/// CSV. `start_ms`/`end_ms` limit the export to a date range.
+#[command]
+pub async fn export_activity(
+    format: ExportFormat,
+    path: String,
+    include_summaries: Option<bool>,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+    state: State<'_, Arc<AppState>>,
+) -> Result<ExportReport, HawkeyeError> {
+    let start = start_ms.unwrap_or(0);
+    let end = end_ms.unwrap_or(u64::MAX);
+
+    // Copy out so the file is written without holding the log lock
+    let (entries, summaries) = {
+        let log = state.activity_log.read().await;
+        let entries: Vec<ActivityEntry> =
+            log.entries_in_range(start, end).into_iter().cloned().collect();
+        let summaries = if include_summaries.unwrap_or(false) {
+            log.summaries_in_range(start, end)
+        } else {
+            Vec::new()
+        };
+        (entries, summaries)
+    };
+
+    let entries: Vec<&ActivityEntry> = entries.iter().collect();
+    activity_export::export(&entries, &summaries, format, std::path::Path::new(&path))
+        .map_err(|e| format!("Failed to export activity: {}", e).into())
+}
+
 /// Get current activity log stats
 #[command]
 pub async fn get_activity_stats(
     state: State<'_, Arc<AppState>>,
-) -> Result<ActivityStats, String> {
+) -> Result<ActivityStats, HawkeyeError> {
     let log = state.activity_log.read().await;
     let pending = log.pending_entries();
     Ok(ActivityStats {
@@ -120,3 +220,11 @@ pub struct ActivityStats {
     pub oldest_pending: Option<u64>,
     pub newest_pending: Option<u64>,
 }
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntriesPage {
+    pub entries: Vec<ActivityEntry>,
+    /// Matching entries across all pages
+    pub total: usize,
+}
@@ -4,6 +4,7 @@ use std::sync::Arc;
 use tauri::{command, AppHandle, Emitter, State};
 use tauri_plugin_updater::UpdaterExt;
 
+use crate::error::HawkeyeError;
 use crate::events;
 use crate::state::AppState;
 
@@ -20,7 +21,7 @@ pub struct UpdateCheckResult {
 
 /// Check for updates
 #[command]
-pub async fn check_for_update(app: AppHandle) -> Result<UpdateCheckResult, String> {
+pub async fn check_for_update(app: AppHandle) -> Result<UpdateCheckResult, HawkeyeError> {
     let current_version = app
         .config()
         .version
@@ -62,14 +63,14 @@ pub async fn check_for_update(app: AppHandle) -> Result<UpdateCheckResult, Strin
         }
         Err(e) => {
             log::warn!("[Updater] Check failed: {}", e);
-            Err(format!("Update check failed: {}", e))
+            Err(format!("Update check failed: {}", e).into())
         }
     }
 }
 
 /// Download and install update
 #[command]
-pub async fn install_update(app: AppHandle) -> Result<(), String> {
+pub async fn install_update(app: AppHandle) -> Result<(), HawkeyeError> {
     let updater = app.updater().map_err(|e| e.to_string())?;
 
     let update = updater
@@ -111,7 +112,7 @@ pub async fn install_update(app: AppHandle) -> Result<(), String> {
 
 /// Get current app version
 #[command]
-pub async fn get_app_version(app: AppHandle) -> Result<String, String> {
+pub async fn get_app_version(app: AppHandle) -> Result<String, HawkeyeError> {
     Ok(app
         .config()
         .version
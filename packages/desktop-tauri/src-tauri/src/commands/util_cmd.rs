@@ -2,8 +2,10 @@
 
 use tauri::command;
 
+use crate::error::HawkeyeError;
+
 /// Open a URL in the default browser
 #[command]
-pub async fn open_url(url: String) -> Result<(), String> {
-    open::that(&url).map_err(|e| e.to_string())
+pub async fn open_url(url: String) -> Result<(), HawkeyeError> {
+    open::that(&url).map_err(HawkeyeError::from)
 }
@@ -1,30 +1,128 @@
 //! Voice pipeline commands — speech recognition via macOS native APIs
 
-use tauri::command;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{command, AppHandle, State};
 
+use crate::ai::rate_limit::{self, RequestPriority};
+use crate::ai::ChatMessage;
+use crate::commands::debug_cmd::{record_debug, DebugEventType};
+use crate::error::HawkeyeError;
+use crate::event_sink::{SharedSink, TauriSink};
+use crate::state::AppState;
+use crate::voice::notes::{self, Transcriber};
 use crate::voice::speech;
 
+/// Transcript and AI summary of a recorded voice note
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceNoteSummary {
+    pub transcript: String,
+    /// Bullet-point summary from the active AI provider
+    pub summary: String,
+    /// "native" or "whisper"
+    pub engine: String,
+    pub chunks: usize,
+}
+
 /// Check speech recognition availability
 #[command]
-pub async fn speech_status() -> Result<speech::SpeechStatus, String> {
-    speech::check_status().await.map_err(|e| e.to_string())
+pub async fn speech_status() -> Result<speech::SpeechStatus, HawkeyeError> {
+    speech::check_status().await.map_err(HawkeyeError::from)
 }
 
 /// Listen from microphone for specified duration and transcribe
 #[command]
 pub async fn speech_listen(
     duration_secs: Option<u32>,
-) -> Result<speech::SpeechResult, String> {
+) -> Result<speech::SpeechResult, HawkeyeError> {
     let duration = duration_secs.unwrap_or(5);
-    speech::listen(duration).await.map_err(|e| e.to_string())
+    speech::listen(duration).await.map_err(HawkeyeError::from)
 }
 
 /// Transcribe an audio file
 #[command]
 pub async fn speech_transcribe_file(
     audio_path: String,
-) -> Result<speech::SpeechResult, String> {
+) -> Result<speech::SpeechResult, HawkeyeError> {
     speech::transcribe_file(&audio_path)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(HawkeyeError::from)
+}
+
+/// Transcribe a recording (whisper.cpp when available, else native) and
+/// summarize it with the active AI provider — e.g. for meeting notes
+#[command]
+pub async fn transcribe_and_summarize(
+    audio_path: String,
+    state: State<'_, Arc<AppState>>,
+    app: AppHandle,
+) -> Result<VoiceNoteSummary, HawkeyeError> {
+    let provider = state.ai_provider().await?;
+
+    let sink: SharedSink = state
+        .event_sink
+        .read()
+        .await
+        .clone()
+        .unwrap_or_else(|| -> SharedSink { Arc::new(TauriSink::new(app)) });
+
+    let transcriber = {
+        let models = state.model_manager.read().await.list_models().unwrap_or_default();
+        Transcriber::detect(&models)
+    };
+
+    let start = Instant::now();
+    let transcript = notes::transcribe_long(&transcriber, Path::new(&audio_path)).await?;
+    record_debug(
+        sink.as_ref(),
+        &state,
+        DebugEventType::SpeechSegment,
+        "Voice note transcribed",
+        serde_json::json!({
+            "engine": transcriber.name(),
+            "chunks": transcript.chunks,
+            "chars": transcript.text.chars().count(),
+        }),
+        Some(start.elapsed().as_millis() as u64),
+        None,
+    )
+    .await;
+
+    if transcript.text.is_empty() {
+        return Err("No speech recognized in recording".into());
+    }
+
+    let messages = vec![ChatMessage {
+        role: "user".to_string(),
+        content: format!(
+            "Summarize this voice note as concise bullet points. Capture decisions, \
+             action items, and open questions. Respond with only the bullets.\n\n\
+             Transcript:\n{}",
+            transcript.text
+        ),
+    }];
+    if !rate_limit::acquire(&state, provider.provider_name(), RequestPriority::Interactive).await {
+        return Err(rate_limit::limited_error(provider.provider_name()));
+    }
+    let response = provider.chat(messages).await?;
+    record_debug(
+        sink.as_ref(),
+        &state,
+        DebugEventType::LlmOutput,
+        "Voice note summary",
+        serde_json::json!({ "text": response.text, "usage": response.usage }),
+        Some(response.duration_ms),
+        None,
+    )
+    .await;
+
+    Ok(VoiceNoteSummary {
+        transcript: transcript.text,
+        summary: response.text,
+        engine: transcriber.name().to_string(),
+        chunks: transcript.chunks,
+    })
 }
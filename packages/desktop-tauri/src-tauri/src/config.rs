@@ -2,9 +2,34 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::ai::history::TrimStrategy;
+use crate::life_tree::PhaseThresholds;
+use crate::observe::media::MediaSampling;
+use crate::perception::ocr::RecognitionLevel;
+use crate::perception::screen::PngCompression;
+
+/// Named AI backend preset (e.g. "OpenRouter", "Local LM Studio")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderProfile {
+    pub name: String,
+    /// Provider type, same values as `AppConfig::ai_provider`
+    pub provider: String,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+    /// Key name in `AppConfig::api_keys`. Profiles never hold the key itself;
+    /// `None` keeps the provider's current key.
+    pub api_key_ref: Option<String>,
+}
+
+fn default_observation_enabled() -> bool {
+    true
+}
+
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -22,14 +47,141 @@ pub struct AppConfig {
     pub openai_api_key: Option<String>,
     pub openai_model: Option<String>,
 
+    /// Network settings for AI provider requests
+    pub request_timeout_secs: Option<u64>,
+    pub http_proxy: Option<String>,
+    /// Log AI request/response metadata (keys redacted) and mirror it to
+    /// the debug timeline
+    #[serde(default)]
+    pub log_ai_requests: bool,
+
+    /// Assistant persona: `system_prompt`, `personality` and a set `language`
+    /// lead every chat; `personality` and `language` also shape summaries,
+    /// intents and experiments
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    #[serde(default)]
+    pub personality: Option<String>,
+    /// Reply language for chat, summaries, intents and experiments, as a
+    /// name or code ("Chinese", "zh-CN"). Unset means English for summaries,
+    /// intents and experiments; chat then follows the user's language.
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// Chat: how to shrink history that exceeds the model's context
+    #[serde(default)]
+    pub history_trim_strategy: TrimStrategy,
+
+    /// Per-provider request caps (provider name → requests per minute).
+    /// Providers without an entry are unlimited.
+    #[serde(default)]
+    pub ai_requests_per_minute: HashMap<String, u32>,
+
+    /// Hugging Face access token, sent only to huggingface.co when
+    /// downloading gated models
+    #[serde(default)]
+    pub hf_token: Option<String>,
+
+    /// Store models and the life tree here instead of the platform data
+    /// directory. `HAWKEYE_DATA_DIR` takes precedence.
+    #[serde(default)]
+    pub data_dir_override: Option<PathBuf>,
+    /// Keep downloaded models here (e.g. an external disk) instead of
+    /// `<data dir>/models`. Change it with `set_models_dir`, which moves
+    /// existing models.
+    #[serde(default)]
+    pub models_dir_override: Option<PathBuf>,
+
     /// Sync settings
     pub sync_port: u16,
     pub auto_start_sync: bool,
 
     /// App settings
     pub auto_update: bool,
+    /// Start the observe loop at launch, once perception is initialized
+    #[serde(default)]
+    pub auto_start_observe: bool,
+    /// Mirrors the OS login item, which is the source of truth
+    #[serde(default)]
+    pub launch_at_login: bool,
+    /// Screen capture master switch, restored at launch. Missing means on.
+    #[serde(default = "default_observation_enabled")]
+    pub observation_enabled: bool,
     pub local_only: bool,
     pub onboarding_completed: Option<bool>,
+
+    /// Life tree: successful experiments needed to unlock each phase
+    #[serde(default)]
+    pub experiment_phase_thresholds: PhaseThresholds,
+
+    /// Summarizer batch bounds (pending activity entries per AI call).
+    /// Every call repeats the prompt preamble, so tiny batches spend most of
+    /// their tokens on overhead; oversized batches balloon the prompt and get
+    /// vaguer summaries. `None` uses the defaults in `observe::activity_log`.
+    #[serde(default)]
+    pub summary_min_entries: Option<usize>,
+    #[serde(default)]
+    pub summary_max_entries: Option<usize>,
+
+    /// Observe loop: periodically refine rule-based intents with one AI call
+    #[serde(default)]
+    pub ai_intent_enhancement: bool,
+    /// Observe loop: hash and OCR only the focused window. Falls back to the
+    /// full screen when the platform can't report window bounds.
+    #[serde(default)]
+    pub observe_active_window_only: bool,
+    /// Observe loop: drop changed frames whose app, window title and OCR
+    /// text match the previous observation (flickery UIs, animations)
+    #[serde(default)]
+    pub observe_skip_duplicates: bool,
+    /// Recorded frames kept on disk for `get_recent_observations`
+    /// (None = 20, 0 = off)
+    #[serde(default)]
+    pub recent_frames_count: Option<usize>,
+    /// Keep those frames when the loop stops instead of clearing them
+    #[serde(default)]
+    pub retain_recent_frames: bool,
+    /// Observe loop: slow down while a video plays (sustained heavy change
+    /// in one window)
+    #[serde(default)]
+    pub media_sampling: MediaSampling,
+
+    /// OCR: Vision recognition languages (empty = auto-detect) and level
+    #[serde(default)]
+    pub ocr_languages: Vec<String>,
+    #[serde(default)]
+    pub ocr_recognition_level: RecognitionLevel,
+    /// OCR: words dropped from recognized text (whole tokens, case-insensitive)
+    /// and regexes whose matches are removed, e.g. IDE line numbers. Saving
+    /// a pattern that doesn't compile is refused.
+    #[serde(default)]
+    pub ocr_stopwords: Vec<String>,
+    #[serde(default)]
+    pub ocr_filter_patterns: Vec<String>,
+    /// OCR: seconds before a stuck helper is killed (default 5)
+    #[serde(default)]
+    pub ocr_timeout_secs: Option<u64>,
+
+    /// Capture: PNG compression for screenshots sent to vision models, and
+    /// the quality used when a capture asks for JPEG (default 80)
+    #[serde(default)]
+    pub capture_png_compression: PngCompression,
+    #[serde(default)]
+    pub capture_jpeg_quality: Option<u8>,
+
+    /// Privacy: apps (by name) whose clipboard is never read
+    #[serde(default)]
+    pub excluded_apps: Vec<String>,
+
+    /// Saved provider presets. The flat provider fields above remain the
+    /// current selection; activating a profile copies its values into them.
+    #[serde(default)]
+    pub provider_profiles: Vec<ProviderProfile>,
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Named API keys referenced by `ProviderProfile::api_key_ref`
+    #[serde(default)]
+    pub api_keys: HashMap<String, String>,
 }
 
 impl Default for AppConfig {
@@ -42,20 +194,135 @@ impl Default for AppConfig {
             openai_base_url: None,
             openai_api_key: None,
             openai_model: Some("gemini-3-flash-preview".to_string()),
+            request_timeout_secs: None,
+            http_proxy: None,
+            log_ai_requests: false,
+            system_prompt: None,
+            personality: None,
+            language: None,
+            history_trim_strategy: TrimStrategy::DropOldest,
+            ai_requests_per_minute: HashMap::new(),
+            hf_token: None,
+            data_dir_override: None,
+            models_dir_override: None,
             sync_port: 23789,
             auto_start_sync: false,
             auto_update: true,
+            auto_start_observe: false,
+            launch_at_login: false,
+            observation_enabled: true,
             local_only: false,
             onboarding_completed: None,
+            experiment_phase_thresholds: PhaseThresholds::default(),
+            summary_min_entries: None,
+            summary_max_entries: None,
+            ai_intent_enhancement: false,
+            observe_active_window_only: false,
+            observe_skip_duplicates: false,
+            recent_frames_count: None,
+            retain_recent_frames: false,
+            media_sampling: MediaSampling::default(),
+            ocr_languages: Vec::new(),
+            ocr_recognition_level: RecognitionLevel::Accurate,
+            ocr_stopwords: Vec::new(),
+            ocr_filter_patterns: Vec::new(),
+            ocr_timeout_secs: None,
+            capture_png_compression: PngCompression::Fast,
+            capture_jpeg_quality: None,
+            excluded_apps: Vec::new(),
+            provider_profiles: Vec::new(),
+            active_profile: None,
+            api_keys: HashMap::new(),
         }
     }
 }
 
+impl AppConfig {
+    /// Copy a saved profile into the flat provider fields and mark it active
+    pub fn apply_profile(&mut self, name: &str) -> Result<()> {
+        let profile = self
+            .provider_profiles
+            .iter()
+            .find(|p| p.name == name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown provider profile: {}", name))?;
+
+        let api_key = match &profile.api_key_ref {
+            Some(key_ref) => Some(
+                self.api_keys
+                    .get(key_ref)
+                    .cloned()
+                    .ok_or_else(|| anyhow::anyhow!("API key '{}' not found", key_ref))?,
+            ),
+            None => None,
+        };
+
+        match profile.provider.as_str() {
+            "openai" => {
+                self.openai_base_url = profile.base_url;
+                self.openai_model = profile.model;
+                if api_key.is_some() {
+                    self.openai_api_key = api_key;
+                }
+            }
+            "gemini" => {
+                self.gemini_base_url = profile.base_url;
+                self.gemini_model = profile.model;
+                if api_key.is_some() {
+                    self.gemini_api_key = api_key;
+                }
+            }
+            _ => {}
+        }
+
+        self.ai_provider = profile.provider;
+        self.active_profile = Some(profile.name);
+        Ok(())
+    }
+
+    /// Blank every credential: provider keys, named keys and the Hugging
+    /// Face token. Used for exports, which get shared.
+    pub fn clear_secrets(&mut self) {
+        self.gemini_api_key = None;
+        self.openai_api_key = None;
+        self.api_keys.clear();
+        self.hf_token = None;
+    }
+
+    /// Take the credentials [`Self::clear_secrets`] covers from `other`
+    pub fn copy_secrets_from(&mut self, other: &AppConfig) {
+        self.gemini_api_key = other.gemini_api_key.clone();
+        self.openai_api_key = other.openai_api_key.clone();
+        self.api_keys = other.api_keys.clone();
+        self.hf_token = other.hf_token.clone();
+    }
+
+    /// Configured model of the current provider
+    pub fn model(&self) -> Option<&str> {
+        match self.ai_provider.as_str() {
+            "openai" => self.openai_model.as_deref(),
+            "gemini" => self.gemini_model.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Set the model of the current provider. Fails for local models, which
+    /// are picked from the downloaded files instead.
+    pub fn set_model(&mut self, model: &str) -> Result<()> {
+        let slot = match self.ai_provider.as_str() {
+            "openai" => &mut self.openai_model,
+            "gemini" => &mut self.gemini_model,
+            other => anyhow::bail!("Provider '{}' has no model setting", other),
+        };
+        *slot = Some(model.to_string());
+        Ok(())
+    }
+}
+
 /// Get the config file path
-fn get_config_path() -> Result<PathBuf> {
-    let config_dir = dirs::config_dir()
-        .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?
-        .join("hawkeye");
+pub(crate) fn get_config_path() -> Result<PathBuf> {
+    let config_dir = crate::paths::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?;
 
     fs::create_dir_all(&config_dir)?;
     Ok(config_dir.join("config.json"))
@@ -82,3 +349,52 @@ pub fn save_config(config: &AppConfig) -> Result<()> {
     fs::write(&path, content)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_profile_swaps_openai_settings() {
+        let mut config = AppConfig::default();
+        config.api_keys.insert("openrouter".into(), "sk-or".into());
+        config.provider_profiles.push(ProviderProfile {
+            name: "OpenRouter".into(),
+            provider: "openai".into(),
+            base_url: Some("https://openrouter.ai/api/v1".into()),
+            model: Some("anthropic/claude-sonnet-4".into()),
+            api_key_ref: Some("openrouter".into()),
+        });
+
+        config.apply_profile("OpenRouter").unwrap();
+
+        assert_eq!(config.ai_provider, "openai");
+        assert_eq!(config.openai_base_url.as_deref(), Some("https://openrouter.ai/api/v1"));
+        assert_eq!(config.openai_api_key.as_deref(), Some("sk-or"));
+        assert_eq!(config.active_profile.as_deref(), Some("OpenRouter"));
+        assert!(config.apply_profile("missing").is_err());
+    }
+
+    #[test]
+    fn set_model_targets_the_current_provider() {
+        let mut config = AppConfig {
+            ai_provider: "openai".into(),
+            ..AppConfig::default()
+        };
+        config.set_model("gpt-4.1").unwrap();
+        assert_eq!(config.model(), Some("gpt-4.1"));
+        assert_eq!(config.gemini_model.as_deref(), Some("gemini-2.5-flash-preview-05-20"));
+
+        config.ai_provider = "llama-cpp".into();
+        assert!(config.set_model("qwen").is_err());
+        assert_eq!(config.model(), None);
+    }
+
+    #[test]
+    fn capture_switch_defaults_on_for_older_configs() {
+        let mut json = serde_json::to_value(AppConfig::default()).unwrap();
+        json.as_object_mut().unwrap().remove("observationEnabled");
+        let config: AppConfig = serde_json::from_value(json).unwrap();
+        assert!(config.observation_enabled);
+    }
+}
@@ -0,0 +1,159 @@
+//! Structured errors returned by commands
+//!
+//! [`HawkeyeError`] serializes to `{ code, message }` so the frontend can
+//! react to the kind of failure (e.g. prompt for an API key on
+//! `ai_not_initialized`) instead of matching on prose.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::ai::gemini::GeminiApiError;
+use crate::ai::http::{HttpStatusError, TransportError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum HawkeyeError {
+    #[error("AI not initialized. Call init_ai first.")]
+    AiNotInitialized,
+    #[error("{0}")]
+    Network(String),
+    #[error("{0}")]
+    RateLimited(String),
+    #[error("{0}")]
+    Cancelled(String),
+    #[error("{0}")]
+    PermissionDenied(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl HawkeyeError {
+    /// Stable machine-readable code sent to the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::AiNotInitialized => "ai_not_initialized",
+            Self::Network(_) => "network",
+            Self::RateLimited(_) => "rate_limited",
+            Self::Cancelled(_) => "cancelled",
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::NotFound(_) => "not_found",
+            Self::Other(_) => "other",
+        }
+    }
+
+    /// Classify a non-success HTTP reply from a provider
+    fn from_status(status: u16, message: String) -> Self {
+        match status {
+            429 => Self::RateLimited(message),
+            403 => Self::PermissionDenied(message),
+            404 => Self::NotFound(message),
+            408 | 502 | 503 | 504 => Self::Network(message),
+            _ => Self::Other(message),
+        }
+    }
+}
+
+impl Serialize for HawkeyeError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("HawkeyeError", 2)?;
+        s.serialize_field("code", self.code())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.end()
+    }
+}
+
+/// Code that still reports failures as strings returns them as `other`;
+/// return a typed variant where the kind matters to the frontend
+impl From<String> for HawkeyeError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<&str> for HawkeyeError {
+    fn from(message: &str) -> Self {
+        Self::Other(message.to_string())
+    }
+}
+
+impl From<anyhow::Error> for HawkeyeError {
+    fn from(e: anyhow::Error) -> Self {
+        // Keep the variant of a `HawkeyeError` that passed through anyhow
+        let e = match e.downcast::<HawkeyeError>() {
+            Ok(err) => return err,
+            Err(e) => e,
+        };
+        let message = e.to_string();
+        if let Some(api) = e.downcast_ref::<GeminiApiError>() {
+            return match api.status.as_deref() {
+                Some("RESOURCE_EXHAUSTED") => Self::RateLimited(message),
+                Some("PERMISSION_DENIED") => Self::PermissionDenied(message),
+                Some("NOT_FOUND") => Self::NotFound(message),
+                Some("UNAVAILABLE") => Self::Network(message),
+                _ => Self::from_status(api.http_status, message),
+            };
+        }
+        if let Some(status) = e.downcast_ref::<HttpStatusError>() {
+            return Self::from_status(status.status, message);
+        }
+        if e.is::<TransportError>() || e.chain().any(|cause| cause.is::<reqwest::Error>()) {
+            return Self::Network(message);
+        }
+        match e.downcast_ref::<std::io::Error>().map(|io| io.kind()) {
+            Some(std::io::ErrorKind::NotFound) => Self::NotFound(message),
+            Some(std::io::ErrorKind::PermissionDenied) => Self::PermissionDenied(message),
+            _ => Self::Other(message),
+        }
+    }
+}
+
+impl From<std::io::Error> for HawkeyeError {
+    fn from(e: std::io::Error) -> Self {
+        anyhow::Error::from(e).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_and_message() {
+        let err = HawkeyeError::NotFound("Local model 'x' not downloaded.".to_string());
+        let json = serde_json::to_value(err).unwrap();
+        assert_eq!(json["code"], "not_found");
+        assert_eq!(json["message"], "Local model 'x' not downloaded.");
+
+        let json = serde_json::to_value(HawkeyeError::AiNotInitialized).unwrap();
+        assert_eq!(json["code"], "ai_not_initialized");
+
+        // A typed error keeps its variant through anyhow
+        let wrapped = anyhow::Error::from(HawkeyeError::PermissionDenied("off".to_string()));
+        assert_eq!(HawkeyeError::from(wrapped).code(), "permission_denied");
+    }
+
+    #[test]
+    fn classifies_by_error_type_not_message_text() {
+        // Prose that merely mentions a kind stays `other`
+        assert_eq!(HawkeyeError::from("Download cancelled").code(), "other");
+        let io = std::io::Error::new(std::io::ErrorKind::Other, "connection timed out");
+        assert_eq!(HawkeyeError::from(io).code(), "other");
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+        assert_eq!(HawkeyeError::from(io).code(), "not_found");
+
+        let status = |status: u16| -> HawkeyeError {
+            anyhow::Error::from(HttpStatusError {
+                status,
+                message: "nope".to_string(),
+            })
+            .into()
+        };
+        assert_eq!(status(429).code(), "rate_limited");
+        assert_eq!(status(403).code(), "permission_denied");
+        assert_eq!(status(503).code(), "network");
+        assert_eq!(status(500).code(), "other");
+        assert_eq!(status(429).to_string(), "nope");
+    }
+}
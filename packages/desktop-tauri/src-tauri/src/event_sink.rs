@@ -12,6 +12,12 @@ use std::sync::Arc;
 /// Provider-neutral sink for backend → frontend (or backend → stdout) events.
 pub trait EventSink: Send + Sync {
     fn emit(&self, event: &str, payload: Value);
+
+    /// Whether anyone is looking at the events right now. Producers may skip
+    /// heavy, display-only payloads when this is false.
+    fn has_viewer(&self) -> bool {
+        true
+    }
 }
 
 /// Tauri implementation — forwards to `AppHandle::emit`.
@@ -29,6 +35,13 @@ impl EventSink for TauriSink {
     fn emit(&self, event: &str, payload: Value) {
         let _ = tauri::Emitter::emit(&self.handle, event, payload);
     }
+
+    /// The main window is open and visible (not tray-only)
+    fn has_viewer(&self) -> bool {
+        tauri::Manager::get_webview_window(&self.handle, "main")
+            .and_then(|window| window.is_visible().ok())
+            .unwrap_or(false)
+    }
 }
 
 /// CLI implementation — emits NDJSON to stdout (one JSON object per line).
@@ -4,6 +4,18 @@
 pub const OBSERVE_UPDATE: &str = "observe:update";
 pub const OBSERVE_CHANGE: &str = "observe:change-detected";
 pub const OBSERVE_STOPPED: &str = "observe:stopped";
+/// The loop gave up — payload `{ "reason", "consecutiveFailures" }`
+pub const OBSERVE_ERROR: &str = "observe:error";
+/// Sustained change in one window (video); the loop samples slowly —
+/// payload `{ "app", "title", "intervalMs" }`
+pub const OBSERVE_MEDIA_DETECTED: &str = "observe:media-detected";
+/// Playback ended; normal cadence resumes
+pub const OBSERVE_MEDIA_ENDED: &str = "observe:media-ended";
+
+/// Perception events — payload `{ "permission": "accessibility" | ... }`
+pub const PERMISSION_REQUIRED: &str = "perception:permission-required";
+/// Capture master switch toggled — payload is the new `bool`
+pub const CAPTURE_ENABLED_CHANGED: &str = "perception:capture-enabled-changed";
 
 /// AI events
 pub const AI_INITIALIZED: &str = "ai:initialized";
@@ -12,9 +24,15 @@ pub const AI_ERROR: &str = "ai:error";
 /// Intent events
 pub const INTENT_RECOGNIZED: &str = "intent:recognized";
 
+/// Focus session went off-task — payload is the `Distraction`
+pub const FOCUS_DISTRACTION: &str = "focus:distraction";
+
 /// Activity summary events
 pub const SUMMARY_GENERATED: &str = "activity:summary-generated";
 
+/// Life tree events — payload is the experiment node
+pub const EXPERIMENT_DUE: &str = "experiment:due";
+
 /// Model download events
 pub const MODEL_DOWNLOAD_PROGRESS: &str = "model:download-progress";
 
@@ -61,3 +79,6 @@ pub const AGENT_TOOL_CALL_START: &str = "agent:tool-call-start";
 pub const AGENT_TOOL_CALL_END: &str = "agent:tool-call-end";
 pub const AGENT_DAEMON_READY: &str = "agent:daemon-ready";
 pub const AGENT_DAEMON_ERROR: &str = "agent:daemon-error";
+
+/// Plan executor progress, one per step start and finish
+pub const PLAN_PROGRESS: &str = "plan:progress";
@@ -0,0 +1,32 @@
+//! Command-line flags for the desktop app
+
+/// How the app was launched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    /// `--headless`: keep the main window hidden and run from the tray.
+    /// With `auto_start_observe` the observe loop starts at launch.
+    pub headless: bool,
+}
+
+impl LaunchOptions {
+    /// Parse process arguments (including the program name). Unknown flags
+    /// are left for Tauri and plugins.
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Self {
+        Self {
+            headless: args.into_iter().skip(1).any(|arg| arg == "--headless"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headless_flag_is_parsed_after_program_name() {
+        let args = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert!(LaunchOptions::from_args(args(&["hawkeye", "--headless"])).headless);
+        assert!(!LaunchOptions::from_args(args(&["hawkeye"])).headless);
+        assert!(!LaunchOptions::from_args(args(&["--headless"])).headless);
+    }
+}
@@ -11,15 +11,22 @@
 
 pub mod agent;
 pub mod ai;
+pub mod clock;
 pub mod commands;
 pub mod config;
+pub mod error;
 pub mod event_sink;
 pub mod events;
 pub mod gaze;
+pub mod launch;
+pub mod login_item;
 pub mod life_tree;
+pub mod log_throttle;
 pub mod models;
 pub mod observe;
+pub mod paths;
 pub mod perception;
+pub mod snapshot;
 pub mod state;
 pub mod training;
 pub mod voice;
@@ -32,6 +39,7 @@ use tauri::tray::TrayIconEvent;
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     env_logger::init();
+    let launch = launch::LaunchOptions::from_args(std::env::args());
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
@@ -39,7 +47,7 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .setup(|app| {
+        .setup(move |app| {
             // Load config
             let cfg = config::load_config().unwrap_or_default();
 
@@ -59,11 +67,84 @@ pub fn run() {
                 });
             }
 
-            // Initialize perception engine
-            tauri::async_runtime::spawn(async {
-                if let Err(e) = perception::init().await {
+            // Clipboard access needs the app handle; hand the observe loop a reader
+            {
+                use tauri_plugin_clipboard_manager::ClipboardExt;
+
+                let handle = app.handle().clone();
+                let reader: perception::clipboard::ClipboardReader = std::sync::Arc::new(move || {
+                    handle.clipboard().read_text().ok().filter(|t| !t.is_empty())
+                });
+                let state = app_state.clone();
+                tauri::async_runtime::spawn(async move {
+                    *state.clipboard_reader.write().await = Some(reader);
+                });
+            }
+
+            // Launch at login: the OS entry is the source of truth; keep it
+            // pointing at this executable and sync the config to it
+            let state = app_state.clone();
+            tauri::async_runtime::spawn(async move {
+                match login_item::sync_on_startup() {
+                    Ok(enabled) => {
+                        if let Err(e) = commands::config_cmd::sync_launch_at_login(&state, enabled).await {
+                            log::warn!("[Launch] Failed to sync launch_at_login: {}", e);
+                        }
+                    }
+                    Err(e) => log::debug!("[Launch] Login item unavailable: {}", e),
+                }
+            });
+
+            // Debounced life-tree persistence (keeps disk I/O off the observe loop)
+            tauri::async_runtime::spawn(life_tree::autosave::run(app_state.clone()));
+
+            // Remind the user to conclude experiments that are past due
+            tauri::async_runtime::spawn(life_tree::reminders::run(app_state.clone()));
+
+            // Sweep partial model downloads left behind by a crash
+            let state = app_state.clone();
+            tauri::async_runtime::spawn(async move {
+                let mgr = state.model_manager.read().await;
+                match mgr.cleanup_partial_downloads(models::manager::STALE_PARTIAL_AGE) {
+                    Ok(c) if c.removed_files > 0 => log::info!(
+                        "[Models] Removed {} stale partial download(s), {} bytes",
+                        c.removed_files,
+                        c.reclaimed_bytes
+                    ),
+                    Ok(_) => {}
+                    Err(e) => log::warn!("[Models] Partial download sweep failed: {}", e),
+                }
+            });
+
+            // Initialize perception engine, then auto-start observing. Uses
+            // `start_if_idle` like the tray and `start_observe`, so whichever
+            // runs first wins and the others are no-ops.
+            let state = app_state.clone();
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = perception::init(&state).await {
                     log::error!("Failed to initialize perception: {}", e);
                 }
+                if state.config.read().await.auto_start_observe {
+                    let sink: event_sink::SharedSink = state
+                        .event_sink
+                        .read()
+                        .await
+                        .clone()
+                        .unwrap_or_else(|| {
+                            std::sync::Arc::new(event_sink::TauriSink::new(handle))
+                        });
+                    if observe::ObserveLoop::start_if_idle(&state, sink.clone()).await {
+                        log::info!(
+                            "[Launch] Auto-started observe{}",
+                            if launch.headless { " (headless)" } else { "" }
+                        );
+                        let status = commands::status::current_status(&state).await;
+                        if let Ok(payload) = serde_json::to_value(status) {
+                            sink.emit(events::STATUS_CHANGED, payload);
+                        }
+                    }
+                }
             });
 
             // Initialize cua-driver supervisor (does NOT auto-spawn the
@@ -92,9 +173,15 @@ pub fn run() {
                 }
             });
 
-            // Set up main window
+            // Set up main window. It starts hidden (see tauri.conf.json) so
+            // a headless launch never flashes it; the tray shows it on demand.
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.set_always_on_top(true);
+                if launch.headless {
+                    log::info!("[Launch] Headless: main window hidden, running from the tray");
+                } else {
+                    let _ = window.show();
+                }
             }
 
             // --- Tray menu ---
@@ -148,25 +235,17 @@ pub fn run() {
                         let handle = app_handle.clone();
                         tauri::async_runtime::spawn(async move {
                             let state = handle.state::<std::sync::Arc<state::AppState>>();
-                            let mut loop_handle = state.observe_loop.write().await;
-                            if loop_handle.is_none() {
-                                let sink: event_sink::SharedSink = state
-                                    .event_sink
-                                    .read()
-                                    .await
-                                    .clone()
-                                    .unwrap_or_else(|| {
-                                        std::sync::Arc::new(event_sink::TauriSink::new(
-                                            handle.clone(),
-                                        ))
-                                    });
-                                let obs = observe::ObserveLoop::start(
-                                    sink,
-                                    std::sync::Arc::clone(&state),
-                                    3000,
-                                    0.05,
-                                );
-                                *loop_handle = Some(obs);
+                            let sink: event_sink::SharedSink = state
+                                .event_sink
+                                .read()
+                                .await
+                                .clone()
+                                .unwrap_or_else(|| {
+                                    std::sync::Arc::new(event_sink::TauriSink::new(
+                                        handle.clone(),
+                                    ))
+                                });
+                            if observe::ObserveLoop::start_if_idle(&state, sink).await {
                                 log::info!("[Tray] Started observe");
                             }
                         });
@@ -193,54 +272,109 @@ pub fn run() {
             // Config
             commands::config_cmd::load_config,
             commands::config_cmd::save_config,
+            commands::config_cmd::set_active_profile,
+            commands::config_cmd::set_system_prompt,
+            commands::config_cmd::set_launch_at_login,
+            commands::config_cmd::get_launch_at_login,
+            // State snapshots
+            commands::snapshot_cmd::export_state,
+            commands::snapshot_cmd::import_state,
+            // Granular data resets
+            commands::reset_cmd::reset_adaptive,
+            commands::reset_cmd::clear_activity_log,
+            commands::reset_cmd::clear_intents,
             // Perception
             commands::perception_cmd::capture_screen,
             commands::perception_cmd::run_ocr,
+            commands::perception_cmd::run_ocr_regions,
+            commands::perception_cmd::reocr_last_observation,
             commands::perception_cmd::get_clipboard,
             commands::perception_cmd::get_active_window,
+            commands::perception_cmd::capture_named_window,
+            commands::perception_cmd::capture_context,
+            commands::perception_cmd::set_capture_enabled,
+            commands::perception_cmd::is_capture_enabled,
+            commands::perception_cmd::list_monitors,
+            commands::perception_cmd::get_display_info,
+            commands::perception_cmd::check_permissions,
+            commands::perception_cmd::request_permission,
             // Chat
             commands::chat_cmd::chat,
+            commands::chat_cmd::get_conversation,
             commands::chat_cmd::init_ai,
+            commands::chat_cmd::set_active_model,
+            commands::chat_cmd::estimate_tokens,
+            commands::chat_cmd::embed_texts,
+            commands::chat_cmd::get_provider_capabilities,
+            commands::chat_cmd::get_ai_diagnostics,
             // Agent (cua-driver tool-use)
             commands::agent_cmd::get_agent_status,
             commands::agent_cmd::start_agent,
             commands::agent_cmd::chat_with_agent,
             commands::agent_cmd::invoke_cua_tool,
+            commands::agent_cmd::execute_plan,
             // Observe
             commands::observe_cmd::start_observe,
             commands::observe_cmd::stop_observe,
             commands::observe_cmd::get_observe_status,
+            commands::observe_cmd::get_last_full_screenshot,
+            commands::observe_cmd::get_recent_observations,
+            commands::observe_cmd::diff_observations,
+            commands::observe_cmd::set_observe_params,
+            commands::observe_cmd::get_observe_params,
+            commands::observe_cmd::get_change_stats,
+            commands::observe_cmd::observe_once,
+            commands::observe_cmd::benchmark_pipeline,
             // Adaptive refresh
             commands::adaptive_cmd::record_activity,
             commands::adaptive_cmd::get_refresh_status,
+            commands::adaptive_cmd::set_activity_level,
+            commands::adaptive_cmd::clear_forced_level,
             // Activity summarizer
             commands::summarizer_cmd::generate_summary,
             commands::summarizer_cmd::get_recent_summaries,
             commands::summarizer_cmd::get_activity_stats,
+            commands::summarizer_cmd::get_activity_entries,
+            commands::summarizer_cmd::export_activity,
             // Intent pipeline
             commands::intent_cmd::recognize_intent,
             commands::intent_cmd::recognize_intent_ai,
             commands::intent_cmd::get_recent_intents,
+            // Focus sessions
+            commands::focus_cmd::start_focus_session,
+            commands::focus_cmd::end_focus_session,
             // Voice
             commands::voice_cmd::speech_status,
             commands::voice_cmd::speech_listen,
             commands::voice_cmd::speech_transcribe_file,
+            commands::voice_cmd::transcribe_and_summarize,
             // Life tree
             commands::life_tree_cmd::get_life_tree,
             commands::life_tree_cmd::rebuild_life_tree,
+            commands::life_tree_cmd::reclassify_life_tree,
             commands::life_tree_cmd::propose_experiment,
+            commands::life_tree_cmd::propose_stage_experiment,
             commands::life_tree_cmd::start_experiment,
             commands::life_tree_cmd::conclude_experiment,
             commands::life_tree_cmd::get_unlocked_phase,
+            commands::life_tree_cmd::get_phase_progress,
             commands::life_tree_cmd::get_experiments,
+            commands::life_tree_cmd::get_due_experiments,
+            commands::life_tree_cmd::get_experiment_history,
+            commands::life_tree_cmd::get_stage_timeline,
+            commands::life_tree_cmd::analyze_life_tree,
             // Model manager
             commands::model_cmd::get_models_dir,
+            commands::model_cmd::set_models_dir,
             commands::model_cmd::list_models,
             commands::model_cmd::get_recommended_models,
             commands::model_cmd::get_models_by_type,
+            commands::model_cmd::get_models_by_types,
+            commands::model_cmd::search_models,
             commands::model_cmd::model_exists,
             commands::model_cmd::download_model,
             commands::model_cmd::cancel_model_download,
+            commands::model_cmd::cleanup_partial_downloads,
             commands::model_cmd::delete_model,
             commands::model_cmd::get_model_path,
             // Gesture control
@@ -255,6 +389,7 @@ pub fn run() {
             // Debug timeline
             commands::debug_cmd::get_debug_events,
             commands::debug_cmd::get_debug_events_since,
+            commands::debug_cmd::get_debug_children,
             commands::debug_cmd::search_debug_events,
             commands::debug_cmd::push_debug_event,
             commands::debug_cmd::get_debug_status,
@@ -276,6 +411,18 @@ pub fn run() {
             // Utilities
             commands::util_cmd::open_url,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Persist any life-tree changes the background saver hasn't written yet
+                let state = app.state::<std::sync::Arc<state::AppState>>();
+                tauri::async_runtime::block_on(async {
+                    if let Err(e) = state.life_tree.write().await.flush() {
+                        log::error!("[LifeTree] Final save failed: {}", e);
+                    }
+                    observe::loop_runner::clear_recent_frames(&state).await;
+                });
+            }
+        });
 }
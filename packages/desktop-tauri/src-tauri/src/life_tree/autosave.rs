@@ -0,0 +1,41 @@
+//! Debounced background saver for the life tree
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+use crate::state::AppState;
+
+/// Minimum time between two saves of a dirty tree
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Persist the life tree at most once per [`SAVE_INTERVAL`] while it has
+/// unsaved changes.
+///
+/// The snapshot is taken under the lock but written on a blocking thread, so
+/// disk I/O never stalls the observe loop. A snapshot overtaken by a newer
+/// save is dropped rather than written. Runs until the runtime shuts down;
+/// call [`LifeTree::flush`] on exit to persist the tail.
+pub async fn run(state: Arc<AppState>) {
+    let mut ticker = tokio::time::interval(SAVE_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        let pending = state.life_tree.write().await.take_pending_save();
+        let Some(pending) = pending else {
+            continue;
+        };
+
+        let result = tokio::task::spawn_blocking(move || pending.write())
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+
+        if let Err(e) = result {
+            log::warn!("[LifeTree] Background save failed: {}", e);
+            state.life_tree.write().await.mark_dirty();
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Cross-stage insights — how activity in one life stage moves with another
+//!
+//! Uses the per-stage daily counts kept on stage nodes. Correlations are plain
+//! Pearson coefficients over days where anything was observed; days the app
+//! wasn't running would otherwise read as "zero everywhere" and inflate every
+//! pair.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::tree::LifeTree;
+use super::types::LifeStage;
+
+/// Below this many active days no correlations are reported
+pub const MIN_SAMPLE_DAYS: usize = 7;
+/// Coefficients weaker than this are treated as noise
+const MIN_ABS_COEFFICIENT: f64 = 0.3;
+/// Pairs returned per direction
+const MAX_PAIRS: usize = 3;
+
+/// Correlation between two stages' daily activity
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageCorrelation {
+    pub stage_a: LifeStage,
+    pub stage_b: LifeStage,
+    /// Pearson coefficient in [-1, 1]
+    pub coefficient: f64,
+    /// Days the coefficient was computed over
+    pub sample_days: usize,
+    pub description: String,
+}
+
+/// Result of `analyze_life_tree`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifeTreeInsights {
+    /// Days in the requested window
+    pub days_analyzed: u32,
+    /// Days within the window with any recorded activity
+    pub active_days: usize,
+    /// Strongest positive pairs, strongest first
+    pub positive: Vec<StageCorrelation>,
+    /// Strongest negative pairs, strongest first
+    pub negative: Vec<StageCorrelation>,
+    /// Caveat about the sample, when there is one worth stating
+    pub note: Option<String>,
+}
+
+/// Correlate daily stage activity over the `days` days ending `today`
+pub fn analyze(tree: &LifeTree, days: u32, today: NaiveDate) -> LifeTreeInsights {
+    let stages = LifeStage::all();
+    let timelines: Vec<Vec<f64>> = stages
+        .iter()
+        .map(|stage| {
+            tree.stage_timeline(stage, days, today)
+                .iter()
+                .map(|d| d.count as f64)
+                .collect()
+        })
+        .collect();
+    let days_analyzed = timelines.first().map(|t| t.len()).unwrap_or(0) as u32;
+
+    // Keep only days where something was observed
+    let active: Vec<usize> = (0..days_analyzed as usize)
+        .filter(|&day| timelines.iter().any(|t| t[day] > 0.0))
+        .collect();
+    let series: Vec<Vec<f64>> = timelines
+        .iter()
+        .map(|t| active.iter().map(|&day| t[day]).collect())
+        .collect();
+
+    let mut insights = LifeTreeInsights {
+        days_analyzed,
+        active_days: active.len(),
+        positive: Vec::new(),
+        negative: Vec::new(),
+        note: None,
+    };
+
+    if active.len() < MIN_SAMPLE_DAYS {
+        insights.note = Some(format!(
+            "Only {} day(s) with activity; at least {} are needed before patterns mean anything.",
+            active.len(),
+            MIN_SAMPLE_DAYS
+        ));
+        return insights;
+    }
+
+    let mut pairs = Vec::new();
+    for a in 0..stages.len() {
+        for b in (a + 1)..stages.len() {
+            let Some(r) = pearson(&series[a], &series[b]) else {
+                continue;
+            };
+            if r.abs() < MIN_ABS_COEFFICIENT {
+                continue;
+            }
+            pairs.push(StageCorrelation {
+                stage_a: stages[a].clone(),
+                stage_b: stages[b].clone(),
+                coefficient: r,
+                sample_days: active.len(),
+                description: describe(&stages[a], &stages[b], r, active.len()),
+            });
+        }
+    }
+
+    pairs.sort_by(|x, y| y.coefficient.abs().total_cmp(&x.coefficient.abs()));
+    let (positive, negative): (Vec<_>, Vec<_>) =
+        pairs.into_iter().partition(|p| p.coefficient > 0.0);
+    insights.positive = positive.into_iter().take(MAX_PAIRS).collect();
+    insights.negative = negative.into_iter().take(MAX_PAIRS).collect();
+
+    if active.len() < 2 * MIN_SAMPLE_DAYS {
+        insights.note = Some(format!(
+            "Based on {} days of activity; treat these as hints, not conclusions.",
+            active.len()
+        ));
+    }
+
+    insights
+}
+
+/// Pearson correlation; `None` when either series is constant
+fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len().min(ys.len());
+    if n < 2 {
+        return None;
+    }
+    let mean_x = xs[..n].iter().sum::<f64>() / n as f64;
+    let mean_y = ys[..n].iter().sum::<f64>() / n as f64;
+
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs[..n].iter().zip(&ys[..n]) {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+
+    if var_x == 0.0 || var_y == 0.0 {
+        return None;
+    }
+    Some(cov / (var_x * var_y).sqrt())
+}
+
+fn describe(a: &LifeStage, b: &LifeStage, r: f64, days: usize) -> String {
+    let strength = if r.abs() >= 0.7 {
+        "strong"
+    } else if r.abs() >= 0.5 {
+        "moderate"
+    } else {
+        "weak"
+    };
+    let direction = if r > 0.0 { "more" } else { "less" };
+    format!(
+        "On days with more {} activity you tend to have {} {} activity ({} link, r = {:.2} over {} days).",
+        a.label(),
+        direction,
+        b.label(),
+        strength,
+        r,
+        days
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pearson_handles_perfect_and_constant_series() {
+        let xs = [1.0, 2.0, 3.0, 4.0];
+        assert!((pearson(&xs, &[2.0, 4.0, 6.0, 8.0]).unwrap() - 1.0).abs() < 1e-9);
+        assert!((pearson(&xs, &[8.0, 6.0, 4.0, 2.0]).unwrap() + 1.0).abs() < 1e-9);
+        assert!(pearson(&xs, &[5.0, 5.0, 5.0, 5.0]).is_none());
+    }
+}
@@ -3,6 +3,9 @@
 //! Structures life observations into: Root → Stages → Goals → Tasks → Experiments
 //! Uses AI to classify activities and propose micro-experiments.
 
+pub mod autosave;
+pub mod insights;
+pub mod reminders;
 pub mod types;
 pub mod tree;
 
@@ -0,0 +1,48 @@
+//! Background check that nudges the user to conclude due experiments
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+use crate::events;
+use crate::state::AppState;
+
+/// How often active experiments are checked against their due date
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Emit [`events::EXPERIMENT_DUE`] once per experiment when it passes its
+/// due date. Experiments already overdue at startup are announced on the
+/// first tick. Runs until the runtime shuts down.
+pub async fn run(state: Arc<AppState>) {
+    let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut notified: HashSet<String> = HashSet::new();
+
+    loop {
+        ticker.tick().await;
+
+        let due: Vec<_> = {
+            let tree = state.life_tree.read().await;
+            tree.due_experiments(tree.now(), 0)
+                .into_iter()
+                .filter(|n| !notified.contains(&n.id))
+                .cloned()
+                .collect()
+        };
+        if due.is_empty() {
+            continue;
+        }
+
+        let Some(sink) = state.event_sink.read().await.clone() else {
+            continue;
+        };
+        for experiment in due {
+            log::info!("[LifeTree] Experiment due: {}", experiment.label);
+            notified.insert(experiment.id.clone());
+            if let Ok(payload) = serde_json::to_value(&experiment) {
+                sink.emit(events::EXPERIMENT_DUE, payload);
+            }
+        }
+    }
+}
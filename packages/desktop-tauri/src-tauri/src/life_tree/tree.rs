@@ -1,32 +1,136 @@
 //! Life Tree builder and manager
 
+use chrono::{Duration, Local, NaiveDate};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
-use crate::ai::ChatMessage;
+use crate::ai::{ChatMessage, ReplyLanguage};
+use crate::clock::{self, SharedClock};
+use crate::error::HawkeyeError;
 use super::types::*;
 
+/// Classification weight of a keyword found in the app name
+const APP_WEIGHT: f64 = 3.0;
+/// Classification weight of a keyword found in the window title
+const TITLE_WEIGHT: f64 = 2.0;
+/// Classification weight of a keyword found in OCR text, which is noisy
+/// and often shows unrelated content
+const OCR_WEIGHT: f64 = 1.0;
+/// OCR keyword matches counted per stage, so a wall of text can't outvote
+/// the app and title
+const OCR_MATCH_CAP: usize = 2;
+/// Score at which a classification counts as fully backed by evidence
+const STRONG_SCORE: f64 = 5.0;
+/// Confidence of a task classified without any matching signal
+const UNCLASSIFIED_CONFIDENCE: f64 = 0.2;
+
+/// One day in milliseconds
+pub const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Winning life stage for an activity and how sure the heuristics are
+#[derive(Debug, Clone, PartialEq)]
+struct StageClassification {
+    stage: LifeStage,
+    score: f64,
+    /// 0.2 (no signal) … 0.9 (strong, unambiguous signal)
+    confidence: f64,
+}
+
+/// Orders writes of the tree file. Every write takes a generation when its
+/// contents are captured; a write that finds a newer one already on disk is
+/// skipped, so a stale background snapshot can't replace a later save.
+#[derive(Debug, Default)]
+struct SaveSequence {
+    issued: AtomicU64,
+    /// Generation currently on disk. Held for the whole write, which
+    /// serializes writers.
+    landed: Mutex<u64>,
+}
+
+impl SaveSequence {
+    fn issue(&self) -> u64 {
+        self.issued.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Run `write` for `generation` unless a newer one has landed.
+    /// Returns `Ok(false)` when the write was skipped.
+    fn land<E>(&self, generation: u64, write: impl FnOnce() -> Result<(), E>) -> Result<bool, E> {
+        let mut landed = self.landed.lock().unwrap_or_else(|e| e.into_inner());
+        if *landed > generation {
+            return Ok(false);
+        }
+        write()?;
+        *landed = generation;
+        Ok(true)
+    }
+}
+
+/// A snapshot captured for a background save
+pub struct PendingSave {
+    path: PathBuf,
+    snapshot: LifeTreeSnapshot,
+    generation: u64,
+    sequence: Arc<SaveSequence>,
+}
+
+impl PendingSave {
+    /// Write the snapshot unless a newer save has already landed.
+    /// Blocking; safe to call off the owning task.
+    pub fn write(&self) -> Result<(), String> {
+        let written = self.sequence.land(self.generation, || {
+            LifeTree::write_snapshot(&self.path, &self.snapshot)
+        })?;
+        if !written {
+            log::debug!("[LifeTree] Skipped stale background save");
+        }
+        Ok(())
+    }
+}
+
 /// Life Tree — manages the hierarchical activity structure
 pub struct LifeTree {
     nodes: HashMap<String, LifeTreeNode>,
     root_id: String,
     data_path: PathBuf,
     phase1_completions: u32,
+    /// Successful goal-level experiments; count toward the automation unlock
+    goal_completions: u32,
+    phase_thresholds: PhaseThresholds,
+    /// Set when the tree has changes not yet persisted to disk
+    dirty: bool,
+    /// Shared with in-flight background saves
+    saves: Arc<SaveSequence>,
+    clock: SharedClock,
 }
 
 impl LifeTree {
     /// Create a new life tree with default stages
     pub fn new() -> Self {
-        let data_path = dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("com.hawkeye.desktop")
-            .join("life_tree.json");
+        let data_path = crate::paths::data_dir().join("life_tree.json");
+
+        Self::with_data_path(data_path)
+    }
+
+    /// Create a life tree persisted at a specific path
+    pub fn with_data_path(data_path: PathBuf) -> Self {
+        Self::with_clock(data_path, clock::system())
+    }
 
+    /// Create a life tree persisted at `data_path` that reads time from `clock`
+    pub fn with_clock(data_path: PathBuf, clock: SharedClock) -> Self {
         let mut tree = Self {
             nodes: HashMap::new(),
             root_id: "root".to_string(),
             data_path,
             phase1_completions: 0,
+            goal_completions: 0,
+            phase_thresholds: PhaseThresholds::default(),
+            dirty: false,
+            saves: Arc::default(),
+            clock,
         };
 
         // Try loading from disk
@@ -41,7 +145,7 @@ impl LifeTree {
 
     /// Build empty tree with root and all stages
     fn build_empty(&mut self) {
-        let now = Self::now();
+        let now = self.now();
 
         let root = LifeTreeNode {
             id: "root".to_string(),
@@ -58,6 +162,10 @@ impl LifeTree {
             experiment_phase: None,
             observation_count: 0,
             related_apps: Vec::new(),
+            daily_counts: Vec::new(),
+            due_at: None,
+            outcome: None,
+            ocr_snippet: None,
         };
 
         self.nodes.insert("root".to_string(), root);
@@ -79,6 +187,10 @@ impl LifeTree {
                 experiment_phase: None,
                 observation_count: 0,
                 related_apps: Vec::new(),
+                daily_counts: Vec::new(),
+                due_at: None,
+                outcome: None,
+                ocr_snippet: None,
             };
             self.nodes.insert(stage_id.clone(), stage_node);
             if let Some(root) = self.nodes.get_mut("root") {
@@ -87,15 +199,20 @@ impl LifeTree {
         }
     }
 
-    /// Process an activity context and update the tree
-    pub fn process_activity(&mut self, ctx: &ActivityContext) {
-        let stage = self.classify_stage(ctx);
+    /// Process an activity context and update the tree. Returns the stage
+    /// and task label the activity was filed under.
+    pub fn process_activity(&mut self, ctx: &ActivityContext) -> (LifeStage, String) {
+        let StageClassification { stage, confidence, .. } = self.classify_stage(ctx);
         let stage_id = format!("stage_{:?}", stage).to_lowercase();
+        let now = self.now();
 
         // Update stage observation count
         if let Some(stage_node) = self.nodes.get_mut(&stage_id) {
             stage_node.observation_count += 1;
-            stage_node.updated_at = Self::now();
+            stage_node.updated_at = now;
+            if let Some(day) = Self::local_day(ctx.timestamp) {
+                Self::record_day(&mut stage_node.daily_counts, day);
+            }
             if let Some(app) = &ctx.app_name {
                 if !stage_node.related_apps.contains(app) {
                     stage_node.related_apps.push(app.clone());
@@ -114,18 +231,22 @@ impl LifeTree {
             let task = LifeTreeNode {
                 id: task_id.clone(),
                 node_type: NodeType::Task,
-                label: task_label,
+                label: task_label.clone(),
                 description: ctx.window_title.clone(),
-                stage: Some(stage),
+                stage: Some(stage.clone()),
                 status: NodeStatus::Active,
-                confidence: 0.6,
+                confidence,
                 children: Vec::new(),
                 parent: Some(stage_id.clone()),
-                created_at: Self::now(),
-                updated_at: Self::now(),
+                created_at: now,
+                updated_at: now,
                 experiment_phase: None,
                 observation_count: 1,
                 related_apps: ctx.app_name.iter().cloned().collect(),
+                daily_counts: Vec::new(),
+                due_at: None,
+                outcome: None,
+                ocr_snippet: ctx.ocr_snippet.clone(),
             };
             self.nodes.insert(task_id.clone(), task);
             if let Some(stage_node) = self.nodes.get_mut(&stage_id) {
@@ -133,78 +254,130 @@ impl LifeTree {
             }
         } else if let Some(task) = self.nodes.get_mut(&task_id) {
             task.observation_count += 1;
-            task.updated_at = Self::now();
+            task.updated_at = now;
             task.confidence = (task.confidence + 0.05).min(1.0);
         }
 
-        // Auto-save periodically (every 10 activities)
-        let total: u32 = self.nodes.values().map(|n| n.observation_count).sum();
-        if total % 10 == 0 {
-            let _ = self.save_to_disk();
-        }
+        // Persisted by the debounced background saver (see `autosave`)
+        self.dirty = true;
+        (stage, task_label)
     }
 
-    /// Classify activity into a life stage using heuristics
-    fn classify_stage(&self, ctx: &ActivityContext) -> LifeStage {
+    /// Life stage and task label `process_activity` would assign, without
+    /// touching the tree
+    pub fn classify(&self, ctx: &ActivityContext) -> (LifeStage, String) {
+        (self.classify_stage(ctx).stage, self.infer_task_label(ctx))
+    }
+
+    /// Classify activity into a life stage using heuristics.
+    ///
+    /// Every stage is scored by its matching signals, weighted by where they
+    /// matched (app > window title > OCR), and the highest score wins. Ties
+    /// go to the stage with the stronger single signal, then to the earlier
+    /// stage in [`LifeStage::all`].
+    fn classify_stage(&self, ctx: &ActivityContext) -> StageClassification {
         let app = ctx.app_name.as_deref().unwrap_or("").to_lowercase();
         let title = ctx.window_title.as_deref().unwrap_or("").to_lowercase();
         let text = ctx.ocr_snippet.as_deref().unwrap_or("").to_lowercase();
-        let combined = format!("{} {} {}", app, title, text);
 
-        // Career & Work
-        let work_apps = ["code", "vscode", "cursor", "idea", "webstorm", "pycharm",
-            "sublime", "vim", "nvim", "zed", "terminal", "iterm", "slack", "teams",
-            "zoom", "figma", "notion", "jira", "confluence", "github", "gitlab"];
-        if work_apps.iter().any(|a| app.contains(a)) {
-            return LifeStage::Career;
-        }
-
-        // Learning
-        let learn_keywords = ["tutorial", "course", "learn", "study", "documentation",
-            "docs", "wikipedia", "stackoverflow", "udemy", "coursera", "khan academy",
-            "lecture", "textbook", "research"];
-        if learn_keywords.iter().any(|k| combined.contains(k)) {
-            return LifeStage::Learning;
-        }
-
-        // Health
-        let health_keywords = ["fitness", "workout", "health", "medical", "exercise",
-            "calories", "nutrition", "meditation", "sleep", "strava", "myfitnesspal"];
-        if health_keywords.iter().any(|k| combined.contains(k)) {
-            return LifeStage::Health;
-        }
+        let mut best: Option<(LifeStage, f64, f64)> = None;
+        let mut total = 0.0;
+        for stage in LifeStage::all() {
+            let (apps, keywords) = Self::stage_signals(&stage);
+            let app_hits = apps.iter().chain(keywords).filter(|k| app.contains(*k)).count();
+            let title_hits = keywords.iter().filter(|k| title.contains(*k)).count();
+            let ocr_hits = keywords.iter().filter(|k| text.contains(*k)).count().min(OCR_MATCH_CAP);
 
-        // Relationships
-        let social_apps = ["messages", "whatsapp", "telegram", "discord", "wechat",
-            "facetime", "messenger", "signal"];
-        if social_apps.iter().any(|a| app.contains(a)) {
-            return LifeStage::Relationships;
-        }
+            let score = app_hits as f64 * APP_WEIGHT
+                + title_hits as f64 * TITLE_WEIGHT
+                + ocr_hits as f64 * OCR_WEIGHT;
+            if score == 0.0 {
+                continue;
+            }
+            total += score;
 
-        // Finance
-        let finance_keywords = ["bank", "finance", "investment", "trading", "budget",
-            "tax", "payroll", "invoice", "payment", "crypto", "stock"];
-        if finance_keywords.iter().any(|k| combined.contains(k)) {
-            return LifeStage::Finance;
+            let strongest = if app_hits > 0 {
+                APP_WEIGHT
+            } else if title_hits > 0 {
+                TITLE_WEIGHT
+            } else {
+                OCR_WEIGHT
+            };
+            // Strict comparisons keep the earlier stage on a full tie
+            let better = match &best {
+                None => true,
+                Some((_, best_score, best_strongest)) => {
+                    score > *best_score || (score == *best_score && strongest > *best_strongest)
+                }
+            };
+            if better {
+                best = Some((stage, score, strongest));
+            }
         }
 
-        // Creativity
-        let creative_apps = ["photoshop", "illustrator", "sketch", "blender",
-            "garageband", "logic pro", "final cut", "premiere", "after effects",
-            "procreate", "affinity", "inkscape", "gimp"];
-        if creative_apps.iter().any(|a| app.contains(a)) {
-            return LifeStage::Creativity;
+        match best {
+            Some((stage, score, _)) => {
+                // Strong evidence that clearly beats the other stages → high
+                let strength = (score / STRONG_SCORE).min(1.0);
+                let margin = score / total;
+                StageClassification {
+                    stage,
+                    score,
+                    confidence: 0.3 + 0.6 * strength * margin,
+                }
+            }
+            // No signal at all: career is the most common, but barely a guess
+            None => StageClassification {
+                stage: LifeStage::Career,
+                score: 0.0,
+                confidence: UNCLASSIFIED_CONFIDENCE,
+            },
         }
+    }
 
-        // Safety
-        let safety_keywords = ["security", "password", "vpn", "firewall", "backup",
-            "encryption", "antivirus", "privacy"];
-        if safety_keywords.iter().any(|k| combined.contains(k)) {
-            return LifeStage::Safety;
+    /// Apps (matched on the app name only) and keywords (matched on app,
+    /// title and OCR) that point to `stage`
+    fn stage_signals(stage: &LifeStage) -> (&'static [&'static str], &'static [&'static str]) {
+        match stage {
+            LifeStage::Career => (
+                &["code", "vscode", "cursor", "idea", "webstorm", "pycharm",
+                  "sublime", "vim", "nvim", "zed", "terminal", "iterm", "slack", "teams",
+                  "zoom", "figma", "notion", "jira", "confluence", "github", "gitlab"],
+                &[],
+            ),
+            LifeStage::Learning => (
+                &[],
+                &["tutorial", "course", "learn", "study", "documentation",
+                  "docs", "wikipedia", "stackoverflow", "udemy", "coursera", "khan academy",
+                  "lecture", "textbook", "research"],
+            ),
+            LifeStage::Health => (
+                &[],
+                &["fitness", "workout", "health", "medical", "exercise",
+                  "calories", "nutrition", "meditation", "sleep", "strava", "myfitnesspal"],
+            ),
+            LifeStage::Relationships => (
+                &["messages", "whatsapp", "telegram", "discord", "wechat",
+                  "facetime", "messenger", "signal"],
+                &[],
+            ),
+            LifeStage::Creativity => (
+                &["photoshop", "illustrator", "sketch", "blender",
+                  "garageband", "logic pro", "final cut", "premiere", "after effects",
+                  "procreate", "affinity", "inkscape", "gimp"],
+                &[],
+            ),
+            LifeStage::Finance => (
+                &[],
+                &["bank", "finance", "investment", "trading", "budget",
+                  "tax", "payroll", "invoice", "payment", "crypto", "stock"],
+            ),
+            LifeStage::Safety => (
+                &[],
+                &["security", "password", "vpn", "firewall", "backup",
+                  "encryption", "antivirus", "privacy"],
+            ),
         }
-
-        // Default to career (most common)
-        LifeStage::Career
     }
 
     /// Infer a short task label from the activity context
@@ -229,6 +402,56 @@ impl LifeTree {
         ctx.app_name.clone().unwrap_or_else(|| "Unknown Activity".to_string())
     }
 
+    /// Daily observation counts for `stage` over the `days` days ending
+    /// `today`, oldest first, with zeros for days without activity
+    pub fn stage_timeline(
+        &self,
+        stage: &LifeStage,
+        days: u32,
+        today: NaiveDate,
+    ) -> Vec<DailyCount> {
+        let stage_id = format!("stage_{:?}", stage).to_lowercase();
+        let recorded = self
+            .nodes
+            .get(&stage_id)
+            .map(|n| n.daily_counts.as_slice())
+            .unwrap_or_default();
+
+        let days = days.clamp(1, STAGE_HISTORY_DAYS);
+        (0..days)
+            .rev()
+            .map(|offset| {
+                let date = (today - Duration::days(offset as i64)).format("%Y-%m-%d").to_string();
+                let count = recorded.iter().find(|d| d.date == date).map(|d| d.count).unwrap_or(0);
+                DailyCount { date, count }
+            })
+            .collect()
+    }
+
+    /// Bump `day` and drop days older than the retained window
+    fn record_day(counts: &mut Vec<DailyCount>, day: NaiveDate) {
+        let date = day.format("%Y-%m-%d").to_string();
+        match counts.iter_mut().find(|d| d.date == date) {
+            Some(existing) => existing.count += 1,
+            None => {
+                counts.push(DailyCount { date, count: 1 });
+                counts.sort_by(|a, b| a.date.cmp(&b.date));
+            }
+        }
+
+        // ISO dates compare correctly as strings
+        let oldest = (day - Duration::days(STAGE_HISTORY_DAYS as i64 - 1))
+            .format("%Y-%m-%d")
+            .to_string();
+        counts.retain(|d| d.date >= oldest);
+    }
+
+    /// Local calendar day of a millisecond timestamp
+    fn local_day(timestamp_ms: u64) -> Option<NaiveDate> {
+        chrono::DateTime::from_timestamp_millis(timestamp_ms as i64)
+            .map(|dt| dt.with_timezone(&Local).date_naive())
+    }
+
     /// Get a snapshot of the tree
     pub fn snapshot(&self) -> LifeTreeSnapshot {
         let nodes: Vec<LifeTreeNode> = self.nodes.values().cloned().collect();
@@ -253,14 +476,18 @@ impl LifeTree {
                 experiments_completed,
                 most_active_stage,
             },
-            generated_at: Self::now(),
+            generated_at: self.now(),
         }
     }
 
     /// Propose an experiment for a node (returns AI prompt)
-    pub fn build_experiment_prompt(&self, node_id: &str) -> Result<Vec<ChatMessage>, String> {
+    pub fn build_experiment_prompt(
+        &self,
+        node_id: &str,
+        language: &ReplyLanguage,
+    ) -> Result<Vec<ChatMessage>, HawkeyeError> {
         let node = self.nodes.get(node_id)
-            .ok_or_else(|| format!("Node not found: {}", node_id))?;
+            .ok_or_else(|| HawkeyeError::NotFound(format!("Node not found: {}", node_id)))?;
 
         let stage_label = node.stage.as_ref()
             .map(|s| s.label().to_string())
@@ -274,10 +501,12 @@ impl LifeTree {
              - Completable in 1-3 days\n\
              - Specific and measurable\n\
              - Low-risk\n\n\
-             Respond with ONLY a JSON object: {{\"title\": \"...\", \"description\": \"...\", \"duration_days\": N}}",
+             Respond with ONLY a JSON object: {{\"title\": \"...\", \"description\": \"...\", \"duration_days\": N}}\n\
+             {}",
             node.label,
             stage_label,
             node.observation_count,
+            language.json_instruction(),
         );
 
         Ok(vec![ChatMessage {
@@ -286,19 +515,98 @@ impl LifeTree {
         }])
     }
 
-    /// Create an experiment node under a given parent
+    /// Propose an experiment for a whole stage (returns AI prompt). Built
+    /// from the stage's busiest tasks and apps, and scoped to the highest
+    /// unlocked experiment phase.
+    pub fn build_stage_experiment_prompt(
+        &self,
+        stage: &LifeStage,
+        language: &ReplyLanguage,
+    ) -> Result<(String, ExperimentPhase, Vec<ChatMessage>), HawkeyeError> {
+        let stage_id = format!("stage_{:?}", stage).to_lowercase();
+        let node = self.nodes.get(&stage_id)
+            .ok_or_else(|| HawkeyeError::NotFound(format!("Node not found: {}", stage_id)))?;
+        if node.observation_count == 0 {
+            return Err(format!("No activity recorded for {} yet", stage.label()).into());
+        }
+
+        let mut tasks: Vec<&LifeTreeNode> = node.children.iter()
+            .filter_map(|id| self.nodes.get(id))
+            .filter(|n| n.node_type == NodeType::Task)
+            .collect();
+        tasks.sort_by(|a, b| b.observation_count.cmp(&a.observation_count).then(a.label.cmp(&b.label)));
+        let top_tasks = tasks.iter()
+            .take(5)
+            .map(|t| format!("- {} ({} observations)", t.label, t.observation_count))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let apps = if node.related_apps.is_empty() {
+            "unknown".to_string()
+        } else {
+            node.related_apps.join(", ")
+        };
+
+        let phase = self.unlocked_phase();
+        let scope = match phase {
+            ExperimentPhase::TaskLevel =>
+                "a small task-level experiment: one concrete change to a single activity above",
+            ExperimentPhase::GoalLevel =>
+                "a goal-level experiment that works toward a broader goal across these activities",
+            ExperimentPhase::AutomationLevel =>
+                "an automation experiment that automates or systematizes one recurring activity above",
+        };
+
+        let prompt = format!(
+            "You are Hawkeye, an AI life assistant. The user's activity in the \"{}\" life stage \
+             ({} observations) is mostly:\n{}\n\nApps they use here: {}.\n\n\
+             Propose ONE {}. The experiment should be:\n\
+             - Completable in 1-3 days\n\
+             - Specific and measurable\n\
+             - Low-risk\n\n\
+             Respond with ONLY a JSON object: {{\"title\": \"...\", \"description\": \"...\", \"duration_days\": N}}\n\
+             {}",
+            stage.label(),
+            node.observation_count,
+            top_tasks,
+            apps,
+            scope,
+            language.json_instruction(),
+        );
+
+        Ok((stage_id, phase, vec![ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        }]))
+    }
+
+    /// Create an experiment node under a given parent. `phase` must already
+    /// be unlocked. With `duration_days` the experiment becomes due that many
+    /// days from now.
     pub fn create_experiment(
         &mut self,
         parent_id: &str,
         title: String,
         description: String,
         phase: ExperimentPhase,
-    ) -> Result<String, String> {
+        duration_days: Option<u32>,
+    ) -> Result<String, HawkeyeError> {
         if !self.nodes.contains_key(parent_id) {
-            return Err(format!("Parent node not found: {}", parent_id));
+            return Err(HawkeyeError::NotFound(format!(
+                "Parent node not found: {}",
+                parent_id
+            )));
+        }
+
+        let unlocked = self.unlocked_phase();
+        if phase > unlocked {
+            return Err(format!(
+                "Experiment phase {:?} is locked; only up to {:?} is unlocked",
+                phase, unlocked
+            )
+            .into());
         }
 
-        let now = Self::now();
+        let now = self.now();
         let exp_id = format!("exp_{}_{}", parent_id, now);
 
         let stage = self.nodes.get(parent_id).and_then(|n| n.stage.clone());
@@ -318,6 +626,10 @@ impl LifeTree {
             experiment_phase: Some(phase),
             observation_count: 0,
             related_apps: Vec::new(),
+            daily_counts: Vec::new(),
+            due_at: duration_days.map(|days| now + days as u64 * DAY_MS),
+            outcome: None,
+            ocr_snippet: None,
         };
 
         self.nodes.insert(exp_id.clone(), experiment);
@@ -329,21 +641,36 @@ impl LifeTree {
         Ok(exp_id)
     }
 
-    /// Conclude an experiment
-    pub fn conclude_experiment(&mut self, exp_id: &str, succeeded: bool) -> Result<(), String> {
+    /// Conclude an experiment, recording its outcome
+    pub fn conclude_experiment(
+        &mut self,
+        exp_id: &str,
+        succeeded: bool,
+        notes: Option<String>,
+        metrics: HashMap<String, f64>,
+    ) -> Result<(), HawkeyeError> {
+        let now = self.now();
         let node = self.nodes.get_mut(exp_id)
-            .ok_or_else(|| format!("Experiment not found: {}", exp_id))?;
+            .ok_or_else(|| HawkeyeError::NotFound(format!("Experiment not found: {}", exp_id)))?;
 
         if node.node_type != NodeType::Experiment {
-            return Err("Node is not an experiment".to_string());
+            return Err("Node is not an experiment".into());
         }
 
         node.status = if succeeded { NodeStatus::Completed } else { NodeStatus::Failed };
-        node.updated_at = Self::now();
+        node.updated_at = now;
+        node.outcome = Some(ExperimentOutcome {
+            succeeded,
+            notes: notes.filter(|n| !n.trim().is_empty()),
+            metrics,
+            concluded_at: now,
+        });
 
         if succeeded {
-            if let Some(ExperimentPhase::TaskLevel) = &node.experiment_phase {
-                self.phase1_completions += 1;
+            match &node.experiment_phase {
+                Some(ExperimentPhase::TaskLevel) => self.phase1_completions += 1,
+                Some(ExperimentPhase::GoalLevel) => self.goal_completions += 1,
+                _ => {}
             }
         }
 
@@ -351,17 +678,72 @@ impl LifeTree {
         Ok(())
     }
 
+    /// Concluded experiments, most recently concluded first
+    pub fn experiment_history(&self) -> Vec<&LifeTreeNode> {
+        let mut history: Vec<&LifeTreeNode> = self.nodes.values()
+            .filter(|n| n.node_type == NodeType::Experiment)
+            .filter(|n| matches!(n.status, NodeStatus::Completed | NodeStatus::Failed))
+            .collect();
+        // Experiments concluded before outcomes were recorded fall back to
+        // their last update
+        history.sort_by_key(|n| {
+            std::cmp::Reverse(n.outcome.as_ref().map_or(n.updated_at, |o| o.concluded_at))
+        });
+        history
+    }
+
+    /// Active experiments due before `now + within_ms` (overdue included),
+    /// soonest first
+    pub fn due_experiments(&self, now: u64, within_ms: u64) -> Vec<&LifeTreeNode> {
+        let mut due: Vec<&LifeTreeNode> = self.nodes.values()
+            .filter(|n| n.node_type == NodeType::Experiment && n.status == NodeStatus::Active)
+            .filter(|n| n.due_at.is_some_and(|at| at <= now.saturating_add(within_ms)))
+            .collect();
+        due.sort_by_key(|n| n.due_at);
+        due
+    }
+
     /// Get the max unlocked experiment phase
     pub fn unlocked_phase(&self) -> ExperimentPhase {
-        if self.phase1_completions >= 10 {
-            ExperimentPhase::AutomationLevel
-        } else if self.phase1_completions >= 3 {
-            ExperimentPhase::GoalLevel
+        self.phase_progress().unlocked_phase
+    }
+
+    /// Completions so far and how many more unlock the next phase
+    pub fn phase_progress(&self) -> PhaseProgress {
+        let thresholds = self.phase_thresholds;
+        let task = self.phase1_completions;
+        let combined = task + self.goal_completions;
+
+        let (unlocked_phase, next_phase, completions_to_next) = if task < thresholds.goal_level {
+            (
+                ExperimentPhase::TaskLevel,
+                Some(ExperimentPhase::GoalLevel),
+                thresholds.goal_level - task,
+            )
+        } else if combined < thresholds.automation_level {
+            (
+                ExperimentPhase::GoalLevel,
+                Some(ExperimentPhase::AutomationLevel),
+                thresholds.automation_level - combined,
+            )
         } else {
-            ExperimentPhase::TaskLevel
+            (ExperimentPhase::AutomationLevel, None, 0)
+        };
+
+        PhaseProgress {
+            unlocked_phase,
+            task_level_completions: task,
+            goal_level_completions: self.goal_completions,
+            next_phase,
+            completions_to_next,
         }
     }
 
+    /// Apply unlock thresholds from the app config
+    pub fn set_phase_thresholds(&mut self, thresholds: PhaseThresholds) {
+        self.phase_thresholds = thresholds;
+    }
+
     /// Get all experiment nodes
     pub fn experiments(&self) -> Vec<&LifeTreeNode> {
         self.nodes.values()
@@ -369,63 +751,259 @@ impl LifeTree {
             .collect()
     }
 
+    /// Whether the tree has changes not yet persisted to disk
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Flag the tree as needing a save (e.g. after a failed background write)
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Take a snapshot for a background save and clear the dirty flag.
+    /// Returns `None` when there is nothing to persist.
+    pub fn take_pending_save(&mut self) -> Option<PendingSave> {
+        if !self.dirty {
+            return None;
+        }
+        self.dirty = false;
+        Some(PendingSave {
+            path: self.data_path.clone(),
+            snapshot: self.snapshot(),
+            generation: self.saves.issue(),
+            sequence: self.saves.clone(),
+        })
+    }
+
+    /// Save immediately if there are unsaved changes (used on shutdown)
+    pub fn flush(&mut self) -> Result<(), String> {
+        if self.dirty {
+            self.save_to_disk()?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
     /// Save tree to disk
     pub fn save_to_disk(&self) -> Result<(), String> {
-        if let Some(parent) = self.data_path.parent() {
+        let snapshot = self.snapshot();
+        self.replace_file(|| Self::write_snapshot(&self.data_path, &snapshot))
+    }
+
+    /// Replace the tree file with `write`, ordered after every save captured
+    /// so far. Used by anything other than the tree that writes its file
+    /// (e.g. a state import).
+    pub fn replace_file<E>(&self, write: impl FnOnce() -> Result<(), E>) -> Result<(), E> {
+        self.saves.land(self.saves.issue(), write).map(|_| ())
+    }
+
+    /// Write a snapshot to `path`
+    fn write_snapshot(path: &Path, snapshot: &LifeTreeSnapshot) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)
                 .map_err(|e| format!("Failed to create directory: {}", e))?;
         }
 
-        let snapshot = self.snapshot();
-        let json = serde_json::to_string_pretty(&snapshot)
+        let json = serde_json::to_string_pretty(snapshot)
             .map_err(|e| format!("Failed to serialize: {}", e))?;
-        std::fs::write(&self.data_path, json)
-            .map_err(|e| format!("Failed to write: {}", e))?;
+
+        // Write a sibling temp file and rename it over the target so a crash
+        // mid-write can never leave a truncated tree behind. The name is
+        // unique so a background save and an explicit save can't collide.
+        let tmp_path = path.with_extension(format!("json.{}.tmp", uuid::Uuid::new_v4()));
+        {
+            let mut file = std::fs::File::create(&tmp_path)
+                .map_err(|e| format!("Failed to create temp file: {}", e))?;
+            file.write_all(json.as_bytes())
+                .map_err(|e| format!("Failed to write: {}", e))?;
+            file.sync_all()
+                .map_err(|e| format!("Failed to sync: {}", e))?;
+        }
+
+        // Keep the previous good copy around as a load fallback
+        if path.exists() {
+            if let Err(e) = std::fs::copy(path, Self::backup_path(path)) {
+                log::warn!("[LifeTree] Failed to write backup: {}", e);
+            }
+        }
+
+        if let Err(e) = std::fs::rename(&tmp_path, path) {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(format!("Failed to replace tree file: {}", e));
+        }
         Ok(())
     }
 
-    /// Load tree from disk
+    /// Path of the backup copy written before each save
+    fn backup_path(path: &Path) -> PathBuf {
+        path.with_extension("json.bak")
+    }
+
+    /// Read and parse a saved snapshot
+    fn read_snapshot(path: &Path) -> Result<LifeTreeSnapshot, String> {
+        let data = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read: {}", e))?;
+        serde_json::from_str(&data)
+            .map_err(|e| format!("Failed to parse: {}", e))
+    }
+
+    /// Load tree from disk, falling back to the backup if the main file is
+    /// unreadable or corrupt
     fn load_from_disk(&mut self) -> Result<(), String> {
         if !self.data_path.exists() {
             return Err("No saved tree".to_string());
         }
 
-        let data = std::fs::read_to_string(&self.data_path)
-            .map_err(|e| format!("Failed to read: {}", e))?;
-        let snapshot: LifeTreeSnapshot = serde_json::from_str(&data)
-            .map_err(|e| format!("Failed to parse: {}", e))?;
+        let snapshot = match Self::read_snapshot(&self.data_path) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                log::warn!("[LifeTree] {} is unusable ({}), trying backup", self.data_path.display(), e);
+                Self::read_snapshot(&Self::backup_path(&self.data_path))
+                    .map_err(|bak_err| format!("{}; backup: {}", e, bak_err))?
+            }
+        };
+
+        self.apply_snapshot(snapshot);
+        Ok(())
+    }
+
+    /// File the tree is saved to
+    pub fn data_path(&self) -> &Path {
+        &self.data_path
+    }
+
+    /// Replace the in-memory tree with `snapshot` after a state import has
+    /// written it to [`Self::data_path`]. The snapshot must contain its root.
+    pub fn restore(&mut self, snapshot: LifeTreeSnapshot) {
+        self.apply_snapshot(snapshot);
+        self.dirty = false;
+    }
 
+    fn apply_snapshot(&mut self, snapshot: LifeTreeSnapshot) {
         self.root_id = snapshot.root_id;
         self.nodes.clear();
         for node in snapshot.nodes {
             self.nodes.insert(node.id.clone(), node);
         }
 
-        // Count phase1 completions
-        self.phase1_completions = self.nodes.values()
+        // Count completions per phase
+        self.phase1_completions = self.completions(ExperimentPhase::TaskLevel);
+        self.goal_completions = self.completions(ExperimentPhase::GoalLevel);
+    }
+
+    /// Successfully completed experiments of `phase`
+    fn completions(&self, phase: ExperimentPhase) -> u32 {
+        self.nodes.values()
             .filter(|n| {
                 n.node_type == NodeType::Experiment
                     && n.status == NodeStatus::Completed
-                    && n.experiment_phase == Some(ExperimentPhase::TaskLevel)
+                    && n.experiment_phase.as_ref() == Some(&phase)
             })
-            .count() as u32;
-
-        Ok(())
+            .count() as u32
     }
 
     /// Rebuild tree from scratch (clear and re-init)
     pub fn rebuild(&mut self) {
         self.nodes.clear();
         self.phase1_completions = 0;
+        self.goal_completions = 0;
         self.build_empty();
         let _ = self.save_to_disk();
+        self.dirty = false;
     }
 
-    fn now() -> u64 {
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64
+    /// Re-run the current classifier over the tree's task nodes, so rule
+    /// changes apply to past activity too. The tree is the only durable
+    /// record of that activity: each task is re-classified from the app,
+    /// window title and OCR text it was created from, and moves with its
+    /// observation count and experiments to the stage and label the rules
+    /// give now. Moved experiments take the new stage too.
+    /// Tasks that land on the same label merge; nothing is dropped. Per-day
+    /// stage history stays where it was recorded. Returns the number of
+    /// tasks moved.
+    pub fn reclassify(&mut self) -> Result<usize, String> {
+        let mut task_ids: Vec<String> = self
+            .nodes
+            .values()
+            .filter(|n| n.node_type == NodeType::Task)
+            .map(|n| n.id.clone())
+            .collect();
+        task_ids.sort();
+
+        let mut moved = 0;
+        for old_id in task_ids {
+            let Some(task) = self.nodes.get(&old_id) else {
+                continue;
+            };
+            let ctx = ActivityContext {
+                app_name: task.related_apps.first().cloned(),
+                window_title: task.description.clone(),
+                ocr_snippet: task.ocr_snippet.clone(),
+                timestamp: task.updated_at,
+            };
+            if ctx.app_name.is_none() && ctx.window_title.is_none() {
+                continue;
+            }
+            let (stage, label) = self.classify(&ctx);
+            let stage_id = format!("stage_{:?}", stage).to_lowercase();
+            let new_id = format!("task_{}_{}", stage_id, Self::slugify(&label));
+            if new_id == old_id || !self.nodes.contains_key(&stage_id) {
+                continue;
+            }
+
+            let Some(mut task) = self.nodes.remove(&old_id) else {
+                continue;
+            };
+            if let Some(old_stage) = task.parent.as_ref().and_then(|p| self.nodes.get_mut(p)) {
+                old_stage.children.retain(|c| *c != old_id);
+                old_stage.observation_count = old_stage
+                    .observation_count
+                    .saturating_sub(task.observation_count);
+            }
+            for child in &task.children {
+                if let Some(child) = self.nodes.get_mut(child) {
+                    child.parent = Some(new_id.clone());
+                    child.stage = Some(stage.clone());
+                }
+            }
+            if let Some(stage_node) = self.nodes.get_mut(&stage_id) {
+                stage_node.observation_count += task.observation_count;
+            }
+
+            if let Some(existing) = self.nodes.get_mut(&new_id) {
+                existing.observation_count += task.observation_count;
+                existing.created_at = existing.created_at.min(task.created_at);
+                existing.updated_at = existing.updated_at.max(task.updated_at);
+                existing.children.append(&mut task.children);
+                for app in task.related_apps {
+                    if !existing.related_apps.contains(&app) {
+                        existing.related_apps.push(app);
+                    }
+                }
+            } else {
+                task.id = new_id.clone();
+                task.label = label;
+                task.stage = Some(stage);
+                task.parent = Some(stage_id.clone());
+                self.nodes.insert(new_id.clone(), task);
+                if let Some(stage_node) = self.nodes.get_mut(&stage_id) {
+                    stage_node.children.push(new_id);
+                }
+            }
+            moved += 1;
+        }
+
+        if moved > 0 {
+            self.save_to_disk()?;
+            self.dirty = false;
+        }
+        Ok(moved)
+    }
+
+    /// Current time (ms) from the tree's clock
+    pub fn now(&self) -> u64 {
+        self.clock.now_ms()
     }
 
     fn slugify(s: &str) -> String {
@@ -443,3 +1021,361 @@ impl Default for LifeTree {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn temp_tree_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("hawkeye-life-tree-{}", uuid::Uuid::new_v4()))
+            .join("life_tree.json")
+    }
+
+    fn coding_activity() -> ActivityContext {
+        ActivityContext {
+            app_name: Some("Cursor".to_string()),
+            window_title: Some("tree.rs — hawkeye".to_string()),
+            ocr_snippet: None,
+            timestamp: 0,
+        }
+    }
+
+    fn activity(app: &str, title: &str, ocr: Option<&str>) -> ActivityContext {
+        ActivityContext {
+            app_name: Some(app.to_string()),
+            window_title: Some(title.to_string()),
+            ocr_snippet: ocr.map(str::to_string),
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn reclassify_moves_stale_tasks_and_keeps_their_history() {
+        let mut tree = LifeTree::with_data_path(temp_tree_path());
+        tree.process_activity(&coding_activity());
+        tree.process_activity(&coding_activity());
+        let (stage, label) = tree.classify(&coding_activity());
+        let stage_id = format!("stage_{:?}", stage).to_lowercase();
+        let task_id = format!("task_{}_{}", stage_id, LifeTree::slugify(&label));
+
+        // A copy of the task filed under another stage by older rules
+        let other = LifeStage::all().into_iter().find(|s| *s != stage).unwrap();
+        let other_id = format!("stage_{:?}", other).to_lowercase();
+        let stale_id = format!("task_{}_old", other_id);
+        let mut stale = tree.nodes[&task_id].clone();
+        stale.id = stale_id.clone();
+        stale.stage = Some(other);
+        stale.parent = Some(other_id.clone());
+        stale.observation_count = 5;
+        tree.nodes.insert(stale_id.clone(), stale);
+        let other_stage = tree.nodes.get_mut(&other_id).unwrap();
+        other_stage.children.push(stale_id.clone());
+        other_stage.observation_count += 5;
+        let exp_id = tree
+            .create_experiment(
+                &stale_id,
+                "Pomodoro".into(),
+                String::new(),
+                ExperimentPhase::TaskLevel,
+                None,
+            )
+            .unwrap();
+        tree.conclude_experiment(&exp_id, true, None, HashMap::new())
+            .unwrap();
+        let nodes = tree.nodes.len();
+        let stage_count = tree.nodes[&stage_id].observation_count;
+
+        assert_eq!(tree.reclassify().unwrap(), 1);
+        assert_eq!(tree.nodes.len(), nodes - 1);
+        assert!(!tree.nodes.contains_key(&stale_id));
+        assert!(!tree.nodes[&other_id].children.contains(&stale_id));
+        assert_eq!(tree.nodes[&other_id].observation_count, 0);
+        assert_eq!(tree.nodes[&stage_id].observation_count, stage_count + 5);
+
+        // Merged into the current task, experiment and outcome included
+        let task = &tree.nodes[&task_id];
+        assert_eq!(task.observation_count, 7);
+        assert!(task.children.contains(&exp_id));
+        let experiment = &tree.nodes[&exp_id];
+        assert_eq!(experiment.parent.as_deref(), Some(task_id.as_str()));
+        assert!(experiment.outcome.as_ref().unwrap().succeeded);
+        assert_eq!(experiment.stage, Some(stage));
+        assert_eq!(tree.phase_progress().task_level_completions, 1);
+
+        // Already up to date: nothing moves
+        assert_eq!(tree.reclassify().unwrap(), 0);
+    }
+
+    #[test]
+    fn reclassify_keeps_tasks_classified_by_ocr() {
+        let mut tree = LifeTree::with_data_path(temp_tree_path());
+        // Only the OCR text points anywhere
+        let ctx = activity("Preview", "scan.pdf", Some("lecture notes"));
+        let (stage, _) = tree.process_activity(&ctx);
+        assert_eq!(stage, LifeStage::Learning);
+
+        assert_eq!(tree.reclassify().unwrap(), 0);
+        assert!(tree.nodes.contains_key("task_stage_learning_scan_pdf"));
+    }
+
+    #[test]
+    fn overlapping_signals_pick_the_highest_score() {
+        let tree = LifeTree::with_data_path(temp_tree_path());
+
+        // An editor alone is work
+        let plain = tree.classify_stage(&activity("Cursor", "main.rs", None));
+        assert_eq!(plain.stage, LifeStage::Career);
+
+        // ...but reading a tutorial's docs in it outweighs the app
+        let docs = tree.classify_stage(&activity("Cursor", "Rust docs — tutorial", None));
+        assert_eq!(docs.stage, LifeStage::Learning);
+        assert!(docs.confidence < plain.confidence);
+
+        // OCR matches are capped, so noisy text can't outvote the app
+        let noisy = tree.classify_stage(&activity(
+            "Slack",
+            "general",
+            Some("course lecture study tutorial research"),
+        ));
+        assert_eq!(noisy.stage, LifeStage::Career);
+    }
+
+    #[test]
+    fn ties_and_empty_input_are_deterministic() {
+        let tree = LifeTree::with_data_path(temp_tree_path());
+
+        // Finance and Safety both score one title keyword; Finance is listed first
+        let tie = tree.classify_stage(&activity("Preview", "backup of budget.pdf", None));
+        assert_eq!(tie.stage, LifeStage::Finance);
+
+        // A title hit beats an equal score made up of OCR hits
+        let stronger = tree.classify_stage(&activity("Preview", "vpn", Some("tax invoice")));
+        assert_eq!(stronger.stage, LifeStage::Safety);
+
+        let unknown = tree.classify_stage(&activity("Preview", "scan.pdf", None));
+        assert_eq!(unknown.stage, LifeStage::Career);
+        assert_eq!(unknown.score, 0.0);
+        assert_eq!(unknown.confidence, UNCLASSIFIED_CONFIDENCE);
+    }
+
+    #[test]
+    fn stage_timeline_counts_days_and_prunes_old_ones() {
+        let mut tree = LifeTree::with_data_path(temp_tree_path());
+        let day_ms = 24 * 60 * 60 * 1000;
+        let noon = |day: u64| {
+            let mut ctx = coding_activity();
+            ctx.timestamp = day * day_ms + day_ms / 2;
+            ctx
+        };
+
+        tree.process_activity(&noon(1_000));
+        for _ in 0..3 {
+            tree.process_activity(&noon(1_040));
+        }
+        tree.process_activity(&noon(1_042));
+
+        let today = LifeTree::local_day(noon(1_042).timestamp).unwrap();
+        let timeline = tree.stage_timeline(&LifeStage::Career, 3, today);
+        let counts: Vec<u32> = timeline.iter().map(|d| d.count).collect();
+        assert_eq!(counts, vec![3, 0, 1]);
+
+        // Day 1000 fell out of the 30-day window when later days were recorded
+        let stored = &tree.nodes["stage_career"].daily_counts;
+        assert_eq!(stored.len(), 2);
+    }
+
+    #[test]
+    fn stage_prompt_lists_busiest_tasks_at_unlocked_phase() {
+        let mut tree = LifeTree::with_data_path(temp_tree_path());
+        assert!(tree
+            .build_stage_experiment_prompt(&LifeStage::Career, &ReplyLanguage::English)
+            .is_err());
+
+        tree.process_activity(&coding_activity());
+        tree.process_activity(&coding_activity());
+        tree.process_activity(&activity("Cursor", "notes.md — hawkeye", None));
+
+        let (node_id, phase, messages) =
+            tree.build_stage_experiment_prompt(&LifeStage::Career, &ReplyLanguage::Chinese).unwrap();
+        assert_eq!(node_id, "stage_career");
+        assert_eq!(phase, ExperimentPhase::TaskLevel);
+        let prompt = &messages[0].content;
+        assert!(prompt.contains("- tree.rs (2 observations)\n- notes.md (1 observations)"));
+        assert!(prompt.contains("task-level"));
+        assert!(prompt.ends_with(&ReplyLanguage::Chinese.json_instruction()));
+    }
+
+    #[test]
+    fn due_experiments_include_overdue_and_upcoming_only() {
+        let mut tree = LifeTree::with_data_path(temp_tree_path());
+        // Distinct parents: experiment ids embed the creation millisecond
+        let mut start = |parent: &str, days: Option<u32>| {
+            tree.create_experiment(parent, "Try".into(), String::new(), ExperimentPhase::TaskLevel, days)
+                .unwrap()
+        };
+        let soon = start("stage_health", Some(1));
+        let later = start("stage_learning", Some(7));
+        start("stage_finance", None);
+
+        let now = tree.now();
+        let ids = |nodes: Vec<&LifeTreeNode>| nodes.into_iter().map(|n| n.id.clone()).collect::<Vec<_>>();
+        assert!(tree.due_experiments(now, 0).is_empty());
+        assert_eq!(ids(tree.due_experiments(now, 2 * DAY_MS)), vec![soon.clone()]);
+        assert_eq!(ids(tree.due_experiments(now + 8 * DAY_MS, 0)), vec![soon.clone(), later]);
+
+        let metrics = HashMap::from([("walks".to_string(), 3.0)]);
+        tree.conclude_experiment(&soon, true, Some("Mornings worked".into()), metrics).unwrap();
+        assert_eq!(tree.due_experiments(now + 8 * DAY_MS, 0).len(), 1);
+
+        let history = tree.experiment_history();
+        assert_eq!(history.len(), 1);
+        let outcome = history[0].outcome.as_ref().unwrap();
+        assert_eq!(outcome.notes.as_deref(), Some("Mornings worked"));
+        assert_eq!(outcome.metrics["walks"], 3.0);
+
+        let _ = std::fs::remove_dir_all(tree.data_path.parent().unwrap());
+    }
+
+    #[test]
+    fn locked_phases_are_rejected() {
+        let mut tree = LifeTree::with_data_path(temp_tree_path());
+        let mut start = |parent: &str, phase: ExperimentPhase| {
+            tree.create_experiment(parent, "Try".into(), String::new(), phase, None)
+        };
+
+        assert!(start("stage_career", ExperimentPhase::TaskLevel).is_ok());
+        let err = start("stage_health", ExperimentPhase::GoalLevel).unwrap_err();
+        assert!(err.to_string().contains("locked"));
+        assert!(start("stage_finance", ExperimentPhase::AutomationLevel).is_err());
+
+        // Three completed task-level experiments unlock goal level
+        tree.phase1_completions = 3;
+        assert!(tree
+            .create_experiment("stage_health", "Goal".into(), String::new(), ExperimentPhase::GoalLevel, None)
+            .is_ok());
+
+        let _ = std::fs::remove_dir_all(tree.data_path.parent().unwrap());
+    }
+
+    #[test]
+    fn phase_progress_uses_thresholds_and_both_tiers() {
+        let mut tree = LifeTree::with_data_path(temp_tree_path());
+        tree.set_phase_thresholds(PhaseThresholds { goal_level: 1, automation_level: 3 });
+
+        let progress = tree.phase_progress();
+        assert_eq!(progress.unlocked_phase, ExperimentPhase::TaskLevel);
+        assert_eq!(progress.completions_to_next, 1);
+
+        tree.phase1_completions = 1;
+        tree.goal_completions = 1;
+        let progress = tree.phase_progress();
+        assert_eq!(progress.unlocked_phase, ExperimentPhase::GoalLevel);
+        assert_eq!(progress.next_phase, Some(ExperimentPhase::AutomationLevel));
+        assert_eq!(progress.completions_to_next, 1);
+
+        // A goal-level success counts toward the automation unlock
+        tree.goal_completions = 2;
+        assert_eq!(tree.unlocked_phase(), ExperimentPhase::AutomationLevel);
+        assert_eq!(tree.phase_progress().next_phase, None);
+    }
+
+    #[test]
+    fn experiments_come_due_as_the_clock_advances() {
+        let clock = MockClock::new(1_700_000_000_000);
+        let mut tree = LifeTree::with_clock(temp_tree_path(), clock.clone());
+        let exp = tree
+            .create_experiment(
+                "stage_health",
+                "Walk".into(),
+                String::new(),
+                ExperimentPhase::TaskLevel,
+                Some(2),
+            )
+            .unwrap();
+        assert_eq!(tree.nodes[&exp].created_at, 1_700_000_000_000);
+
+        clock.advance(std::time::Duration::from_millis(DAY_MS));
+        assert!(tree.due_experiments(tree.now(), 0).is_empty());
+
+        clock.advance(std::time::Duration::from_millis(DAY_MS));
+        assert_eq!(tree.due_experiments(tree.now(), 0).len(), 1);
+    }
+
+    #[test]
+    fn corrupt_file_falls_back_to_backup() {
+        let path = temp_tree_path();
+        let mut tree = LifeTree::with_data_path(path.clone());
+        tree.process_activity(&coding_activity());
+        tree.save_to_disk().unwrap();
+        // Second save copies the first one to .bak
+        tree.save_to_disk().unwrap();
+        let expected = tree.nodes.len();
+
+        // Simulate a crash that truncated the main file
+        std::fs::write(&path, r#"{"rootId": "ro"#).unwrap();
+
+        let reloaded = LifeTree::with_data_path(path.clone());
+        assert_eq!(reloaded.nodes.len(), expected);
+        assert!(reloaded.nodes.values().any(|n| n.label == "tree.rs"));
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn save_leaves_no_temp_file() {
+        let path = temp_tree_path();
+        let tree = LifeTree::with_data_path(path.clone());
+        tree.save_to_disk().unwrap();
+
+        assert!(path.exists());
+        let leftovers = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn activity_marks_dirty_without_writing() {
+        let path = temp_tree_path();
+        let mut tree = LifeTree::with_data_path(path.clone());
+        assert!(!tree.is_dirty());
+
+        for _ in 0..10 {
+            tree.process_activity(&coding_activity());
+        }
+        assert!(tree.is_dirty());
+        assert!(!path.exists());
+
+        tree.flush().unwrap();
+        assert!(!tree.is_dirty());
+        assert!(path.exists());
+        assert!(tree.take_pending_save().is_none());
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn stale_background_save_does_not_overwrite_a_newer_one() {
+        let path = temp_tree_path();
+        let mut tree = LifeTree::with_data_path(path.clone());
+        tree.mark_dirty();
+        let stale = tree.take_pending_save().unwrap();
+
+        tree.process_activity(&coding_activity());
+        tree.flush().unwrap();
+        let expected = tree.nodes.len();
+
+        // The older snapshot finishes last and must be dropped
+        stale.write().unwrap();
+        let reloaded = LifeTree::with_data_path(path.clone());
+        assert_eq!(reloaded.nodes.len(), expected);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
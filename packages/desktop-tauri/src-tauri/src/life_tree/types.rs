@@ -1,6 +1,7 @@
 //! Life Tree data types
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Life stage categories
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -62,8 +63,8 @@ pub enum NodeStatus {
     Failed,
 }
 
-/// Experiment phase (progressive complexity)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Experiment phase (progressive complexity). Ordered by unlock order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "snake_case")]
 pub enum ExperimentPhase {
     /// Phase 1: Simple task-level experiments
@@ -74,6 +75,38 @@ pub enum ExperimentPhase {
     AutomationLevel,
 }
 
+/// Successful experiments needed to unlock each phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PhaseThresholds {
+    /// Task-level completions that unlock goal level
+    pub goal_level: u32,
+    /// Task- plus goal-level completions that unlock automation level
+    pub automation_level: u32,
+}
+
+impl Default for PhaseThresholds {
+    fn default() -> Self {
+        Self {
+            goal_level: 3,
+            automation_level: 10,
+        }
+    }
+}
+
+/// Where the user stands in the experiment progression
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseProgress {
+    pub unlocked_phase: ExperimentPhase,
+    pub task_level_completions: u32,
+    pub goal_level_completions: u32,
+    /// `None` once every phase is unlocked
+    pub next_phase: Option<ExperimentPhase>,
+    /// Successful experiments still needed to unlock `next_phase`
+    pub completions_to_next: u32,
+}
+
 /// A node in the life tree
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -95,6 +128,45 @@ pub struct LifeTreeNode {
     pub observation_count: u32,
     /// Most recent related apps
     pub related_apps: Vec<String>,
+    /// Stage nodes: observations per local day, oldest first, covering at
+    /// most the last [`STAGE_HISTORY_DAYS`] days
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub daily_counts: Vec<DailyCount>,
+    /// Experiment nodes: when the experiment should be concluded (ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_at: Option<u64>,
+    /// Experiment nodes: what came of it, once concluded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<ExperimentOutcome>,
+    /// Task nodes: OCR text the task was first classified with, so
+    /// reclassification sees the same signals
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr_snippet: Option<String>,
+}
+
+/// Result of a concluded experiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExperimentOutcome {
+    pub succeeded: bool,
+    /// Free-form reflection: why it worked or didn't
+    pub notes: Option<String>,
+    /// User-defined measurements, e.g. "focus_minutes" → 95
+    #[serde(default)]
+    pub metrics: HashMap<String, f64>,
+    pub concluded_at: u64,
+}
+
+/// Days of per-stage history kept on stage nodes
+pub const STAGE_HISTORY_DAYS: u32 = 30;
+
+/// Observations on one local calendar day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyCount {
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub count: u32,
 }
 
 /// Full tree snapshot
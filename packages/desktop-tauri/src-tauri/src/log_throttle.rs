@@ -0,0 +1,192 @@
+//! Deduplicated logging for hot paths
+//!
+//! The observe loop runs every second or so, and a sustained failure (OCR
+//! helper crashing, bad filter pattern, unwritable data dir) would log the
+//! same warning on every frame. [`warn`] logs a message the first time,
+//! then swallows identical copies for [`DEFAULT_WINDOW`]; the next copy
+//! after that is logged with "(repeated N times)" appended. If no copy
+//! comes, the count is flushed on its own the next time anything is logged.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::clock::{self, SharedClock};
+
+/// How long identical messages are collapsed
+pub const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Distinct messages tracked before expired ones are dropped
+const MAX_TRACKED: usize = 256;
+
+#[derive(Debug)]
+struct Seen {
+    logged_at: Instant,
+    suppressed: u32,
+}
+
+#[derive(Debug)]
+pub struct LogThrottle {
+    window: Duration,
+    clock: SharedClock,
+    seen: Mutex<HashMap<String, Seen>>,
+}
+
+impl LogThrottle {
+    pub fn new(window: Duration) -> Self {
+        Self::with_clock(window, clock::system())
+    }
+
+    pub fn with_clock(window: Duration, clock: SharedClock) -> Self {
+        Self {
+            window,
+            clock,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The line to log for `message` now, or `None` while an identical
+    /// message logged less than a window ago is being suppressed
+    pub fn check(&self, message: &str) -> Option<String> {
+        let now = self.clock.instant();
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(entry) = seen.get_mut(message) {
+            if now.duration_since(entry.logged_at) < self.window {
+                entry.suppressed += 1;
+                return None;
+            }
+            let suppressed = std::mem::replace(&mut entry.suppressed, 0);
+            entry.logged_at = now;
+            return Some(repeated(message, suppressed));
+        }
+
+        if seen.len() >= MAX_TRACKED {
+            let window = self.window;
+            seen.retain(|_, entry| now.duration_since(entry.logged_at) < window);
+        }
+        seen.insert(
+            message.to_string(),
+            Seen {
+                logged_at: now,
+                suppressed: 0,
+            },
+        );
+        Some(message.to_string())
+    }
+
+    /// Counts owed by messages whose window ran out with copies still
+    /// suppressed. Expired messages are forgotten, so their next copy is
+    /// logged plainly.
+    pub fn take_expired(&self) -> Vec<String> {
+        let now = self.clock.instant();
+        let window = self.window;
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut lines = Vec::new();
+        seen.retain(|message, entry| {
+            if now.duration_since(entry.logged_at) < window {
+                return true;
+            }
+            if entry.suppressed > 0 {
+                lines.push(repeated(message, entry.suppressed));
+            }
+            false
+        });
+        lines
+    }
+
+    pub fn log(&self, level: log::Level, message: &str) {
+        if let Some(line) = self.check(message) {
+            log::log!(level, "{}", line);
+        }
+        for line in self.take_expired() {
+            log::log!(level, "{}", line);
+        }
+    }
+}
+
+fn repeated(message: &str, suppressed: u32) -> String {
+    match suppressed {
+        0 => message.to_string(),
+        1 => format!("{} (repeated once)", message),
+        n => format!("{} (repeated {} times)", message, n),
+    }
+}
+
+/// Throttle shared by [`warn`]
+pub fn global() -> &'static LogThrottle {
+    static GLOBAL: OnceLock<LogThrottle> = OnceLock::new();
+    GLOBAL.get_or_init(|| LogThrottle::new(DEFAULT_WINDOW))
+}
+
+/// `log::warn!` with identical repeats collapsed
+pub fn warn(message: &str) {
+    global().log(log::Level::Warn, message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn repeats_are_collapsed_until_the_window_passes() {
+        let clock = MockClock::new(0);
+        let throttle = LogThrottle::with_clock(Duration::from_secs(60), clock.clone());
+
+        assert_eq!(
+            throttle.check("[Observe] OCR failed: timeout").as_deref(),
+            Some("[Observe] OCR failed: timeout")
+        );
+        for _ in 0..41 {
+            clock.advance(Duration::from_secs(1));
+            assert_eq!(throttle.check("[Observe] OCR failed: timeout"), None);
+        }
+        // A different message isn't held back
+        assert!(throttle.check("[Observe] Thumbnail failed").is_some());
+
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(
+            throttle.check("[Observe] OCR failed: timeout").as_deref(),
+            Some("[Observe] OCR failed: timeout (repeated 41 times)")
+        );
+        assert_eq!(throttle.check("[Observe] OCR failed: timeout"), None);
+
+        // The copy swallowed just above rides along with the next one
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(
+            throttle.check("[Observe] OCR failed: timeout").as_deref(),
+            Some("[Observe] OCR failed: timeout (repeated once)")
+        );
+        // Quiet for a whole window: logged plainly again
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(
+            throttle.check("[Observe] OCR failed: timeout").as_deref(),
+            Some("[Observe] OCR failed: timeout")
+        );
+    }
+
+    #[test]
+    fn expired_counts_are_flushed_without_another_copy() {
+        let clock = MockClock::new(0);
+        let throttle = LogThrottle::with_clock(Duration::from_secs(60), clock.clone());
+
+        throttle.check("[OCR] Helper failed");
+        throttle.check("[OCR] Helper failed");
+        throttle.check("[OCR] Helper failed");
+        throttle.check("[Observe] Thumbnail failed");
+        assert!(throttle.take_expired().is_empty());
+
+        clock.advance(Duration::from_secs(61));
+        assert_eq!(
+            throttle.take_expired(),
+            vec!["[OCR] Helper failed (repeated 2 times)".to_string()]
+        );
+        assert!(throttle.take_expired().is_empty());
+        assert_eq!(
+            throttle.check("[OCR] Helper failed").as_deref(),
+            Some("[OCR] Helper failed")
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Launch at login
+//!
+//! Registers the app with the OS so it starts, tray-only (`--headless`),
+//! when the user logs in: a LaunchAgent plist on macOS, an XDG autostart
+//! entry on Linux. The entry on disk is the source of truth — the user can
+//! remove it from System Settings without going through Hawkeye — and
+//! `AppConfig::launch_at_login` is synced to it.
+
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// LaunchAgent label / autostart entry name
+const LABEL: &str = "com.hawkeye.app";
+
+/// Whether the OS will launch Hawkeye at login
+pub fn is_enabled() -> Result<bool> {
+    Ok(entry_path()?.exists())
+}
+
+/// Register (pointing at the running executable) or unregister the entry
+pub fn set_enabled(enabled: bool) -> Result<()> {
+    let path = entry_path()?;
+    if !enabled {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", path.display()))
+            }
+            _ => Ok(()),
+        };
+    }
+
+    let exe = std::env::current_exe().context("Failed to locate the Hawkeye executable")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&path, entry_contents(&exe))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Reconcile at startup. The OS entry wins: returns its state so the caller
+/// can sync the config. An existing entry is rewritten so it follows the
+/// app if it was moved or updated.
+pub fn sync_on_startup() -> Result<bool> {
+    let enabled = is_enabled()?;
+    if enabled {
+        set_enabled(true)?;
+    }
+    Ok(enabled)
+}
+
+#[cfg(target_os = "macos")]
+fn entry_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow!("No home directory"))?;
+    Ok(home.join("Library/LaunchAgents").join(format!("{}.plist", LABEL)))
+}
+
+#[cfg(target_os = "linux")]
+fn entry_path() -> Result<PathBuf> {
+    let config = dirs::config_dir().ok_or_else(|| anyhow!("No config directory"))?;
+    Ok(config.join("autostart").join(format!("{}.desktop", LABEL)))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn entry_path() -> Result<PathBuf> {
+    Err(anyhow!("Launch at login is not supported on this platform"))
+}
+
+#[cfg(target_os = "macos")]
+fn entry_contents(exe: &Path) -> String {
+    launch_agent_plist(exe)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn entry_contents(exe: &Path) -> String {
+    desktop_entry(exe)
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn launch_agent_plist(exe: &Path) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+        <string>--headless</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        LABEL,
+        xml_escape(&exe.to_string_lossy())
+    )
+}
+
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+fn desktop_entry(exe: &Path) -> String {
+    format!(
+        "[Desktop Entry]\nType=Application\nName=Hawkeye\nExec=\"{}\" --headless\nX-GNOME-Autostart-enabled=true\n",
+        exe.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_launch_the_executable_headless() {
+        let exe = Path::new("/Applications/Hawk & Eye.app/Contents/MacOS/hawkeye");
+        let plist = launch_agent_plist(exe);
+        assert!(plist.contains("<string>/Applications/Hawk &amp; Eye.app/Contents/MacOS/hawkeye</string>"));
+        assert!(plist.contains("<string>--headless</string>"));
+        assert!(plist.contains("<string>com.hawkeye.app</string>"));
+
+        let desktop = desktop_entry(Path::new("/opt/hawkeye/hawkeye"));
+        assert!(desktop.contains("Exec=\"/opt/hawkeye/hawkeye\" --headless\n"));
+    }
+}
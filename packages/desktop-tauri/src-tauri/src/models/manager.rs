@@ -1,10 +1,25 @@
 //! Model download and lifecycle manager
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
-use tokio::sync::watch;
+use tokio::sync::{watch, RwLock};
 
 use super::registry::{ModelInfo, ModelType};
+use crate::clock::{Clock, SystemClock};
+use crate::error::HawkeyeError;
+
+/// Minimum spacing between `Downloading` progress callbacks (~10/sec)
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Smallest byte step between progress callbacks
+const MIN_PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+
+/// Downloads stream into `<filename>.part` and are renamed on success
+const PARTIAL_EXTENSION: &str = "part";
+
+/// `.part` files untouched for this long are treated as abandoned
+pub const STALE_PARTIAL_AGE: Duration = Duration::from_secs(60 * 60);
 
 /// Status of a local model
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +46,14 @@ pub struct DownloadProgress {
     pub status: DownloadStatus,
 }
 
+/// What `cleanup_partial_downloads` removed
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialCleanup {
+    pub removed_files: usize,
+    pub reclaimed_bytes: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum DownloadStatus {
@@ -41,6 +64,19 @@ pub enum DownloadStatus {
     Cancelled,
 }
 
+/// What `relocate` did
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocateReport {
+    pub models_dir: String,
+    /// Model files moved from the old directory
+    pub moved: usize,
+    /// Models the new directory already had; the old copies are left alone
+    pub already_present: usize,
+    /// Manifest of the new directory after merging
+    pub models: Vec<LocalModel>,
+}
+
 /// Model manager — handles model directory, downloads, and listing
 pub struct ModelManager {
     models_dir: PathBuf,
@@ -49,19 +85,119 @@ pub struct ModelManager {
 
 impl ModelManager {
     pub fn new() -> Self {
-        let models_dir = Self::default_models_dir();
+        Self::with_dir(None)
+    }
+
+    /// Manager for `dir`, or the default directory when `None`
+    /// (`AppConfig::models_dir_override`)
+    pub fn with_dir(dir: Option<PathBuf>) -> Self {
         Self {
-            models_dir,
+            models_dir: dir
+                .filter(|d| !d.as_os_str().is_empty())
+                .unwrap_or_else(Self::default_models_dir),
             cancel_tx: None,
         }
     }
 
     /// Default model storage directory
-    fn default_models_dir() -> PathBuf {
-        dirs::data_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("com.hawkeye.desktop")
-            .join("models")
+    pub fn default_models_dir() -> PathBuf {
+        crate::paths::data_dir().join("models")
+    }
+
+    /// Point the manager at `new_dir`, which must be writable. Downloaded
+    /// models move along (a rename, or copy + delete across disks) unless
+    /// `move_files` is false, in which case only models already in
+    /// `new_dir` stay listed. The new directory's own manifest is merged,
+    /// so a disk that already holds models keeps them. If a move fails, the
+    /// models already moved are put back and the manager stays where it was.
+    pub fn relocate(&mut self, new_dir: &Path, move_files: bool) -> Result<RelocateReport, String> {
+        if self.cancel_tx.is_some() {
+            return Err("Can't move models while a download is running".to_string());
+        }
+        if new_dir == self.models_dir {
+            return Ok(RelocateReport {
+                models_dir: new_dir.to_string_lossy().to_string(),
+                models: self.list_models()?,
+                ..RelocateReport::default()
+            });
+        }
+        check_writable(new_dir)?;
+
+        let current = self.list_models()?;
+        let target = Self::with_dir(Some(new_dir.to_path_buf()));
+        let mut merged = target.list_models()?;
+        let mut report = RelocateReport {
+            models_dir: new_dir.to_string_lossy().to_string(),
+            ..RelocateReport::default()
+        };
+        let mut left_behind = Vec::new();
+        let mut moved = Vec::new();
+
+        for mut model in current {
+            let dest = new_dir.join(&model.filename);
+            if dest.exists() {
+                report.already_present += 1;
+            } else if move_files {
+                if let Err(e) = move_file(Path::new(&model.path), &dest) {
+                    return Err(self.undo_moves(&target, moved, e));
+                }
+                moved.push((model.clone(), dest.clone()));
+                report.moved += 1;
+            } else {
+                left_behind.push(model);
+                continue;
+            }
+            model.path = dest.to_string_lossy().to_string();
+            merged.retain(|m| m.id != model.id);
+            merged.push(model);
+        }
+
+        target.save_manifest(&merged)?;
+        if self.manifest_path().exists() {
+            self.save_manifest(&left_behind)?;
+        }
+        self.models_dir = new_dir.to_path_buf();
+        report.models = merged;
+        Ok(report)
+    }
+
+    /// Put back the models a failed [`Self::relocate`] already moved. Any
+    /// that can't be moved back are added to `target`'s manifest and
+    /// dropped from this one, so no model goes missing from both.
+    fn undo_moves(
+        &self,
+        target: &ModelManager,
+        moved: Vec<(LocalModel, PathBuf)>,
+        error: String,
+    ) -> String {
+        let mut stranded = Vec::new();
+        for (model, dest) in moved.into_iter().rev() {
+            if move_file(&dest, Path::new(&model.path)).is_err() {
+                stranded.push(LocalModel {
+                    path: dest.to_string_lossy().to_string(),
+                    ..model
+                });
+            }
+        }
+        if stranded.is_empty() {
+            return format!("{}; models already moved were put back", error);
+        }
+
+        let is_stranded = |m: &LocalModel| stranded.iter().any(|s| s.id == m.id);
+        let mut in_target = target.list_models().unwrap_or_default();
+        in_target.retain(|m| !is_stranded(m));
+        in_target.extend(stranded.iter().cloned());
+        let mut in_self = self.list_models().unwrap_or_default();
+        in_self.retain(|m| !is_stranded(m));
+        if let Err(e) = target.save_manifest(&in_target).and(self.save_manifest(&in_self)) {
+            log::warn!("[Models] Failed to record partially moved models: {}", e);
+        }
+        format!(
+            "{}; {} model(s) could not be moved back and stay in {}",
+            error,
+            stranded.len(),
+            target.models_dir.display()
+        )
     }
 
     /// Ensure models directory exists
@@ -105,12 +241,12 @@ impl ModelManager {
     }
 
     /// Delete a model by ID
-    pub fn delete_model(&self, model_id: &str) -> Result<(), String> {
+    pub fn delete_model(&self, model_id: &str) -> Result<(), HawkeyeError> {
         let mut models = self.list_models()?;
         let idx = models
             .iter()
             .position(|m| m.id == model_id)
-            .ok_or_else(|| format!("Model not found: {}", model_id))?;
+            .ok_or_else(|| HawkeyeError::NotFound(format!("Model not found: {}", model_id)))?;
 
         let model = &models[idx];
         let path = PathBuf::from(&model.path);
@@ -124,113 +260,120 @@ impl ModelManager {
         Ok(())
     }
 
-    /// Download a model from the registry
+    /// Download a model from the registry. `hf_token` is sent to
+    /// huggingface.co for gated models. `manager` is locked only to start
+    /// the download and to record it, so cancelling, listing and cleanup
+    /// don't wait for the transfer. One download runs at a time.
     pub async fn download_model<F>(
-        &mut self,
+        manager: &RwLock<ModelManager>,
         model_info: &ModelInfo,
+        hf_token: Option<&str>,
         on_progress: F,
-    ) -> Result<LocalModel, String>
+    ) -> Result<LocalModel, HawkeyeError>
     where
-        F: Fn(DownloadProgress) + Send + 'static,
+        F: Fn(DownloadProgress) + Send + Sync + 'static,
     {
-        self.ensure_dir()?;
-
-        let dest_path = self.models_dir.join(&model_info.filename);
+        Self::download_model_with_interval(
+            manager,
+            model_info,
+            hf_token,
+            PROGRESS_INTERVAL,
+            on_progress,
+        )
+        .await
+    }
 
-        // Create cancellation channel
-        let (cancel_tx, cancel_rx) = watch::channel(false);
-        self.cancel_tx = Some(cancel_tx);
+    /// [`download_model`](Self::download_model) with a custom minimum
+    /// spacing between `Downloading` callbacks. `Starting` and the terminal
+    /// `Completed` / `Failed` / `Cancelled` callbacks are never throttled.
+    pub async fn download_model_with_interval<F>(
+        manager: &RwLock<ModelManager>,
+        model_info: &ModelInfo,
+        hf_token: Option<&str>,
+        progress_interval: Duration,
+        on_progress: F,
+    ) -> Result<LocalModel, HawkeyeError>
+    where
+        F: Fn(DownloadProgress) + Send + Sync + 'static,
+    {
+        // `cancel_tx` marks the download as running until it is recorded,
+        // which also keeps `relocate` from moving the directory under it
+        let (dest_path, cancel_rx) = {
+            let mut mgr = manager.write().await;
+            if mgr.cancel_tx.is_some() {
+                return Err("Another model download is already running".into());
+            }
+            mgr.ensure_dir()?;
+            let (cancel_tx, cancel_rx) = watch::channel(false);
+            mgr.cancel_tx = Some(cancel_tx);
+            (mgr.models_dir.join(&model_info.filename), cancel_rx)
+        };
+        let partial_path = partial_path(&dest_path);
 
         let model_id = model_info.id.clone();
         let filename = model_info.filename.clone();
+        let report = |downloaded_bytes: u64, total_bytes: u64, progress: f64, status: DownloadStatus| {
+            on_progress(DownloadProgress {
+                model_id: model_id.clone(),
+                filename: filename.clone(),
+                downloaded_bytes,
+                total_bytes,
+                progress,
+                status,
+            });
+        };
 
-        on_progress(DownloadProgress {
-            model_id: model_id.clone(),
-            filename: filename.clone(),
-            downloaded_bytes: 0,
-            total_bytes: model_info.size_bytes,
-            progress: 0.0,
-            status: DownloadStatus::Starting,
-        });
-
-        // Perform download with reqwest
-        let client = reqwest::Client::builder()
-            .redirect(reqwest::redirect::Policy::limited(10))
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-        let response = client
-            .get(&model_info.download_url)
-            .send()
-            .await
-            .map_err(|e| format!("Download request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("Download failed with status: {}", response.status()));
-        }
-
-        let total_bytes = response.content_length().unwrap_or(model_info.size_bytes);
-
-        let mut file = tokio::fs::File::create(&dest_path)
-            .await
-            .map_err(|e| format!("Failed to create file: {}", e))?;
+        report(0, model_info.size_bytes, 0.0, DownloadStatus::Starting);
 
         let mut downloaded: u64 = 0;
-        let mut stream = response.bytes_stream();
-        use futures_util::StreamExt;
-        use tokio::io::AsyncWriteExt;
-
-        while let Some(chunk_result) = stream.next().await {
-            // Check cancellation
-            if *cancel_rx.borrow() {
-                drop(file);
-                let _ = tokio::fs::remove_file(&dest_path).await;
-                on_progress(DownloadProgress {
-                    model_id: model_id.clone(),
-                    filename: filename.clone(),
-                    downloaded_bytes: downloaded,
+        let mut total_bytes = model_info.size_bytes;
+        let result = match open_download(model_info, hf_token).await {
+            Ok(response) => {
+                total_bytes = response.content_length().unwrap_or(model_info.size_bytes);
+                Self::stream_to_file(
+                    response,
+                    &partial_path,
+                    &cancel_rx,
+                    progress_interval,
                     total_bytes,
-                    progress: 0.0,
-                    status: DownloadStatus::Cancelled,
-                });
-                self.cancel_tx = None;
-                return Err("Download cancelled".to_string());
+                    &mut downloaded,
+                    &report,
+                )
+                .await
             }
+            Err(e) => Err(e),
+        };
 
-            let chunk = chunk_result
-                .map_err(|e| format!("Download stream error: {}", e))?;
-
-            file.write_all(&chunk)
+        // A redirect to an HTML page (e.g. a login wall for gated models)
+        // "succeeds" with a bogus file; sniff the header before accepting it
+        let result = match result {
+            Ok(()) => {
+                verify_magic(&partial_path, &model_info.model_type).map_err(HawkeyeError::from)
+            }
+            Err(e) => Err(e),
+        };
+        let result = match result {
+            Ok(()) => tokio::fs::rename(&partial_path, &dest_path)
                 .await
-                .map_err(|e| format!("Write error: {}", e))?;
+                .map_err(|e| format!("Failed to move download into place: {}", e).into()),
+            Err(e) => Err(e),
+        };
+
+        let mut mgr = manager.write().await;
+        mgr.cancel_tx = None;
 
-            downloaded += chunk.len() as u64;
-            let progress = if total_bytes > 0 {
-                downloaded as f64 / total_bytes as f64
+        if let Err(e) = result {
+            let _ = tokio::fs::remove_file(&partial_path).await;
+            let status = if *cancel_rx.borrow() {
+                DownloadStatus::Cancelled
             } else {
-                0.0
+                DownloadStatus::Failed
             };
-
-            on_progress(DownloadProgress {
-                model_id: model_id.clone(),
-                filename: filename.clone(),
-                downloaded_bytes: downloaded,
-                total_bytes,
-                progress,
-                status: DownloadStatus::Downloading,
-            });
+            report(downloaded, total_bytes, 0.0, status);
+            return Err(e);
         }
 
-        file.flush()
-            .await
-            .map_err(|e| format!("Flush error: {}", e))?;
-
-        self.cancel_tx = None;
-
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
+        let now = SystemClock.now_ms();
 
         let local_model = LocalModel {
             id: model_info.id.clone(),
@@ -243,33 +386,125 @@ impl ModelManager {
         };
 
         // Save to manifest
-        let mut models = self.list_models().unwrap_or_default();
+        let mut models = mgr.list_models().unwrap_or_default();
         models.retain(|m| m.id != model_info.id);
         models.push(local_model.clone());
-        self.save_manifest(&models)?;
+        if let Err(e) = mgr.save_manifest(&models) {
+            report(downloaded, total_bytes, 0.0, DownloadStatus::Failed);
+            return Err(e.into());
+        }
 
-        on_progress(DownloadProgress {
-            model_id: model_id.clone(),
-            filename: filename.clone(),
-            downloaded_bytes: downloaded,
-            total_bytes,
-            progress: 1.0,
-            status: DownloadStatus::Completed,
-        });
+        report(downloaded, total_bytes, 1.0, DownloadStatus::Completed);
 
         Ok(local_model)
     }
 
+    /// Stream `response` into `dest_path`, reporting throttled
+    /// `Downloading` progress. The byte count is written through so the
+    /// caller can report it on failure.
+    async fn stream_to_file(
+        response: reqwest::Response,
+        dest_path: &Path,
+        cancel_rx: &watch::Receiver<bool>,
+        progress_interval: Duration,
+        total_bytes: u64,
+        downloaded: &mut u64,
+        report: &(dyn Fn(u64, u64, f64, DownloadStatus) + Send + Sync),
+    ) -> Result<(), HawkeyeError> {
+        use futures_util::StreamExt;
+        use tokio::io::AsyncWriteExt;
+
+        let mut file = tokio::fs::File::create(dest_path)
+            .await
+            .map_err(|e| format!("Failed to create file: {}", e))?;
+
+        let mut throttle = ProgressThrottle::new(progress_interval, total_bytes);
+        let mut stream = response.bytes_stream();
+
+        while let Some(chunk_result) = stream.next().await {
+            // Check cancellation
+            if *cancel_rx.borrow() {
+                return Err(HawkeyeError::Cancelled("Download cancelled".to_string()));
+            }
+
+            let chunk = chunk_result
+                .map_err(|e| HawkeyeError::Network(format!("Download stream error: {}", e)))?;
+
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Write error: {}", e))?;
+
+            *downloaded += chunk.len() as u64;
+            if throttle.should_emit(*downloaded) {
+                let progress = if total_bytes > 0 {
+                    *downloaded as f64 / total_bytes as f64
+                } else {
+                    0.0
+                };
+                report(*downloaded, total_bytes, progress, DownloadStatus::Downloading);
+            }
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| format!("Flush error: {}", e).into())
+    }
+
     /// Cancel ongoing download
-    pub fn cancel_download(&mut self) {
+    pub fn cancel_download(&self) {
         if let Some(tx) = &self.cancel_tx {
             let _ = tx.send(true);
         }
     }
 
+    /// Remove `.part` files last modified at least `older_than` ago, left
+    /// behind by a crash or an interrupted download. Does nothing while a
+    /// download is running.
+    pub fn cleanup_partial_downloads(&self, older_than: Duration) -> Result<PartialCleanup, String> {
+        let mut cleanup = PartialCleanup::default();
+        if self.cancel_tx.is_some() || !self.models_dir.exists() {
+            return Ok(cleanup);
+        }
+
+        let entries = std::fs::read_dir(&self.models_dir)
+            .map_err(|e| format!("Failed to read models directory: {}", e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(PARTIAL_EXTENSION) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let age = metadata
+                .modified()
+                .ok()
+                .and_then(|m| m.elapsed().ok())
+                .unwrap_or_default();
+            if !metadata.is_file() || age < older_than {
+                continue;
+            }
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    cleanup.removed_files += 1;
+                    cleanup.reclaimed_bytes += metadata.len();
+                }
+                Err(e) => log::warn!("[Models] Failed to remove {}: {}", path.display(), e),
+            }
+        }
+        Ok(cleanup)
+    }
+
+    /// Where the manifest lives. Entries whose files are missing are
+    /// ignored by [`Self::list_models`], so an imported manifest can be
+    /// written here as is.
+    pub fn manifest_path(&self) -> PathBuf {
+        self.models_dir.join("manifest.json")
+    }
+
     /// Save model manifest to disk
     fn save_manifest(&self, models: &[LocalModel]) -> Result<(), String> {
-        let manifest_path = self.models_dir.join("manifest.json");
+        let manifest_path = self.manifest_path();
         let json = serde_json::to_string_pretty(models)
             .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
         std::fs::write(&manifest_path, json)
@@ -287,8 +522,345 @@ impl ModelManager {
     }
 }
 
+/// Create `dir` if needed and prove a file can be written there
+fn check_writable(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Can't create models directory {}: {}", dir.display(), e))?;
+    let probe = dir.join(".hawkeye-write-test");
+    std::fs::write(&probe, b"ok")
+        .map_err(|e| format!("Models directory {} is not writable: {}", dir.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Rename, falling back to copy + delete when `to` is on another disk
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    let partial = partial_path(to);
+    std::fs::copy(from, &partial)
+        .and_then(|_| std::fs::rename(&partial, to))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&partial);
+            format!("Failed to move {}: {}", from.display(), e)
+        })?;
+    std::fs::remove_file(from).map_err(|e| format!("Failed to remove {}: {}", from.display(), e))
+}
+
+/// Rate limit for download progress: a callback fires only once both the
+/// interval has passed and 1% or 1 MB (whichever is larger) has arrived
+struct ProgressThrottle {
+    interval: Duration,
+    step_bytes: u64,
+    last_at: Option<Instant>,
+    last_bytes: u64,
+}
+
+impl ProgressThrottle {
+    fn new(interval: Duration, total_bytes: u64) -> Self {
+        Self {
+            interval,
+            step_bytes: (total_bytes / 100).max(MIN_PROGRESS_STEP_BYTES),
+            last_at: None,
+            last_bytes: 0,
+        }
+    }
+
+    fn should_emit(&mut self, downloaded: u64) -> bool {
+        let due = self.last_at.map_or(true, |t| t.elapsed() >= self.interval);
+        if !due || downloaded - self.last_bytes < self.step_bytes {
+            return false;
+        }
+        self.last_at = Some(Instant::now());
+        self.last_bytes = downloaded;
+        true
+    }
+}
+
+/// `<dest>.part`, where a download streams before it is verified
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(PARTIAL_EXTENSION);
+    dest.with_file_name(name)
+}
+
+fn is_huggingface(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h == "huggingface.co" || h.ends_with(".huggingface.co")))
+        .unwrap_or(false)
+}
+
+/// Start the GET for `model_info`, authenticating to Hugging Face when a
+/// token is given. Auth walls (401/403, or an HTML page in place of the
+/// file) are turned into an "authentication required" error.
+async fn open_download(
+    model_info: &ModelInfo,
+    hf_token: Option<&str>,
+) -> Result<reqwest::Response, HawkeyeError> {
+    // reqwest drops the Authorization header when a redirect leaves the
+    // original host, so the token never reaches the CDN
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::limited(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let huggingface = is_huggingface(&model_info.download_url);
+    let mut request = client.get(&model_info.download_url);
+    if let Some(token) = hf_token.filter(|t| huggingface && !t.is_empty()) {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| HawkeyeError::Network(format!("Download request failed: {}", e)))?;
+
+    let status = response.status();
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+
+    if status == reqwest::StatusCode::UNAUTHORIZED
+        || status == reqwest::StatusCode::FORBIDDEN
+        || (status.is_success() && is_html)
+    {
+        let hint = if huggingface && hf_token.map_or(true, str::is_empty) {
+            "Set a Hugging Face access token (hfToken) in settings and accept the model's license on huggingface.co."
+        } else {
+            "Check that your token has access to this model and that its license has been accepted."
+        };
+        return Err(format!(
+            "Authentication required to download {} ({}). {}",
+            model_info.name, status, hint
+        )
+        .into());
+    }
+
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(HawkeyeError::NotFound(format!(
+            "Download failed with status: {}",
+            status
+        )));
+    }
+    if !status.is_success() {
+        return Err(format!("Download failed with status: {}", status).into());
+    }
+
+    Ok(response)
+}
+
+/// File-format magic expected for a model type
+fn expected_magic(model_type: &ModelType) -> Option<(&'static str, &'static [&'static [u8]])> {
+    match model_type {
+        ModelType::TextLlm | ModelType::VisionLlm | ModelType::Embedding => {
+            Some(("GGUF", &[b"GGUF"]))
+        }
+        // whisper.cpp writes the ggml magic as a little-endian u32
+        ModelType::Whisper => Some(("ggml", &[b"lmgg", b"ggml"])),
+        // ONNX is a protobuf ModelProto whose first field is `ir_version` (field 1, varint)
+        ModelType::Vad => Some(("ONNX", &[&[0x08]])),
+        ModelType::Tts => None,
+    }
+}
+
+/// Check that `header` starts with the magic bytes for `model_type`
+fn check_magic(header: &[u8], model_type: &ModelType) -> Result<(), String> {
+    let Some((format, magics)) = expected_magic(model_type) else {
+        return Ok(());
+    };
+    if magics.iter().any(|magic| header.starts_with(magic)) {
+        return Ok(());
+    }
+
+    let looks_like_html = header.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'<');
+    if looks_like_html {
+        Err(format!(
+            "Downloaded file is an HTML page, not a {} model. The model may be gated \
+             (requires a login or license acceptance) or the URL has moved.",
+            format
+        ))
+    } else {
+        Err(format!(
+            "Downloaded file is not a valid {} model (unexpected header {:02x?})",
+            format, header
+        ))
+    }
+}
+
+fn verify_magic(path: &Path, model_type: &ModelType) -> Result<(), String> {
+    use std::io::Read;
+
+    let mut header = Vec::with_capacity(16);
+    std::fs::File::open(path)
+        .and_then(|f| f.take(16).read_to_end(&mut header))
+        .map_err(|e| format!("Failed to read downloaded file: {}", e))?;
+    check_magic(&header, model_type)
+}
+
 impl Default for ModelManager {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_waits_for_byte_step() {
+        const MB: u64 = 1024 * 1024;
+        let mut throttle = ProgressThrottle::new(Duration::ZERO, 500 * MB);
+
+        // 1% of 500 MB = 5 MB per step
+        assert!(!throttle.should_emit(MB));
+        assert!(throttle.should_emit(5 * MB));
+        assert!(!throttle.should_emit(9 * MB));
+        assert!(throttle.should_emit(10 * MB));
+    }
+
+    #[test]
+    fn magic_check_rejects_html_and_accepts_model_headers() {
+        assert!(check_magic(b"GGUF\x03\x00\x00\x00", &ModelType::TextLlm).is_ok());
+        assert!(check_magic(b"lmgg\x00\x00", &ModelType::Whisper).is_ok());
+        assert!(check_magic(&[0x08, 0x07, 0x12], &ModelType::Vad).is_ok());
+
+        let err = check_magic(b"\n<!DOCTYPE html>", &ModelType::TextLlm).unwrap_err();
+        assert!(err.contains("HTML"));
+        assert!(check_magic(b"GGUF", &ModelType::Whisper).is_err());
+    }
+
+    #[test]
+    fn cleanup_removes_only_partial_files() {
+        let dir = std::env::temp_dir().join(format!("hawkeye-models-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("model.gguf"), b"GGUF").unwrap();
+        std::fs::write(partial_path(&dir.join("other.gguf")), vec![0u8; 10]).unwrap();
+
+        let mut mgr = ModelManager { models_dir: dir.clone(), cancel_tx: None };
+        let fresh = mgr.cleanup_partial_downloads(STALE_PARTIAL_AGE).unwrap();
+        assert_eq!(fresh.removed_files, 0);
+
+        // A running download keeps its partial
+        let (tx, _rx) = watch::channel(false);
+        mgr.cancel_tx = Some(tx);
+        assert_eq!(mgr.cleanup_partial_downloads(Duration::ZERO).unwrap().removed_files, 0);
+        mgr.cancel_tx = None;
+
+        let stale = mgr.cleanup_partial_downloads(Duration::ZERO).unwrap();
+        assert_eq!(stale.removed_files, 1);
+        assert_eq!(stale.reclaimed_bytes, 10);
+        assert!(dir.join("model.gguf").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn a_running_download_refuses_another_without_holding_the_lock() {
+        let dir = std::env::temp_dir().join(format!("hawkeye-models-{}", uuid::Uuid::new_v4()));
+        let (tx, rx) = watch::channel(false);
+        let manager = RwLock::new(ModelManager {
+            models_dir: dir.clone(),
+            cancel_tx: Some(tx),
+        });
+        let info = crate::models::registry::recommended_models().remove(0);
+
+        let err = ModelManager::download_model(&manager, &info, None, |_| {})
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already running"), "{}", err);
+
+        // Cancelling only needs a read lock
+        manager.read().await.cancel_download();
+        assert!(*rx.borrow());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn relocate_moves_models_and_merges_the_target_manifest() {
+        let root = std::env::temp_dir().join(format!("hawkeye-models-{}", uuid::Uuid::new_v4()));
+        let (old_dir, new_dir) = (root.join("old"), root.join("new"));
+        let model = |dir: &Path, id: &str| {
+            let filename = format!("{}.gguf", id);
+            std::fs::write(dir.join(&filename), b"GGUF").unwrap();
+            LocalModel {
+                id: id.to_string(),
+                name: id.to_string(),
+                model_type: ModelType::TextLlm,
+                path: dir.join(&filename).to_string_lossy().to_string(),
+                filename,
+                size_bytes: 4,
+                downloaded_at: 0,
+            }
+        };
+
+        let mut mgr = ModelManager::with_dir(Some(old_dir.clone()));
+        mgr.ensure_dir().unwrap();
+        mgr.save_manifest(&[model(&old_dir, "qwen")]).unwrap();
+
+        // The new disk already holds a model of its own
+        let target = ModelManager::with_dir(Some(new_dir.clone()));
+        target.ensure_dir().unwrap();
+        target.save_manifest(&[model(&new_dir, "llama")]).unwrap();
+
+        let report = mgr.relocate(&new_dir, true).unwrap();
+        assert_eq!((report.moved, report.already_present), (1, 0));
+        assert_eq!(mgr.models_dir(), &new_dir);
+        assert!(!old_dir.join("qwen.gguf").exists());
+        assert_eq!(mgr.model_path("qwen"), Some(new_dir.join("qwen.gguf")));
+        assert!(mgr.model_exists("llama"));
+        let old = ModelManager::with_dir(Some(old_dir));
+        assert!(old.list_models().unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn failed_relocate_puts_moved_models_back() {
+        let root = std::env::temp_dir().join(format!("hawkeye-models-{}", uuid::Uuid::new_v4()));
+        let (old_dir, new_dir) = (root.join("old"), root.join("new"));
+        let mut mgr = ModelManager::with_dir(Some(old_dir.clone()));
+        mgr.ensure_dir().unwrap();
+        let model = |id: &str, filename: &str| {
+            let path = old_dir.join(format!("{}.gguf", id));
+            std::fs::write(&path, b"GGUF").unwrap();
+            LocalModel {
+                id: id.to_string(),
+                name: id.to_string(),
+                model_type: ModelType::TextLlm,
+                filename: filename.to_string(),
+                path: path.to_string_lossy().to_string(),
+                size_bytes: 4,
+                downloaded_at: 0,
+            }
+        };
+        // The second move fails: its target sits in a missing subdirectory
+        mgr.save_manifest(&[
+            model("qwen", "qwen.gguf"),
+            model("llama", "missing/llama.gguf"),
+        ])
+        .unwrap();
+
+        let err = mgr.relocate(&new_dir, true).unwrap_err();
+        assert!(err.contains("put back"), "{}", err);
+        assert_eq!(mgr.models_dir(), &old_dir);
+        assert!(old_dir.join("qwen.gguf").exists());
+        assert!(!new_dir.join("qwen.gguf").exists());
+        assert_eq!(mgr.list_models().unwrap().len(), 2);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn throttle_caps_rate() {
+        let mut throttle = ProgressThrottle::new(Duration::from_secs(3600), 0);
+
+        assert!(throttle.should_emit(MIN_PROGRESS_STEP_BYTES));
+        assert!(!throttle.should_emit(100 * MIN_PROGRESS_STEP_BYTES));
+    }
+}
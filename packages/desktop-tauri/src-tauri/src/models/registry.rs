@@ -25,6 +25,10 @@ pub struct ModelInfo {
     pub size_bytes: u64,
     pub download_url: String,
     pub filename: String,
+    /// Requires a Hugging Face token with the license accepted
+    /// (`AppConfig::hf_token`)
+    #[serde(default)]
+    pub gated: bool,
 }
 
 impl ModelInfo {
@@ -39,7 +43,10 @@ impl ModelInfo {
     }
 }
 
-/// Get all recommended models
+/// Get all recommended models.
+///
+/// None of these are gated: the Llama entry points at bartowski's public
+/// GGUF mirror rather than the gated `meta-llama` repo.
 pub fn recommended_models() -> Vec<ModelInfo> {
     vec![
         // Text LLMs (GGUF)
@@ -51,6 +58,7 @@ pub fn recommended_models() -> Vec<ModelInfo> {
             size_bytes: 2_000_000_000,
             download_url: "https://huggingface.co/Qwen/Qwen2.5-3B-Instruct-GGUF/resolve/main/qwen2.5-3b-instruct-q4_k_m.gguf".to_string(),
             filename: "qwen2.5-3b-instruct-q4_k_m.gguf".to_string(),
+            gated: false,
         },
         ModelInfo {
             id: "llama3.2-3b-q4".to_string(),
@@ -60,6 +68,7 @@ pub fn recommended_models() -> Vec<ModelInfo> {
             size_bytes: 2_019_000_000,
             download_url: "https://huggingface.co/bartowski/Llama-3.2-3B-Instruct-GGUF/resolve/main/Llama-3.2-3B-Instruct-Q4_K_M.gguf".to_string(),
             filename: "Llama-3.2-3B-Instruct-Q4_K_M.gguf".to_string(),
+            gated: false,
         },
         ModelInfo {
             id: "phi3-mini-q4".to_string(),
@@ -69,6 +78,7 @@ pub fn recommended_models() -> Vec<ModelInfo> {
             size_bytes: 2_394_000_000,
             download_url: "https://huggingface.co/bartowski/Phi-3.5-mini-instruct-GGUF/resolve/main/Phi-3.5-mini-instruct-Q4_K_M.gguf".to_string(),
             filename: "Phi-3.5-mini-instruct-Q4_K_M.gguf".to_string(),
+            gated: false,
         },
         // Whisper ASR
         ModelInfo {
@@ -79,6 +89,7 @@ pub fn recommended_models() -> Vec<ModelInfo> {
             size_bytes: 547_000_000,
             download_url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3-turbo-q5_0.bin".to_string(),
             filename: "ggml-large-v3-turbo-q5_0.bin".to_string(),
+            gated: false,
         },
         ModelInfo {
             id: "whisper-base".to_string(),
@@ -88,6 +99,7 @@ pub fn recommended_models() -> Vec<ModelInfo> {
             size_bytes: 148_000_000,
             download_url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin".to_string(),
             filename: "ggml-base.bin".to_string(),
+            gated: false,
         },
         // VAD
         ModelInfo {
@@ -98,6 +110,7 @@ pub fn recommended_models() -> Vec<ModelInfo> {
             size_bytes: 2_000_000,
             download_url: "https://github.com/snakers4/silero-vad/raw/master/src/silero_vad/data/silero_vad.onnx".to_string(),
             filename: "silero_vad.onnx".to_string(),
+            gated: false,
         },
     ]
 }
@@ -114,3 +127,49 @@ pub fn get_models_by_type(model_type: &ModelType) -> Vec<ModelInfo> {
         .filter(|m| &m.model_type == model_type)
         .collect()
 }
+
+/// Models matching any of `types`, in registry order without duplicates
+pub fn get_models_by_types(types: &[ModelType]) -> Vec<ModelInfo> {
+    recommended_models()
+        .into_iter()
+        .filter(|m| types.contains(&m.model_type))
+        .collect()
+}
+
+/// Case-insensitive substring search over model names and descriptions
+pub fn search_models(query: &str) -> Vec<ModelInfo> {
+    let q = query.trim().to_lowercase();
+    recommended_models()
+        .into_iter()
+        .filter(|m| {
+            q.is_empty()
+                || m.name.to_lowercase().contains(&q)
+                || m.description.to_lowercase().contains(&q)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn by_types_is_a_deduplicated_union_in_registry_order() {
+        let all = recommended_models();
+        let picked = get_models_by_types(&[ModelType::Vad, ModelType::Whisper, ModelType::Vad]);
+
+        let expected: Vec<_> = all
+            .iter()
+            .filter(|m| matches!(m.model_type, ModelType::Whisper | ModelType::Vad))
+            .map(|m| m.id.clone())
+            .collect();
+        assert_eq!(picked.iter().map(|m| m.id.clone()).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn search_matches_name_or_description_case_insensitively() {
+        assert!(search_models("WHISPER").iter().all(|m| m.model_type == ModelType::Whisper));
+        assert!(search_models("voice activity").iter().any(|m| m.id == "silero-vad-v5"));
+        assert_eq!(search_models("").len(), recommended_models().len());
+    }
+}
@@ -0,0 +1,160 @@
+//! Activity export — write the activity log and summaries as JSON or CSV
+//! for analysis outside the app
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::activity_log::{ActivityEntry, ActivitySummary};
+
+/// Output format for [`export`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// One exported entry. OCR text is reduced to its length so exports don't
+/// carry screen contents.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedEntry {
+    pub timestamp: u64,
+    pub end_timestamp: u64,
+    pub app_name: Option<String>,
+    pub window_title: Option<String>,
+    pub ocr_chars: usize,
+    pub change_ratio: f64,
+}
+
+impl From<&ActivityEntry> for ExportedEntry {
+    fn from(entry: &ActivityEntry) -> Self {
+        Self {
+            timestamp: entry.timestamp,
+            end_timestamp: entry.end_timestamp,
+            app_name: entry.app_name.clone(),
+            window_title: entry.window_title.clone(),
+            ocr_chars: entry.ocr_snippet.as_ref().map(|t| t.chars().count()).unwrap_or(0),
+            change_ratio: entry.change_ratio,
+        }
+    }
+}
+
+/// What [`export`] wrote
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReport {
+    pub entry_count: usize,
+    pub summary_count: usize,
+    pub files: Vec<PathBuf>,
+}
+
+/// Write `entries` (and `summaries`, if any) to `path`.
+///
+/// JSON produces a single `{ entries, summaries }` document. CSV is one
+/// table per file, so summaries go to a sibling `<name>-summaries.csv`.
+pub fn export(
+    entries: &[&ActivityEntry],
+    summaries: &[ActivitySummary],
+    format: ExportFormat,
+    path: &Path,
+) -> Result<ExportReport> {
+    let rows: Vec<ExportedEntry> = entries.iter().map(|e| ExportedEntry::from(*e)).collect();
+    let mut files = vec![path.to_path_buf()];
+
+    match format {
+        ExportFormat::Json => {
+            let doc = serde_json::json!({ "entries": rows, "summaries": summaries });
+            std::fs::write(path, serde_json::to_string_pretty(&doc)?)?;
+        }
+        ExportFormat::Csv => {
+            std::fs::write(path, entries_csv(&rows))?;
+            if !summaries.is_empty() {
+                let summaries_path = sibling_path(path, "summaries")?;
+                std::fs::write(&summaries_path, summaries_csv(summaries))?;
+                files.push(summaries_path);
+            }
+        }
+    }
+
+    Ok(ExportReport {
+        entry_count: rows.len(),
+        summary_count: summaries.len(),
+        files,
+    })
+}
+
+fn entries_csv(rows: &[ExportedEntry]) -> String {
+    let mut out =
+        String::from("timestamp,end_timestamp,app_name,window_title,ocr_chars,change_ratio\n");
+    for row in rows {
+        let fields = [
+            row.timestamp.to_string(),
+            row.end_timestamp.to_string(),
+            csv_field(row.app_name.as_deref().unwrap_or("")),
+            csv_field(row.window_title.as_deref().unwrap_or("")),
+            row.ocr_chars.to_string(),
+            format!("{:.4}", row.change_ratio),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn summaries_csv(summaries: &[ActivitySummary]) -> String {
+    let mut out =
+        String::from("period_start,period_end,entry_count,top_apps,summary,generated_at\n");
+    for s in summaries {
+        let fields = [
+            s.period_start.to_string(),
+            s.period_end.to_string(),
+            s.entry_count.to_string(),
+            csv_field(&s.top_apps.join(";")),
+            csv_field(&s.summary),
+            s.generated_at.to_string(),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote a field per RFC 4180 when it holds a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `dir/name.csv` → `dir/name-<suffix>.csv`
+fn sibling_path(path: &Path, suffix: &str) -> Result<PathBuf> {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| anyhow!("Invalid export path: {}", path.display()))?;
+    let name = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}-{}.{}", stem, suffix, ext),
+        None => format!("{}-{}", stem, suffix),
+    };
+    Ok(path.with_file_name(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_escaped() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\"\nbye"), "\"say \"\"hi\"\"\nbye\"");
+        assert_eq!(
+            sibling_path(Path::new("/tmp/log.csv"), "summaries").unwrap(),
+            PathBuf::from("/tmp/log-summaries.csv")
+        );
+    }
+}
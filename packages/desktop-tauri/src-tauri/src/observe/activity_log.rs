@@ -1,21 +1,46 @@
 //! Activity log — in-memory ring buffer of recent observations for summarization
 
-use serde::Serialize;
-use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 
-/// A single activity entry recorded from observations
-#[derive(Debug, Clone, Serialize)]
+/// Fewest pending entries worth an AI summary call
+pub const DEFAULT_SUMMARY_MIN_ENTRIES: usize = 10;
+
+/// Most entries folded into a single summary
+pub const DEFAULT_SUMMARY_MAX_ENTRIES: usize = 120;
+
+/// Frames changing less than this in the same window extend the previous
+/// entry instead of adding a new one
+pub const COALESCE_MAX_CHANGE_RATIO: f64 = 0.25;
+
+/// Longest stretch between two entries credited to the earlier one; beyond
+/// this the user was likely away or observing was paused
+pub const MAX_FOCUS_GAP_MS: u64 = 60_000;
+
+/// A single activity entry recorded from observations. Consecutive frames
+/// of the same window are merged, so one entry may span a stretch of time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ActivityEntry {
+    /// When the entry started
     pub timestamp: u64,
+    /// Last frame merged into this entry; equals `timestamp` when none were
+    pub end_timestamp: u64,
     pub app_name: Option<String>,
     pub window_title: Option<String>,
     pub ocr_snippet: Option<String>,
     pub change_ratio: f64,
 }
 
+impl ActivityEntry {
+    /// Time spent in this window, in milliseconds
+    pub fn duration_ms(&self) -> u64 {
+        self.end_timestamp.saturating_sub(self.timestamp)
+    }
+}
+
 /// Activity summary generated by AI
-#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ActivitySummary {
     pub summary: String,
@@ -23,9 +48,28 @@ pub struct ActivitySummary {
     pub period_end: u64,
     pub entry_count: usize,
     pub top_apps: Vec<String>,
+    /// Time in focus for each of `top_apps`, in the same order
+    #[serde(default)]
+    pub app_usage: Vec<AppUsage>,
     pub generated_at: u64,
 }
 
+/// Time an app spent in focus over a summarized period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUsage {
+    pub app_name: String,
+    pub minutes: f64,
+}
+
+/// Log contents as saved in a state snapshot
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityLogState {
+    pub entries: Vec<ActivityEntry>,
+    pub summaries: Vec<ActivitySummary>,
+}
+
 /// Ring buffer of activity entries
 #[derive(Debug)]
 pub struct ActivityLog {
@@ -47,8 +91,23 @@ impl Default for ActivityLog {
 }
 
 impl ActivityLog {
-    /// Add an activity entry from an observation
+    /// Add an activity entry from an observation. A small change in the
+    /// same app and window extends the last entry rather than appending.
     pub fn push(&mut self, entry: ActivityEntry) {
+        let last_summarized = self.last_summarized();
+        if let Some(last) = self.entries.back_mut() {
+            let same_window =
+                last.app_name == entry.app_name && last.window_title == entry.window_title;
+            // Never extend an entry a summary has already covered
+            if same_window
+                && entry.change_ratio < COALESCE_MAX_CHANGE_RATIO
+                && last.timestamp > last_summarized
+            {
+                last.end_timestamp = last.end_timestamp.max(entry.end_timestamp);
+                return;
+            }
+        }
+
         if self.entries.len() >= self.max_entries {
             self.entries.pop_front();
         }
@@ -63,13 +122,18 @@ impl ActivityLog {
             .collect()
     }
 
+    /// Get summaries whose period overlaps a time range
+    pub fn summaries_in_range(&self, start: u64, end: u64) -> Vec<ActivitySummary> {
+        self.summaries
+            .iter()
+            .filter(|s| s.period_end >= start && s.period_start <= end)
+            .cloned()
+            .collect()
+    }
+
     /// Get all unsummarized entries (entries after the last summary period)
     pub fn pending_entries(&self) -> Vec<&ActivityEntry> {
-        let last_summarized = self
-            .summaries
-            .last()
-            .map(|s| s.period_end)
-            .unwrap_or(0);
+        let last_summarized = self.last_summarized();
 
         self.entries
             .iter()
@@ -77,11 +141,53 @@ impl ActivityLog {
             .collect()
     }
 
+    /// Newest-first page of entries still active at or after `since_ms`,
+    /// with the total number of matching entries
+    pub fn page(
+        &self,
+        offset: usize,
+        limit: usize,
+        since_ms: Option<u64>,
+    ) -> (Vec<ActivityEntry>, usize) {
+        let since = since_ms.unwrap_or(0);
+        let matching = self.entries.iter().rev().filter(|e| e.end_timestamp >= since);
+        let total = matching.clone().count();
+        let entries = matching.skip(offset).take(limit).cloned().collect();
+        (entries, total)
+    }
+
+    fn last_summarized(&self) -> u64 {
+        self.summaries.last().map(|s| s.period_end).unwrap_or(0)
+    }
+
     /// Get total entry count
     pub fn len(&self) -> usize {
         self.entries.len()
     }
 
+    /// Copy out all entries and summaries
+    pub fn export_state(&self) -> ActivityLogState {
+        ActivityLogState {
+            entries: self.entries.iter().cloned().collect(),
+            summaries: self.summaries.clone(),
+        }
+    }
+
+    /// Replace the log's contents, keeping the newest entries and summaries
+    /// that fit
+    pub fn restore_state(&mut self, state: ActivityLogState) {
+        let skip = state.entries.len().saturating_sub(self.max_entries);
+        self.entries = state.entries.into_iter().skip(skip).collect();
+        let skip = state.summaries.len().saturating_sub(self.max_summaries);
+        self.summaries = state.summaries.into_iter().skip(skip).collect();
+    }
+
+    /// Drop every entry and summary
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.summaries.clear();
+    }
+
     /// Store a generated summary
     pub fn add_summary(&mut self, summary: ActivitySummary) {
         if self.summaries.len() >= self.max_summaries {
@@ -114,8 +220,158 @@ impl ActivityLog {
                 .take(100)
                 .collect::<String>();
 
-            lines.push(format!("[{}] {} - {} | {}", ts, app, title, snippet));
+            let minutes = entry.duration_ms() / 60_000;
+            if minutes > 0 {
+                lines.push(format!("[{}] ({}m) {} - {} | {}", ts, minutes, app, title, snippet));
+            } else {
+                lines.push(format!("[{}] {} - {} | {}", ts, app, title, snippet));
+            }
         }
         lines.join("\n")
     }
 }
+
+/// Apps ranked by time in focus, most first; ties go to the app seen most
+/// recently. An entry is in focus until the next one starts (at most
+/// [`MAX_FOCUS_GAP_MS`] past its last frame), so a window visited once still
+/// earns the time until the user switched away. `entries` must be oldest
+/// first.
+pub fn rank_apps(entries: &[&ActivityEntry], limit: usize) -> Vec<AppUsage> {
+    // app -> (focus ms, last seen)
+    let mut totals: HashMap<&str, (u64, u64)> = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let Some(app) = entry.app_name.as_deref() else {
+            continue;
+        };
+        let in_focus = match entries.get(i + 1) {
+            Some(next) => next
+                .timestamp
+                .saturating_sub(entry.timestamp)
+                .min(entry.duration_ms() + MAX_FOCUS_GAP_MS),
+            None => entry.duration_ms(),
+        };
+        let total = totals.entry(app).or_insert((0, 0));
+        total.0 += in_focus;
+        total.1 = total.1.max(entry.end_timestamp);
+    }
+
+    let mut ranked: Vec<(&str, (u64, u64))> = totals.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(app, (ms, _))| AppUsage {
+            app_name: app.to_string(),
+            minutes: ms as f64 / 60_000.0,
+        })
+        .collect()
+}
+
+/// Split `len` entries into contiguous batches of at most `max` entries,
+/// sized as evenly as possible so the last batch isn't a tiny remainder
+pub fn batch_ranges(len: usize, max: usize) -> Vec<std::ops::Range<usize>> {
+    let max = max.max(1);
+    if len == 0 {
+        return Vec::new();
+    }
+    let batches = (len + max - 1) / max;
+    let base = len / batches;
+    let extra = len % batches;
+
+    let mut ranges = Vec::with_capacity(batches);
+    let mut start = 0;
+    for i in 0..batches {
+        let size = base + usize::from(i < extra);
+        ranges.push(start..start + size);
+        start += size;
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: u64, title: &str, change_ratio: f64) -> ActivityEntry {
+        ActivityEntry {
+            timestamp,
+            end_timestamp: timestamp,
+            app_name: Some("Code".to_string()),
+            window_title: Some(title.to_string()),
+            ocr_snippet: None,
+            change_ratio,
+        }
+    }
+
+    #[test]
+    fn small_changes_in_the_same_window_are_merged() {
+        let mut log = ActivityLog::default();
+        log.push(entry(1_000, "main.rs", 1.0));
+        log.push(entry(2_000, "main.rs", 0.1));
+        log.push(entry(3_000, "main.rs", 0.1));
+        assert_eq!(log.len(), 1);
+        assert_eq!(log.pending_entries()[0].duration_ms(), 2_000);
+
+        // Big change or a different window starts a new entry
+        log.push(entry(4_000, "main.rs", 0.6));
+        log.push(entry(5_000, "lib.rs", 0.1));
+        assert_eq!(log.len(), 3);
+
+        log.clear();
+        assert_eq!(log.len(), 0);
+        assert!(log.pending_entries().is_empty());
+    }
+
+    #[test]
+    fn pages_are_newest_first() {
+        let mut log = ActivityLog::default();
+        for i in 0..5 {
+            log.push(entry(i * 1_000, &format!("tab {}", i), 1.0));
+        }
+
+        let (entries, total) = log.page(1, 2, None);
+        assert_eq!(total, 5);
+        let stamps: Vec<u64> = entries.iter().map(|e| e.timestamp).collect();
+        assert_eq!(stamps, vec![3_000, 2_000]);
+
+        let (entries, total) = log.page(0, 10, Some(3_000));
+        assert_eq!((entries.len(), total), (2, 2));
+    }
+
+    #[test]
+    fn apps_rank_by_focus_time_then_recency() {
+        let at = |timestamp: u64, app: &str| ActivityEntry {
+            app_name: Some(app.to_string()),
+            ..entry(timestamp, app, 1.0)
+        };
+        let entries = [
+            // Polled often but only briefly in front
+            at(0, "Slack"),
+            at(10_000, "Figma"),
+            at(250_000, "Slack"),
+            at(260_000, "Mail"),
+            // Two hours away: Mail is only credited the cap
+            at(7_460_000, "Slack"),
+        ];
+        let refs: Vec<&ActivityEntry> = entries.iter().collect();
+        let ranked = rank_apps(&refs, 5);
+        let names: Vec<&str> = ranked.iter().map(|u| u.app_name.as_str()).collect();
+        assert_eq!(names, vec!["Figma", "Mail", "Slack"]);
+        assert!((ranked[0].minutes - 4.0).abs() < 1e-9);
+        assert!((ranked[1].minutes - 1.0).abs() < 1e-9);
+
+        // Equal time: the most recent app wins
+        let tied = [at(0, "Notes"), at(60_000, "Safari"), at(120_000, "Code")];
+        let refs: Vec<&ActivityEntry> = tied.iter().collect();
+        let ranked = rank_apps(&refs, 1);
+        assert_eq!(ranked[0].app_name, "Safari");
+    }
+
+    #[test]
+    fn batches_are_bounded_and_even() {
+        assert!(batch_ranges(0, 10).is_empty());
+        assert_eq!(batch_ranges(7, 10), vec![0..7]);
+        assert_eq!(batch_ranges(105, 100), vec![0..53, 53..105]);
+        assert_eq!(batch_ranges(30, 10), vec![0..10, 10..20, 20..30]);
+    }
+}
@@ -10,6 +10,8 @@
 use serde::Serialize;
 use std::time::Instant;
 
+use crate::clock::{self, SharedClock};
+
 /// Activity event types that affect the refresh rate
 #[derive(Debug, Clone, Copy, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -23,7 +25,7 @@ pub enum ActivityEventType {
 }
 
 /// Activity level derived from the score
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ActivityLevel {
     Idle,
@@ -42,6 +44,17 @@ pub struct AdaptiveRefreshStatus {
     pub activity_level: ActivityLevel,
     pub current_interval_ms: u64,
     pub recent_event_count: usize,
+    /// `activity_level` is pinned by `set_activity_level` rather than
+    /// derived from the score
+    pub forced: bool,
+}
+
+/// Controller settings carried in a state snapshot
+#[derive(Debug, Clone, Copy, Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdaptiveRefreshState {
+    pub enabled: bool,
+    pub activity_score: f64,
 }
 
 /// Adaptive refresh rate controller
@@ -57,24 +70,35 @@ pub struct AdaptiveRefresh {
     gain_rate: f64,
     min_interval_ms: u64,
     max_interval_ms: u64,
+    /// Pinned level; the score keeps tracking activity underneath but
+    /// doesn't set the interval until this is cleared
+    forced_level: Option<ActivityLevel>,
+    clock: SharedClock,
 }
 
 impl Default for AdaptiveRefresh {
     fn default() -> Self {
+        Self::with_clock(clock::system())
+    }
+}
+
+impl AdaptiveRefresh {
+    /// Controller that measures elapsed time with `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
             enabled: true,
             activity_score: 50.0,
-            last_decay_time: Instant::now(),
+            last_decay_time: clock.instant(),
             recent_events: Vec::new(),
             decay_rate: 0.1,
             gain_rate: 20.0,
             min_interval_ms: 1000,
             max_interval_ms: 10000,
+            forced_level: None,
+            clock,
         }
     }
-}
 
-impl AdaptiveRefresh {
     /// Record an activity event, increasing the activity score
     pub fn record_activity(&mut self, event_type: ActivityEventType) {
         if !self.enabled {
@@ -89,7 +113,7 @@ impl AdaptiveRefresh {
         self.activity_score = (self.activity_score + gain).min(100.0);
 
         // Track event time for recent count
-        let now = Instant::now();
+        let now = self.clock.instant();
         self.recent_events.push(now);
         // Keep only last 60s of events
         self.recent_events.retain(|t| now.duration_since(*t).as_secs() < 60);
@@ -97,6 +121,9 @@ impl AdaptiveRefresh {
 
     /// Get the current recommended interval in milliseconds
     pub fn current_interval_ms(&mut self) -> u64 {
+        if let Some(level) = self.forced_level {
+            return self.level_to_interval(level);
+        }
         if !self.enabled {
             return 3000; // default
         }
@@ -106,23 +133,16 @@ impl AdaptiveRefresh {
 
     /// Get the current activity level
     pub fn activity_level(&self) -> ActivityLevel {
-        if self.activity_score > 90.0 {
-            ActivityLevel::VeryHigh
-        } else if self.activity_score > 70.0 {
-            ActivityLevel::High
-        } else if self.activity_score > 50.0 {
-            ActivityLevel::Normal
-        } else if self.activity_score > 20.0 {
-            ActivityLevel::Low
-        } else {
-            ActivityLevel::Idle
+        if let Some(level) = self.forced_level {
+            return level;
         }
+        Self::score_to_level(self.activity_score)
     }
 
     /// Get full status
     pub fn status(&mut self) -> AdaptiveRefreshStatus {
         self.apply_decay();
-        let now = Instant::now();
+        let now = self.clock.instant();
         let recent_count = self.recent_events.iter()
             .filter(|t| now.duration_since(**t).as_secs() < 60)
             .count();
@@ -131,31 +151,62 @@ impl AdaptiveRefresh {
             enabled: self.enabled,
             activity_score: self.activity_score.round() as u32,
             activity_level: self.activity_level(),
-            current_interval_ms: self.score_to_interval(self.activity_score),
+            current_interval_ms: match self.forced_level {
+                Some(level) => self.level_to_interval(level),
+                None => self.score_to_interval(self.activity_score),
+            },
             recent_event_count: recent_count,
+            forced: self.forced_level.is_some(),
         }
     }
 
+    /// Pin the interval to `level`'s band until [`Self::clear_forced_level`]
+    pub fn force_level(&mut self, level: ActivityLevel) {
+        self.forced_level = Some(level);
+    }
+
+    /// Return to score-driven intervals
+    pub fn clear_forced_level(&mut self) {
+        self.forced_level = None;
+    }
+
     /// Enable or disable adaptive refresh
     pub fn set_enabled(&mut self, enabled: bool) {
         self.enabled = enabled;
         if enabled {
-            self.last_decay_time = Instant::now();
+            self.last_decay_time = self.clock.instant();
         }
     }
 
+    /// Current settings, with pending decay applied
+    pub fn export_state(&mut self) -> AdaptiveRefreshState {
+        self.apply_decay();
+        AdaptiveRefreshState {
+            enabled: self.enabled,
+            activity_score: self.activity_score,
+        }
+    }
+
+    /// Restore saved settings; recent events are not carried over
+    pub fn restore_state(&mut self, state: AdaptiveRefreshState) {
+        self.reset();
+        self.set_enabled(state.enabled);
+        self.activity_score = state.activity_score.clamp(0.0, 100.0);
+    }
+
     /// Reset to default state
     pub fn reset(&mut self) {
         self.activity_score = 50.0;
+        self.forced_level = None;
         self.recent_events.clear();
-        self.last_decay_time = Instant::now();
+        self.last_decay_time = self.clock.instant();
     }
 
     // --- Private ---
 
     /// Apply exponential decay based on elapsed time
     fn apply_decay(&mut self) {
-        let now = Instant::now();
+        let now = self.clock.instant();
         let elapsed_secs = now.duration_since(self.last_decay_time).as_secs_f64();
         self.last_decay_time = now;
 
@@ -168,18 +219,34 @@ impl AdaptiveRefresh {
         self.activity_score = (self.activity_score * decay_factor).max(0.0);
     }
 
-    /// Map activity score to refresh interval
-    fn score_to_interval(&self, score: f64) -> u64 {
+    /// Band an activity score falls in
+    fn score_to_level(score: f64) -> ActivityLevel {
         if score > 90.0 {
-            self.min_interval_ms
+            ActivityLevel::VeryHigh
         } else if score > 70.0 {
-            2000
+            ActivityLevel::High
         } else if score > 50.0 {
-            3000
+            ActivityLevel::Normal
         } else if score > 20.0 {
-            5000
+            ActivityLevel::Low
         } else {
-            self.max_interval_ms
+            ActivityLevel::Idle
+        }
+    }
+
+    /// Map activity score to refresh interval
+    fn score_to_interval(&self, score: f64) -> u64 {
+        self.level_to_interval(Self::score_to_level(score))
+    }
+
+    /// Interval of each level's band
+    fn level_to_interval(&self, level: ActivityLevel) -> u64 {
+        match level {
+            ActivityLevel::VeryHigh => self.min_interval_ms,
+            ActivityLevel::High => 2000,
+            ActivityLevel::Normal => 3000,
+            ActivityLevel::Low => 5000,
+            ActivityLevel::Idle => self.max_interval_ms,
         }
     }
 
@@ -195,3 +262,64 @@ impl AdaptiveRefresh {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use std::time::Duration;
+
+    #[test]
+    fn score_decays_into_slower_intervals() {
+        let clock = MockClock::new(0);
+        let mut refresh = AdaptiveRefresh::with_clock(clock.clone());
+        assert_eq!(refresh.current_interval_ms(), 5000);
+
+        // 50 + 20 * 1.5 = 80
+        refresh.record_activity(ActivityEventType::UserInteraction);
+        assert_eq!(refresh.current_interval_ms(), 2000);
+
+        // 80 * 0.9^3 ≈ 58
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(refresh.current_interval_ms(), 3000);
+        assert_eq!(refresh.activity_level(), ActivityLevel::Normal);
+
+        // ≈ 7
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(refresh.current_interval_ms(), 10000);
+        assert_eq!(refresh.activity_level(), ActivityLevel::Idle);
+    }
+
+    #[test]
+    fn events_go_stale_after_a_minute() {
+        let clock = MockClock::new(0);
+        let mut refresh = AdaptiveRefresh::with_clock(clock.clone());
+        refresh.record_activity(ActivityEventType::ScreenChange);
+        refresh.record_activity(ActivityEventType::WindowSwitch);
+
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(refresh.status().recent_event_count, 2);
+
+        clock.advance(Duration::from_secs(2));
+        refresh.record_activity(ActivityEventType::ScreenChange);
+        assert_eq!(refresh.status().recent_event_count, 1);
+    }
+
+    #[test]
+    fn forced_level_pins_the_interval_until_cleared() {
+        let clock = MockClock::new(0);
+        let mut refresh = AdaptiveRefresh::with_clock(clock.clone());
+        refresh.force_level(ActivityLevel::VeryHigh);
+
+        // Decay would drop the score to idle; the forced band holds
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(refresh.current_interval_ms(), 1000);
+        let status = refresh.status();
+        assert!(status.forced);
+        assert_eq!(status.activity_level, ActivityLevel::VeryHigh);
+
+        refresh.clear_forced_level();
+        assert_eq!(refresh.current_interval_ms(), 10000);
+        assert!(!refresh.status().forced);
+    }
+}
@@ -0,0 +1,160 @@
+//! Pipeline benchmark for "observe feels slow" reports
+//!
+//! Times each stage the observe loop runs on a changed frame — capture,
+//! encode, hash, OCR, window lookup — with the current capture and OCR
+//! settings, so downscaling and format choices can be compared with data.
+//! Nothing is recorded and the live loop's state is untouched.
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::observe::change_detector;
+use crate::perception;
+use crate::perception::screen::CaptureOptions;
+use crate::state::AppState;
+
+/// Upper bound on iterations per call; each one captures and OCRs a frame
+pub const MAX_BENCHMARK_ITERATIONS: u32 = 50;
+
+/// Latency of one stage across all iterations, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageTiming {
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub p95_ms: f64,
+    pub samples: usize,
+}
+
+impl StageTiming {
+    /// `None` when the stage never ran
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut ms: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(|a, b| a.total_cmp(b));
+        let p95_index = ((ms.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+        Some(Self {
+            min_ms: ms[0],
+            avg_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+            p95_ms: ms[p95_index],
+            samples: ms.len(),
+        })
+    }
+}
+
+/// Per-stage latency and encode sizes
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    pub iterations: u32,
+    pub width: u32,
+    pub height: u32,
+    /// MIME type of the encoded frame
+    pub format: String,
+    pub capture: Option<StageTiming>,
+    pub encode: Option<StageTiming>,
+    pub hash: Option<StageTiming>,
+    /// `None` when the OCR helper is unavailable or every run failed
+    pub ocr: Option<StageTiming>,
+    pub ocr_errors: usize,
+    pub window: Option<StageTiming>,
+    /// Average size of the encoded frame, before base64
+    pub avg_encoded_bytes: u64,
+    /// Average size of the observe-update thumbnail, before base64
+    pub avg_thumbnail_bytes: u64,
+}
+
+/// Run the pipeline `iterations` times (clamped to 1..=[`MAX_BENCHMARK_ITERATIONS`])
+pub async fn run_benchmark(state: &AppState, iterations: u32) -> Result<BenchmarkReport> {
+    state.ensure_capture_enabled().await?;
+    let iterations = iterations.clamp(1, MAX_BENCHMARK_ITERATIONS);
+    let capture_options = CaptureOptions::from_config(&*state.config.read().await);
+    let ocr_options = state.ocr_options().await;
+    let ocr_available = *state.ocr_available.read().await;
+
+    let (mut capture, mut encode, mut hash, mut ocr, mut window) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let (mut encoded_bytes, mut thumbnail_bytes) = (0u64, 0u64);
+    let mut ocr_errors = 0;
+    let mut dimensions = (0, 0);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let frame = perception::screen::capture_primary_rgba().await?;
+        capture.push(start.elapsed());
+        dimensions = frame.dimensions();
+
+        let start = Instant::now();
+        let encoded = perception::screen::encode_rgba(&frame, capture_options)?;
+        let base64_data = STANDARD.encode(&encoded);
+        encode.push(start.elapsed());
+        encoded_bytes += encoded.len() as u64;
+
+        let start = Instant::now();
+        std::hint::black_box(change_detector::compute_phash(
+            frame.as_raw(),
+            frame.width(),
+            frame.height(),
+        ));
+        hash.push(start.elapsed());
+
+        if let Ok(thumbnail) =
+            perception::screen::encode_thumbnail(&frame, perception::screen::THUMBNAIL_WIDTH)
+        {
+            // Decoded size of the base64 payload
+            thumbnail_bytes += (thumbnail.len() * 3 / 4) as u64;
+        }
+
+        if ocr_available {
+            let start = Instant::now();
+            match perception::ocr::run_ocr(&base64_data, &ocr_options).await {
+                Ok(_) => ocr.push(start.elapsed()),
+                Err(e) => {
+                    log::warn!("[Benchmark] OCR failed: {}", e);
+                    ocr_errors += 1;
+                }
+            }
+        }
+
+        // Bypass the window cache so every lookup is a real one
+        let start = Instant::now();
+        let _ = perception::window::get_active_window(true).await;
+        window.push(start.elapsed());
+    }
+
+    Ok(BenchmarkReport {
+        iterations,
+        width: dimensions.0,
+        height: dimensions.1,
+        format: capture_options.format.mime_type().to_string(),
+        capture: StageTiming::from_samples(&capture),
+        encode: StageTiming::from_samples(&encode),
+        hash: StageTiming::from_samples(&hash),
+        ocr: StageTiming::from_samples(&ocr),
+        ocr_errors,
+        window: StageTiming::from_samples(&window),
+        avg_encoded_bytes: encoded_bytes / iterations as u64,
+        avg_thumbnail_bytes: thumbnail_bytes / iterations as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_timing_reports_min_avg_and_p95() {
+        assert!(StageTiming::from_samples(&[]).is_none());
+
+        let samples: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        let timing = StageTiming::from_samples(&samples).unwrap();
+        assert_eq!(timing.samples, 20);
+        assert!((timing.min_ms - 1.0).abs() < 1e-9);
+        assert!((timing.avg_ms - 10.5).abs() < 1e-9);
+        assert!((timing.p95_ms - 19.0).abs() < 1e-9);
+    }
+}
@@ -1,19 +1,31 @@
 //! Perceptual hash change detection for the observe loop
 
-/// Compute a simple average-hash (aHash) from raw RGBA PNG bytes.
-/// Returns a 64-bit perceptual hash.
-pub fn compute_phash(rgba_data: &[u8], width: u32, height: u32) -> u64 {
-    // Downsample to 8x8 grayscale
-    let mut gray_8x8 = [0u32; 64];
-    let block_w = width / 8;
-    let block_h = height / 8;
+use serde::Serialize;
+
+use crate::perception::window::WindowInfo;
+
+/// Tiles per side of the grid the frame is reduced to
+pub const TILE_GRID: u32 = 8;
+
+/// Mean-luma difference (0–255) above which a tile counts as changed
+const TILE_DELTA: u8 = 12;
+
+/// Mean luma of each tile of an 8x8 grid over the frame, row-major
+pub type TileLuma = [u8; 64];
+
+/// Reduce raw RGBA pixels to the mean luma of each tile. A frame smaller
+/// than the grid gives all zeros.
+pub fn compute_tiles(rgba_data: &[u8], width: u32, height: u32) -> TileLuma {
+    let mut tiles = [0u8; 64];
+    let block_w = width / TILE_GRID;
+    let block_h = height / TILE_GRID;
 
     if block_w == 0 || block_h == 0 {
-        return 0;
+        return tiles;
     }
 
-    for by in 0..8u32 {
-        for bx in 0..8u32 {
+    for by in 0..TILE_GRID {
+        for bx in 0..TILE_GRID {
             let mut sum = 0u64;
             let mut count = 0u32;
             for y in (by * block_h)..((by + 1) * block_h).min(height) {
@@ -28,21 +40,25 @@ pub fn compute_phash(rgba_data: &[u8], width: u32, height: u32) -> u64 {
                     }
                 }
             }
-            gray_8x8[(by * 8 + bx) as usize] = if count > 0 {
-                (sum / count as u64) as u32
+            tiles[(by * TILE_GRID + bx) as usize] = if count > 0 {
+                (sum / count as u64) as u8
             } else {
                 0
             };
         }
     }
 
-    // Compute average
-    let avg: u32 = gray_8x8.iter().sum::<u32>() / 64;
+    tiles
+}
+
+/// Average-hash (aHash) of a tile grid: bit `i` is set when tile `i` is
+/// brighter than the grid's mean
+pub fn phash_from_tiles(tiles: &TileLuma) -> u64 {
+    let avg = tiles.iter().map(|&t| t as u32).sum::<u32>() / 64;
 
-    // Build hash: 1 if pixel > average, 0 otherwise
     let mut hash: u64 = 0;
-    for (i, &val) in gray_8x8.iter().enumerate() {
-        if val > avg {
+    for (i, &val) in tiles.iter().enumerate() {
+        if val as u32 > avg {
             hash |= 1 << i;
         }
     }
@@ -50,6 +66,27 @@ pub fn compute_phash(rgba_data: &[u8], width: u32, height: u32) -> u64 {
     hash
 }
 
+/// Compute a simple average-hash (aHash) from raw RGBA PNG bytes.
+/// Returns a 64-bit perceptual hash.
+pub fn compute_phash(rgba_data: &[u8], width: u32, height: u32) -> u64 {
+    phash_from_tiles(&compute_tiles(rgba_data, width, height))
+}
+
+/// Bit `i` set when tile `i` changed noticeably between two frames
+pub fn tile_change_mask(prev: &TileLuma, cur: &TileLuma) -> u64 {
+    prev.iter()
+        .zip(cur.iter())
+        .enumerate()
+        .filter(|(_, (a, b))| a.abs_diff(**b) > TILE_DELTA)
+        .fold(0u64, |mask, (i, _)| mask | (1 << i))
+}
+
+/// A hash as 16 lowercase hex digits, the form sent to the frontend (a
+/// `u64` doesn't survive a JavaScript number)
+pub fn phash_hex(hash: u64) -> String {
+    format!("{:016x}", hash)
+}
+
 /// Hamming distance between two perceptual hashes
 pub fn hamming_distance(h1: u64, h2: u64) -> u32 {
     (h1 ^ h2).count_ones()
@@ -59,3 +96,138 @@ pub fn hamming_distance(h1: u64, h2: u64) -> u32 {
 pub fn change_ratio(h1: u64, h2: u64) -> f64 {
     hamming_distance(h1, h2) as f64 / 64.0
 }
+
+/// What kind of transition a detected change was
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The focused app changed
+    AppSwitch,
+    /// Same app, different window title: a new window, tab or document
+    NewWindow,
+    /// One tall block of the screen changed while its surroundings stayed
+    Scroll,
+    /// A few tiles changed
+    MinorEdit,
+    /// Most of the screen changed, or there is no previous frame
+    SceneChange,
+}
+
+/// Changed tiles at most this many count as a minor edit
+const MINOR_EDIT_TILES: u32 = 16;
+
+/// Label the change between two frames. Window identity decides first; the
+/// tile mask from [`tile_change_mask`] tells scrolling and small edits from
+/// full scene changes.
+pub fn classify_change(
+    prev_hash: Option<u64>,
+    cur_hash: u64,
+    prev_window: Option<&WindowInfo>,
+    cur_window: Option<&WindowInfo>,
+    tile_mask: u64,
+) -> ChangeKind {
+    if let (Some(prev), Some(cur)) = (prev_window, cur_window) {
+        if prev.app_name != cur.app_name {
+            return ChangeKind::AppSwitch;
+        }
+        if prev.title != cur.title {
+            return ChangeKind::NewWindow;
+        }
+    }
+    let Some(prev_hash) = prev_hash else {
+        return ChangeKind::SceneChange;
+    };
+
+    let changed = tile_mask.count_ones();
+    if looks_like_scroll(tile_mask) {
+        ChangeKind::Scroll
+    } else if changed <= MINOR_EDIT_TILES && change_ratio(prev_hash, cur_hash) < 0.5 {
+        ChangeKind::MinorEdit
+    } else {
+        ChangeKind::SceneChange
+    }
+}
+
+/// Scrolling rewrites a content pane top to bottom and leaves the chrome
+/// around it (toolbar, sidebar, gutter) alone: the changed tiles nearly
+/// fill a box at least 5 rows tall that is smaller than the whole grid.
+fn looks_like_scroll(mask: u64) -> bool {
+    let changed = mask.count_ones();
+    if changed < 10 {
+        return false;
+    }
+    let (rows, cols): (Vec<u32>, Vec<u32>) = (0..64u32)
+        .filter(|i| mask & (1 << i) != 0)
+        .map(|i| (i / TILE_GRID, i % TILE_GRID))
+        .unzip();
+    let height = rows.iter().max().unwrap() - rows.iter().min().unwrap() + 1;
+    let width = cols.iter().max().unwrap() - cols.iter().min().unwrap() + 1;
+    let area = height * width;
+    height >= 5 && area < 64 && changed * 100 >= area * 85
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(app: &str, title: &str) -> WindowInfo {
+        WindowInfo {
+            app_name: app.to_string(),
+            title: title.to_string(),
+            bundle_id: None,
+            bounds: None,
+        }
+    }
+
+    /// Mask with every tile in `rows` x `cols` set
+    fn block(rows: std::ops::Range<u32>, cols: std::ops::Range<u32>) -> u64 {
+        rows.flat_map(|r| cols.clone().map(move |c| 1u64 << (r * TILE_GRID + c)))
+            .fold(0, |mask, bit| mask | bit)
+    }
+
+    #[test]
+    fn hex_hashes_are_fixed_width_and_round_trip() {
+        assert_eq!(phash_hex(0), "0000000000000000");
+        assert_eq!(phash_hex(0xff), "00000000000000ff");
+        let hash = 0xdead_beef_0123_4567;
+        assert_eq!(u64::from_str_radix(&phash_hex(hash), 16).unwrap(), hash);
+        assert_eq!(change_ratio(hash, hash ^ 0xffff), 0.25);
+    }
+
+    #[test]
+    fn tile_mask_marks_changed_tiles() {
+        let prev = [100u8; 64];
+        let mut cur = prev;
+        cur[0] = 200;
+        cur[63] = 95; // Within TILE_DELTA
+        assert_eq!(tile_change_mask(&prev, &cur), 1);
+        assert_eq!(phash_from_tiles(&cur), 1);
+    }
+
+    #[test]
+    fn transitions_are_labelled() {
+        let slack = window("Slack", "general");
+        let code = window("Code", "main.rs");
+        let code_other = window("Code", "lib.rs");
+        let classify = |prev: &WindowInfo, cur: &WindowInfo, mask: u64| {
+            classify_change(Some(0), 1, Some(prev), Some(cur), mask)
+        };
+
+        assert_eq!(classify(&slack, &code, 1), ChangeKind::AppSwitch);
+        assert_eq!(classify(&code, &code_other, 1), ChangeKind::NewWindow);
+        // Editor pane scrolled: columns 2..8 below the toolbar row
+        assert_eq!(
+            classify(&code, &code, block(1..8, 2..8)),
+            ChangeKind::Scroll
+        );
+        assert_eq!(
+            classify(&code, &code, block(3..5, 3..6)),
+            ChangeKind::MinorEdit
+        );
+        assert_eq!(classify(&code, &code, u64::MAX), ChangeKind::SceneChange);
+        assert_eq!(
+            classify_change(None, 1, None, Some(&code), u64::MAX),
+            ChangeKind::SceneChange
+        );
+    }
+}
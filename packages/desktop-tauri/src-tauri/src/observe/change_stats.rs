@@ -0,0 +1,144 @@
+//! Change-ratio statistics for tuning the observe threshold
+//!
+//! When enabled, the observe loop records every frame's `change_ratio` into a
+//! fixed-size ring buffer. A settings screen can then query the distribution
+//! and see how many frames a candidate threshold would have triggered.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Default change threshold (5% of hash bits)
+pub const DEFAULT_THRESHOLD: f64 = 0.05;
+
+/// Number of samples kept in the ring buffer
+const MAX_SAMPLES: usize = 500;
+
+/// Histogram bucket count over [0, 1]
+const HISTOGRAM_BUCKETS: usize = 20;
+
+/// Live-tunable observe loop parameters
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObserveParams {
+    /// Minimum change ratio for a frame to be processed
+    pub threshold: f64,
+    /// Record change ratios for `get_change_stats`. Off by default.
+    pub record_stats: bool,
+}
+
+impl Default for ObserveParams {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_THRESHOLD,
+            record_stats: false,
+        }
+    }
+}
+
+/// Summary of recent change ratios
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeStatsSummary {
+    pub sample_count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Threshold the `triggered` count was computed against
+    pub threshold: f64,
+    /// Samples at or above `threshold`
+    pub triggered: usize,
+    /// Sample counts per equal-width bucket over [0, 1]
+    pub histogram: Vec<usize>,
+}
+
+/// Ring buffer of recent change ratios
+#[derive(Debug, Default)]
+pub struct ChangeStats {
+    samples: VecDeque<f64>,
+}
+
+impl ChangeStats {
+    /// Record a frame's change ratio, evicting the oldest past capacity
+    pub fn record(&mut self, ratio: f64) {
+        if self.samples.len() >= MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(ratio.clamp(0.0, 1.0));
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Summarize the buffer against `threshold`
+    pub fn summary(&self, threshold: f64) -> ChangeStatsSummary {
+        let mut histogram = vec![0usize; HISTOGRAM_BUCKETS];
+        let mut min = f64::MAX;
+        let mut max = 0.0f64;
+        let mut sum = 0.0;
+        let mut triggered = 0;
+
+        for &ratio in &self.samples {
+            min = min.min(ratio);
+            max = max.max(ratio);
+            sum += ratio;
+            if ratio >= threshold {
+                triggered += 1;
+            }
+            let bucket = ((ratio * HISTOGRAM_BUCKETS as f64) as usize).min(HISTOGRAM_BUCKETS - 1);
+            histogram[bucket] += 1;
+        }
+
+        let count = self.samples.len();
+        ChangeStatsSummary {
+            sample_count: count,
+            min: if count > 0 { min } else { 0.0 },
+            max,
+            mean: if count > 0 { sum / count as f64 } else { 0.0 },
+            threshold,
+            triggered,
+            histogram,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_counts_triggered_frames() {
+        let mut stats = ChangeStats::default();
+        for r in [0.0, 0.02, 0.05, 0.1, 1.0] {
+            stats.record(r);
+        }
+
+        let s = stats.summary(0.05);
+        assert_eq!(s.sample_count, 5);
+        assert_eq!(s.triggered, 3);
+        assert_eq!(s.min, 0.0);
+        assert_eq!(s.max, 1.0);
+        assert_eq!(s.histogram.iter().sum::<usize>(), 5);
+        assert_eq!(s.histogram[HISTOGRAM_BUCKETS - 1], 1);
+    }
+
+    #[test]
+    fn ring_buffer_evicts_oldest() {
+        let mut stats = ChangeStats::default();
+        for _ in 0..MAX_SAMPLES {
+            stats.record(1.0);
+        }
+        stats.record(0.0);
+
+        assert_eq!(stats.len(), MAX_SAMPLES);
+        assert_eq!(stats.summary(0.5).triggered, MAX_SAMPLES - 1);
+    }
+}
@@ -0,0 +1,159 @@
+//! Focus sessions
+//!
+//! A session names the life stages that count as on-task. Every observed
+//! frame is classified by the life tree as usual; frames in any other stage
+//! count as distraction, and each switch from focus into distraction is
+//! reported once (`focus:distraction`). Time between two frames is credited
+//! to the earlier frame's state.
+
+use serde::Serialize;
+
+use crate::life_tree::LifeStage;
+
+/// A stretch of the session spent outside the allowed stages
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Distraction {
+    pub timestamp: u64,
+    pub stage: LifeStage,
+    pub app_name: Option<String>,
+    pub task_label: String,
+}
+
+/// Result of `end_focus_session`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusSummary {
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub planned_ms: u64,
+    pub allowed_stages: Vec<LifeStage>,
+    pub focus_ms: u64,
+    pub distraction_ms: u64,
+    /// `focus_ms` over all observed time; 1.0 before any frame was seen
+    pub focus_ratio: f64,
+    pub distractions: Vec<Distraction>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FocusSession {
+    started_at: u64,
+    planned_ms: u64,
+    allowed_stages: Vec<LifeStage>,
+    focus_ms: u64,
+    distraction_ms: u64,
+    distractions: Vec<Distraction>,
+    /// Time and on-task state of the previous frame
+    last_sample: Option<(u64, bool)>,
+}
+
+impl FocusSession {
+    pub fn new(started_at: u64, planned_ms: u64, allowed_stages: Vec<LifeStage>) -> Self {
+        Self {
+            started_at,
+            planned_ms,
+            allowed_stages,
+            focus_ms: 0,
+            distraction_ms: 0,
+            distractions: Vec::new(),
+            last_sample: None,
+        }
+    }
+
+    /// When the planned duration runs out
+    pub fn ends_at(&self) -> u64 {
+        self.started_at + self.planned_ms
+    }
+
+    /// Account for a classified frame. Returns the distraction when this
+    /// frame moves the session from focus (or its start) into distraction;
+    /// frames after the planned end are ignored.
+    pub fn record(
+        &mut self,
+        timestamp: u64,
+        stage: &LifeStage,
+        app_name: Option<&str>,
+        task_label: &str,
+    ) -> Option<Distraction> {
+        if timestamp > self.ends_at() {
+            self.settle(self.ends_at());
+            return None;
+        }
+        let focused = self.allowed_stages.contains(stage);
+        let was_focused = self.last_sample.map_or(true, |(_, focused)| focused);
+        self.settle(timestamp);
+        self.last_sample = Some((timestamp, focused));
+
+        if focused || !was_focused {
+            return None;
+        }
+        let distraction = Distraction {
+            timestamp,
+            stage: stage.clone(),
+            app_name: app_name.map(str::to_string),
+            task_label: task_label.to_string(),
+        };
+        self.distractions.push(distraction.clone());
+        Some(distraction)
+    }
+
+    /// Close the session at `now` (or its planned end, if earlier)
+    pub fn finish(mut self, now: u64) -> FocusSummary {
+        let ended_at = now.min(self.ends_at()).max(self.started_at);
+        self.settle(ended_at);
+        let observed = self.focus_ms + self.distraction_ms;
+        FocusSummary {
+            started_at: self.started_at,
+            ended_at,
+            planned_ms: self.planned_ms,
+            allowed_stages: self.allowed_stages,
+            focus_ms: self.focus_ms,
+            distraction_ms: self.distraction_ms,
+            focus_ratio: if observed == 0 {
+                1.0
+            } else {
+                self.focus_ms as f64 / observed as f64
+            },
+            distractions: self.distractions,
+        }
+    }
+
+    /// Credit the time since the previous frame to that frame's state
+    fn settle(&mut self, until: u64) {
+        if let Some((since, focused)) = self.last_sample {
+            let elapsed = until.saturating_sub(since);
+            if focused {
+                self.focus_ms += elapsed;
+            } else {
+                self.distraction_ms += elapsed;
+            }
+            self.last_sample = Some((until.max(since), focused));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distractions_are_flagged_once_and_time_is_split() {
+        let mut session = FocusSession::new(0, 60_000, vec![LifeStage::Career]);
+        assert!(session.record(0, &LifeStage::Career, Some("Code"), "main.rs").is_none());
+
+        let flagged = session.record(10_000, &LifeStage::Relationships, Some("Twitter"), "Home");
+        assert_eq!(flagged.unwrap().app_name.as_deref(), Some("Twitter"));
+        // Still distracted: not flagged again
+        assert!(session.record(15_000, &LifeStage::Creativity, Some("YouTube"), "Video").is_none());
+        assert!(session.record(20_000, &LifeStage::Career, Some("Code"), "main.rs").is_none());
+        // Past the planned end: ignored
+        assert!(session.record(90_000, &LifeStage::Health, None, "Run").is_none());
+
+        let summary = session.finish(120_000);
+        assert_eq!(summary.ended_at, 60_000);
+        assert_eq!(summary.distraction_ms, 10_000);
+        assert_eq!(summary.focus_ms, 50_000);
+        assert_eq!(summary.distractions.len(), 1);
+        assert!((summary.focus_ratio - 50.0 / 60.0).abs() < 1e-9);
+    }
+}
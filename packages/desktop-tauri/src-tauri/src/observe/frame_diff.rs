@@ -0,0 +1,222 @@
+//! "What changed" between two recorded frames
+//!
+//! Compares window, app and OCR text of two frames from the recent-frames
+//! store and phrases the result as a one-line summary, without an AI call.
+//! OCR output doesn't keep a stable reading order, so the text diff counts
+//! lines rather than aligning them.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::observe::change_detector;
+use crate::observe::recent_frames::RecentFrame;
+
+/// Lines listed per side of a diff; the counts cover all of them
+const MAX_LISTED_LINES: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservationDiff {
+    pub from_timestamp: u64,
+    pub to_timestamp: u64,
+    pub from_app: Option<String>,
+    pub to_app: Option<String>,
+    pub app_changed: bool,
+    /// Same app, different window title
+    pub window_changed: bool,
+    /// OCR lines present only in the later frame
+    pub added_lines: Vec<String>,
+    pub added_count: usize,
+    /// OCR lines present only in the earlier frame
+    pub removed_lines: Vec<String>,
+    pub removed_count: usize,
+    /// Hash distance between the two frames; `None` for frames recorded
+    /// before hashes were stored
+    pub change_ratio: Option<f64>,
+    /// e.g. "Switched from Slack to Code; 12 lines added, 3 removed"
+    pub summary: String,
+}
+
+/// Diff `from` (earlier) against `to` (later)
+pub fn diff(from: &RecentFrame, to: &RecentFrame) -> ObservationDiff {
+    let from_window = from.active_window.as_ref();
+    let to_window = to.active_window.as_ref();
+    let from_app = from_window.map(|w| w.app_name.clone());
+    let to_app = to_window.map(|w| w.app_name.clone());
+    let app_switch = match (&from_app, &to_app) {
+        (Some(a), Some(b)) if a != b => Some(format!("Switched from {} to {}", a, b)),
+        (None, Some(b)) => Some(format!("Switched to {}", b)),
+        (Some(a), None) => Some(format!("Left {}", a)),
+        _ => None,
+    };
+    let app_changed = app_switch.is_some();
+    let window_changed =
+        !app_changed && from_window.map(|w| &w.title) != to_window.map(|w| &w.title);
+
+    let (added, removed) = line_diff(
+        from.ocr_text.as_deref().unwrap_or_default(),
+        to.ocr_text.as_deref().unwrap_or_default(),
+    );
+
+    let change_ratio = match (
+        parse_hash(from.phash.as_deref()),
+        parse_hash(to.phash.as_deref()),
+    ) {
+        (Some(a), Some(b)) => Some(change_detector::change_ratio(a, b)),
+        _ => None,
+    };
+
+    let mut parts = Vec::new();
+    if let Some(switch) = app_switch {
+        parts.push(switch);
+    } else if window_changed {
+        let title = to_window.map_or("", |w| w.title.as_str());
+        parts.push(match &to_app {
+            Some(app) => format!("Opened \"{}\" in {}", title, app),
+            None => format!("Opened \"{}\"", title),
+        });
+    }
+    match (added.len(), removed.len()) {
+        (0, 0) => {}
+        (a, 0) => parts.push(format!("{} {} added", a, lines(a))),
+        (0, r) => parts.push(format!("{} {} removed", r, lines(r))),
+        (a, r) => parts.push(format!("{} {} added, {} removed", a, lines(a), r)),
+    }
+    if from.ocr_failed || to.ocr_failed {
+        parts.push("OCR unavailable for one frame".to_string());
+    }
+    let summary = if parts.is_empty() {
+        "No visible change".to_string()
+    } else {
+        parts.join("; ")
+    };
+
+    ObservationDiff {
+        from_timestamp: from.timestamp,
+        to_timestamp: to.timestamp,
+        from_app,
+        to_app,
+        app_changed,
+        window_changed,
+        added_count: added.len(),
+        added_lines: added.into_iter().take(MAX_LISTED_LINES).collect(),
+        removed_count: removed.len(),
+        removed_lines: removed.into_iter().take(MAX_LISTED_LINES).collect(),
+        change_ratio,
+        summary,
+    }
+}
+
+fn lines(count: usize) -> &'static str {
+    if count == 1 {
+        "line"
+    } else {
+        "lines"
+    }
+}
+
+fn parse_hash(hex: Option<&str>) -> Option<u64> {
+    u64::from_str_radix(hex?, 16).ok()
+}
+
+/// Lines only in `after` and lines only in `before`, trimmed, blank lines
+/// skipped. A line repeated more often on one side counts once per extra
+/// occurrence.
+fn line_diff(before: &str, after: &str) -> (Vec<String>, Vec<String>) {
+    let mut counts: HashMap<&str, i64> = HashMap::new();
+    for line in before.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        *counts.entry(line).or_default() -= 1;
+    }
+    for line in after.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        *counts.entry(line).or_default() += 1;
+    }
+
+    let (mut added, mut removed) = (Vec::new(), Vec::new());
+    // Walk the texts in order so the lists read top to bottom
+    for line in after.lines().map(str::trim) {
+        if let Some(n) = counts.get_mut(line).filter(|n| **n > 0) {
+            *n -= 1;
+            added.push(line.to_string());
+        }
+    }
+    for line in before.lines().map(str::trim) {
+        if let Some(n) = counts.get_mut(line).filter(|n| **n < 0) {
+            *n += 1;
+            removed.push(line.to_string());
+        }
+    }
+    (added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::perception_cmd::WindowInfoResponse;
+    use crate::life_tree::LifeStage;
+
+    fn frame(
+        timestamp: u64,
+        app: &str,
+        title: &str,
+        ocr: &str,
+        phash: Option<&str>,
+    ) -> RecentFrame {
+        RecentFrame {
+            timestamp,
+            thumbnail_base64: None,
+            ocr_text: Some(ocr.to_string()),
+            ocr_failed: false,
+            active_window: Some(WindowInfoResponse {
+                app_name: app.to_string(),
+                title: title.to_string(),
+                bundle_id: None,
+            }),
+            change_ratio: 0.3,
+            phash: phash.map(str::to_string),
+            life_stage: LifeStage::Career,
+            task_label: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn app_switches_and_text_changes_are_summarized() {
+        let slack = frame(
+            1_000,
+            "Slack",
+            "general",
+            "hello\nlunch?",
+            Some("00000000000000ff"),
+        );
+        let code = frame(
+            2_000,
+            "Code",
+            "main.rs",
+            "fn main() {}\nhello",
+            Some("000000000000ffff"),
+        );
+        let changes = diff(&slack, &code);
+        assert!(changes.app_changed && !changes.window_changed);
+        assert_eq!(changes.added_lines, vec!["fn main() {}"]);
+        assert_eq!(changes.removed_lines, vec!["lunch?"]);
+        assert_eq!(changes.change_ratio, Some(0.125));
+        assert_eq!(
+            changes.summary,
+            "Switched from Slack to Code; 1 line added, 1 removed"
+        );
+
+        let lib = frame(3_000, "Code", "lib.rs", "fn main() {}\nhello", None);
+        let changes = diff(&code, &lib);
+        assert!(changes.window_changed);
+        assert_eq!(changes.change_ratio, None);
+        assert_eq!(changes.summary, "Opened \"lib.rs\" in Code");
+
+        assert_eq!(diff(&lib, &lib).summary, "No visible change");
+    }
+
+    #[test]
+    fn repeated_lines_are_counted() {
+        let (added, removed) = line_diff("ok\nok\n\n  x  ", "ok\ny\ny");
+        assert_eq!(added, vec!["y", "y"]);
+        assert_eq!(removed, vec!["ok", "x"]);
+    }
+}
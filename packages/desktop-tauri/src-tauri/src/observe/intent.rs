@@ -2,6 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::ai::ReplyLanguage;
+use crate::clock::{self, SharedClock};
+
 /// Intent types that can be recognized
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
@@ -38,6 +41,7 @@ pub struct IntentContext {
 }
 
 /// Input context for intent recognition
+#[derive(Debug, Clone)]
 pub struct RecognitionInput {
     pub app_name: Option<String>,
     pub window_title: Option<String>,
@@ -50,27 +54,45 @@ pub struct IntentRecognizer {
     min_confidence: f64,
     recent_intents: Vec<UserIntent>,
     max_recent: usize,
+    clock: SharedClock,
 }
 
 impl Default for IntentRecognizer {
     fn default() -> Self {
+        Self::with_clock(clock::system())
+    }
+}
+
+impl IntentRecognizer {
+    /// Recognizer that timestamps intents with `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
             min_confidence: 0.5,
             recent_intents: Vec::new(),
             max_recent: 20,
+            clock,
         }
     }
-}
 
-impl IntentRecognizer {
-    /// Recognize intents from observation context (rule-based)
+    /// Recognize intents from observation context (rule-based) and remember
+    /// them as recent intents
     pub fn recognize(&mut self, input: &RecognitionInput) -> Vec<UserIntent> {
+        let intents = self.evaluate(input);
+
+        // Store recent
+        for intent in &intents {
+            self.recent_intents.insert(0, intent.clone());
+        }
+        self.recent_intents.truncate(self.max_recent);
+
+        intents
+    }
+
+    /// Run the intent rules without recording anything
+    pub fn evaluate(&self, input: &RecognitionInput) -> Vec<UserIntent> {
         let mut intents = Vec::new();
         let context = self.build_context(input);
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
+        let now = self.clock.now_ms();
 
         if self.is_file_organize(input) {
             intents.push(self.create_intent(
@@ -126,12 +148,6 @@ impl IntentRecognizer {
         intents.retain(|i| i.confidence >= self.min_confidence);
         intents.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
 
-        // Store recent
-        for intent in &intents {
-            self.recent_intents.insert(0, intent.clone());
-        }
-        self.recent_intents.truncate(self.max_recent);
-
         intents
     }
 
@@ -140,8 +156,17 @@ impl IntentRecognizer {
         &self.recent_intents
     }
 
-    /// Build the AI prompt for enhanced recognition
-    pub fn build_ai_prompt(input: &RecognitionInput, rule_intents: &[UserIntent]) -> String {
+    /// Forget recently recognized intents
+    pub fn clear_recent(&mut self) {
+        self.recent_intents.clear();
+    }
+
+    /// Build the AI prompt for enhanced recognition, answered in `language`
+    pub fn build_ai_prompt(
+        input: &RecognitionInput,
+        rule_intents: &[UserIntent],
+        language: &ReplyLanguage,
+    ) -> String {
         let mut parts = Vec::new();
 
         if let Some(app) = &input.app_name {
@@ -166,9 +191,10 @@ impl IntentRecognizer {
              describing the user's current activity/intent.\n\n\
              Context:\n{}\n\n\
              Rule-based detection: {}\n\n\
-             Respond with ONLY the activity description, nothing else.",
+             Respond with ONLY the activity description, nothing else. {}",
             parts.join("\n"),
-            if rule_hints.is_empty() { "none".to_string() } else { rule_hints.join(", ") }
+            if rule_hints.is_empty() { "none".to_string() } else { rule_hints.join(", ") },
+            language.instruction()
         )
     }
 
@@ -310,3 +336,27 @@ fn rand_suffix() -> String {
         .subsec_nanos();
     format!("{:x}", nanos)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use std::time::Duration;
+
+    #[test]
+    fn intents_are_stamped_with_the_injected_clock() {
+        let clock = MockClock::new(1_000);
+        let mut recognizer = IntentRecognizer::with_clock(clock.clone());
+        let input = RecognitionInput {
+            app_name: Some("Cursor".to_string()),
+            window_title: Some("main.rs".to_string()),
+            ocr_text: None,
+            clipboard: None,
+        };
+
+        clock.advance(Duration::from_millis(500));
+        let intents = recognizer.recognize(&input);
+        assert!(intents.iter().any(|i| i.intent_type == IntentType::CodeAssist));
+        assert!(intents.iter().all(|i| i.created_at == 1_500));
+    }
+}
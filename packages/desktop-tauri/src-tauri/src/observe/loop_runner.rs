@@ -1,17 +1,138 @@
 //! Observe loop — background tokio task for screen monitoring
 
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::watch;
+use tokio::task::JoinHandle;
 
-use crate::event_sink::EventSink;
+use crate::ai::rate_limit::{self, RequestPriority};
+use crate::ai::ChatMessage;
+use crate::clock::{Clock, SystemClock};
+use crate::commands::debug_cmd::{finish_debug, record_debug, DebugEventType};
+use crate::event_sink::{EventSink, SharedSink};
 use crate::events;
+use crate::log_throttle;
 use crate::observe::change_detector;
+use crate::observe::intent::{IntentRecognizer, RecognitionInput, UserIntent};
+use crate::observe::media::{
+    MediaDetector, MediaSampling, MediaTransition, MAX_SAMPLE_INTERVAL_MS,
+};
+use crate::observe::recent_frames::{RecentFrame, DEFAULT_RECENT_FRAMES};
 use crate::perception;
+use crate::perception::screen::CaptureOptions;
 use crate::state::{AppState, ObservationResult};
 
+/// Minimum spacing between AI intent-enhancement calls
+const AI_INTENT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often window-lookup counters are reported to the debug timeline
+const WINDOW_STATS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Longest a window lookup is reused for frames whose change stayed local
+const WINDOW_REUSE_MAX_AGE: Duration = Duration::from_secs(10);
+
+/// Consecutive capture failures after which the loop gives up and stops
+pub const MAX_CONSECUTIVE_FAILURES: u32 = 10;
+
+/// Upper bound on the extra delay added after repeated capture failures
+const MAX_FAILURE_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A loop that hasn't ticked for this long is reported unhealthy. The
+/// heartbeat is only written once per cycle, so this must cover the longest
+/// sleep (the media sampling interval plus the full failure backoff) and
+/// still leave room for a slow OCR pass.
+pub const STALL_THRESHOLD: Duration = Duration::from_millis(MAX_SAMPLE_INTERVAL_MS)
+    .saturating_add(MAX_FAILURE_BACKOFF)
+    .saturating_add(Duration::from_secs(30));
+
+/// Extra delay before the next capture after `failures` failures in a row:
+/// 1s, 2s, 4s, ... capped at [`MAX_FAILURE_BACKOFF`]
+fn failure_backoff(failures: u32) -> Duration {
+    if failures == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_secs(1u64 << (failures - 1).min(16)).min(MAX_FAILURE_BACKOFF)
+}
+
+/// Whether a frame past the threshold can keep the last window instead of
+/// looking it up again (an `osascript` spawn on macOS). Typing and
+/// scrolling change one region of the screen, while switching apps or
+/// windows repaints most of it, so a change that
+/// [`change_detector::classify_change`] calls a minor edit or a scroll is
+/// taken as the same window, for at most [`WINDOW_REUSE_MAX_AGE`] after the
+/// last real lookup.
+fn reuse_window(
+    previous_hash: Option<u64>,
+    current_hash: u64,
+    tile_mask: u64,
+    last_lookup: Option<Instant>,
+    now: Instant,
+) -> bool {
+    let fresh = last_lookup.is_some_and(|at| now.duration_since(at) < WINDOW_REUSE_MAX_AGE);
+    fresh
+        && matches!(
+            change_detector::classify_change(previous_hash, current_hash, None, None, tile_mask),
+            change_detector::ChangeKind::MinorEdit | change_detector::ChangeKind::Scroll
+        )
+}
+
+/// Id handed to the next started loop, so a loop can tell its own handle
+/// from one that replaced it
+static NEXT_LOOP_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Observe loop tasks currently running
+static RUNNING_LOOPS: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of observe loop tasks currently running (should never exceed 1)
+pub fn running_loops() -> usize {
+    RUNNING_LOOPS.load(Ordering::SeqCst)
+}
+
+/// Counts a running loop task for as long as it is alive
+struct RunningGuard;
+
+impl RunningGuard {
+    fn new() -> Self {
+        RUNNING_LOOPS.fetch_add(1, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING_LOOPS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Liveness of a running loop, derived from its heartbeat
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopHealth {
+    pub secs_since_last_tick: f64,
+    /// The task is alive and ticked within [`STALL_THRESHOLD`]
+    pub healthy: bool,
+}
+
+impl LoopHealth {
+    fn new(last_tick_ms: u64, now_ms: u64, task_finished: bool) -> Self {
+        let since = Duration::from_millis(now_ms.saturating_sub(last_tick_ms));
+        Self {
+            secs_since_last_tick: since.as_secs_f64(),
+            healthy: !task_finished && since < STALL_THRESHOLD,
+        }
+    }
+}
+
 /// Handle to a running observe loop
 pub struct ObserveLoop {
+    id: u64,
     stop_tx: watch::Sender<bool>,
+    /// Wall-clock ms of the loop's latest iteration
+    last_tick: Arc<AtomicU64>,
+    task: JoinHandle<()>,
 }
 
 impl ObserveLoop {
@@ -20,44 +141,104 @@ impl ObserveLoop {
     /// Events are emitted through the supplied [`EventSink`] so the loop
     /// runs identically under Tauri (with `TauriSink`) or under a CLI
     /// (with `StdoutSink`).
+    ///
+    /// `threshold` seeds `AppState::observe_params`; later changes made
+    /// through `set_observe_params` take effect on the next frame.
     pub fn start(
         sink: Arc<dyn EventSink>,
         state: Arc<AppState>,
         interval_ms: u64,
         threshold: f64,
     ) -> Self {
+        let id = NEXT_LOOP_ID.fetch_add(1, Ordering::Relaxed);
         let (stop_tx, stop_rx) = watch::channel(false);
+        let last_tick = Arc::new(AtomicU64::new(SystemClock.now_ms()));
 
-        tokio::spawn(async move {
-            run_loop(sink, state, stop_rx, interval_ms, threshold).await;
+        let heartbeat = Arc::clone(&last_tick);
+        let task = tokio::spawn(async move {
+            run_loop(sink, state, id, stop_rx, heartbeat, interval_ms, threshold).await;
         });
 
-        Self { stop_tx }
+        Self {
+            id,
+            stop_tx,
+            last_tick,
+            task,
+        }
+    }
+
+    /// Start the loop into `AppState::observe_loop` unless one is already
+    /// running. The check and the insert happen under a single write lock,
+    /// so concurrent callers (tray menu, `start_observe`) can't both start a
+    /// loop. A handle whose task has died (panicked) is replaced. Returns
+    /// whether a loop was started.
+    pub async fn start_if_idle(state: &Arc<AppState>, sink: SharedSink) -> bool {
+        let mut loop_handle = state.observe_loop.write().await;
+        if let Some(existing) = loop_handle.as_ref() {
+            if !existing.task.is_finished() {
+                return false;
+            }
+            log::warn!("[Observe] Previous loop task died; restarting");
+        }
+
+        let threshold = state.observe_params.read().await.threshold;
+        *loop_handle = Some(Self::start(sink, Arc::clone(state), 3000, threshold));
+        true
     }
 
     /// Stop the observe loop
     pub fn stop(&self) {
         let _ = self.stop_tx.send(true);
     }
+
+    /// Time since the last iteration, and whether the task still looks alive
+    pub fn health(&self) -> LoopHealth {
+        LoopHealth::new(
+            self.last_tick.load(Ordering::Relaxed),
+            SystemClock.now_ms(),
+            self.task.is_finished(),
+        )
+    }
 }
 
 async fn run_loop(
     sink: Arc<dyn EventSink>,
     state: Arc<AppState>,
+    loop_id: u64,
     mut stop_rx: watch::Receiver<bool>,
+    heartbeat: Arc<AtomicU64>,
     _initial_interval_ms: u64,
     threshold: f64,
 ) {
+    let _running = RunningGuard::new();
     log::info!("[Observe] Loop started (adaptive, threshold={})", threshold);
+    state.observe_params.write().await.threshold = threshold;
 
     let mut last_hash: Option<u64> = None;
+    let mut last_tiles: Option<change_detector::TileLuma> = None;
+    let mut last_ai_intent: Option<Instant> = None;
+    let mut last_window: Option<perception::window::WindowInfo> = None;
+    let mut last_window_lookup: Option<Instant> = None;
+    let mut window_stats_since = (Instant::now(), perception::window::lookup_stats());
+    let mut accessibility_signalled = false;
+    let mut ocr_missing_reported = false;
+    let mut last_clipboard_hash: Option<u64> = None;
+    let mut consecutive_failures: u32 = 0;
+    let mut last_content: Option<u64> = None;
+    let mut media = MediaDetector::default();
 
     loop {
-        // Get adaptive interval
-        let sleep_ms = {
+        heartbeat.store(SystemClock.now_ms(), Ordering::Relaxed);
+
+        // Get adaptive interval, stretched while capture keeps failing.
+        // Media playback overrides it with the slow sampling interval.
+        let media_settings = state.config.read().await.media_sampling;
+        let sleep_ms = if media.is_active() {
+            media_settings.interval_ms()
+        } else {
             let mut ar = state.adaptive_refresh.write().await;
             ar.current_interval_ms()
-        };
+        } + failure_backoff(consecutive_failures).as_millis() as u64;
 
         tokio::select! {
             _ = tokio::time::sleep(tokio::time::Duration::from_millis(sleep_ms)) => {},
@@ -70,49 +251,112 @@ async fn run_loop(
             }
         }
 
-        // Capture screenshot
-        let capture = match perception::screen::capture_screenshot().await {
-            Ok(result) => result,
+        // The user's capture switch wins over a running loop
+        if !*state.observation_enabled.read().await {
+            continue;
+        }
+        // A clear from here on (stop, capture off) drops this cycle's frame
+        let frames_generation = state.recent_frames.read().await.generation();
+
+        // Capture screenshot — PNG, since the frame is hashed and OCR'd
+        let cycle_start = Instant::now();
+        let frame = match perception::screen::capture_primary_rgba().await {
+            Ok(frame) => frame,
             Err(e) => {
-                log::warn!("[Observe] Capture failed: {}", e);
+                consecutive_failures += 1;
+                if give_up_after_failure(&sink, &state, loop_id, consecutive_failures, &e).await {
+                    return;
+                }
                 continue;
             }
         };
 
-        let (base64_data, width, height) = capture;
-
-        // Decode base64 to raw RGBA for hashing
-        use base64::{engine::general_purpose::STANDARD, Engine};
-        let png_bytes = match STANDARD.decode(&base64_data) {
-            Ok(b) => b,
-            Err(_) => continue,
+        // Optionally narrow the frame to the focused window, so hashing and
+        // OCR ignore the menu bar and background windows
+        let focused_window = if state.config.read().await.observe_active_window_only {
+            perception::window::get_active_window(false).await.ok().flatten()
+        } else {
+            None
         };
+        let cropped = focused_window.as_ref().and_then(|w| crop_to_focused(&frame, w));
+        let is_cropped = cropped.is_some();
+        let rgba_data = cropped.unwrap_or(frame);
 
-        // Load PNG to get raw RGBA pixels
-        let rgba_data = match image::load_from_memory(&png_bytes) {
-            Ok(img) => img.to_rgba8(),
-            Err(_) => continue,
+        // Hash the raw RGBA frame directly — no PNG decode round-trip
+        let (width, height) = rgba_data.dimensions();
+        let capture_options = CaptureOptions::from_config(&*state.config.read().await);
+        let base64_data = match perception::screen::encode_rgba(&rgba_data, capture_options) {
+            Ok(png) => STANDARD.encode(png),
+            Err(e) => {
+                consecutive_failures += 1;
+                if give_up_after_failure(&sink, &state, loop_id, consecutive_failures, &e).await {
+                    return;
+                }
+                continue;
+            }
         };
+        if consecutive_failures > 0 {
+            log::info!("[Observe] Capture recovered after {} failure(s)", consecutive_failures);
+            consecutive_failures = 0;
+        }
 
-        let current_hash = change_detector::compute_phash(rgba_data.as_raw(), width, height);
+        let tiles = change_detector::compute_tiles(rgba_data.as_raw(), width, height);
+        let current_hash = change_detector::phash_from_tiles(&tiles);
+        let capture_ms = cycle_start.elapsed().as_millis() as u64;
 
         let change_ratio = match last_hash {
             Some(prev) => change_detector::change_ratio(prev, current_hash),
             None => 1.0, // First capture always counts as change
         };
+        let tile_mask = last_tiles
+            .map_or(u64::MAX, |prev| change_detector::tile_change_mask(&prev, &tiles));
+        let previous_hash = last_hash;
 
         last_hash = Some(current_hash);
+        last_tiles = Some(tiles);
+
+        // Poll the clipboard every tick — copying is activity even when the
+        // screen is static. Contents are never logged, only their length.
+        let clipboard = read_clipboard(&state, last_window.as_ref().map(|w| w.app_name.as_str())).await;
+        if let Some(text) = &clipboard {
+            let hash = perception::clipboard::content_hash(text);
+            if last_clipboard_hash != Some(hash) {
+                if last_clipboard_hash.is_some() {
+                    let chars = text.chars().count();
+                    log::debug!("[Observe] Clipboard changed ({} chars)", chars);
+                    {
+                        let mut ar = state.adaptive_refresh.write().await;
+                        ar.record_activity(super::adaptive_refresh::ActivityEventType::ClipboardChange);
+                    }
+                    record_debug(
+                        sink.as_ref(),
+                        &state,
+                        DebugEventType::Clipboard,
+                        "Clipboard changed",
+                        serde_json::json!({ "chars": chars }),
+                        None,
+                        None,
+                    )
+                    .await;
+                }
+                last_clipboard_hash = Some(hash);
+            }
+        }
+
+        let params = *state.observe_params.read().await;
+        if params.record_stats {
+            state.change_stats.write().await.record(change_ratio);
+        }
 
         // Only process if change exceeds threshold
-        if change_ratio < threshold {
+        if change_ratio < params.threshold {
+            if let Some(transition) = media.record(change_ratio, None, &media_settings) {
+                report_media(&sink, transition, None, &media_settings);
+            }
             continue;
         }
 
         log::info!("[Observe] Change detected (ratio={:.2})", change_ratio);
-        sink.emit(
-            events::OBSERVE_CHANGE,
-            serde_json::json!(change_ratio),
-        );
 
         // Record activity for adaptive refresh
         {
@@ -120,22 +364,258 @@ async fn run_loop(
             ar.record_activity(super::adaptive_refresh::ActivityEventType::ScreenChange);
         }
 
-        // Get active window
-        let window_info = perception::window::get_active_window().await.ok().flatten();
+        // Only frames past the threshold are timed, so idle screens don't
+        // flood the timeline. Every event of this cycle hangs off one parent
+        // `Observe` event, whose duration is filled in once the cycle ends.
+        let cycle_id = record_debug(
+            sink.as_ref(),
+            &state,
+            DebugEventType::Observe,
+            "Observe cycle",
+            serde_json::json!({ "changeRatio": change_ratio }),
+            None,
+            None,
+        )
+        .await;
+        record_debug(
+            sink.as_ref(),
+            &state,
+            DebugEventType::Screenshot,
+            "Screen captured",
+            serde_json::json!({
+                "width": width,
+                "height": height,
+                "changeRatio": change_ratio,
+                "croppedToWindow": is_cropped,
+            }),
+            Some(capture_ms),
+            cycle_id.as_deref(),
+        )
+        .await;
 
-        // Run OCR
-        let ocr_text = match perception::ocr::run_ocr(&base64_data).await {
-            Ok(result) => Some(result.text),
-            Err(e) => {
-                log::warn!("[Observe] OCR failed: {}", e);
+        // Run OCR — skipped entirely when the helper is missing
+        let ocr_available = *state.ocr_available.read().await;
+        if !ocr_available && !ocr_missing_reported {
+            ocr_missing_reported = true;
+            record_debug(
+                sink.as_ref(),
+                &state,
+                DebugEventType::Error,
+                "OCR helper missing",
+                serde_json::json!({ "hint": perception::ocr::MISSING_HELPER_HINT }),
+                None,
+                cycle_id.as_deref(),
+            )
+            .await;
+        }
+        let ocr_options = state.ocr_options().await;
+
+        // Get active window — skipped when the change stayed local (see
+        // `reuse_window`). The lookup and OCR are independent subprocess
+        // round-trips, so they run concurrently; each result is handled on
+        // its own below.
+        let window_reused = focused_window.is_none()
+            && last_window.is_some()
+            && reuse_window(
+                previous_hash,
+                current_hash,
+                tile_mask,
+                last_window_lookup,
+                Instant::now(),
+            );
+        let window_lookup = async {
+            let window_start = Instant::now();
+            let info = if window_reused {
+                last_window.clone()
+            } else if focused_window.is_some() {
+                // Already looked up for cropping this tick
+                focused_window
+            } else {
+                perception::window::get_active_window(false).await.ok().flatten()
+            };
+            (info, window_start.elapsed().as_millis() as u64)
+        };
+        let ocr_run = async {
+            if ocr_available {
+                Some(perception::ocr::run_ocr_for_monitor(&base64_data, 0, &ocr_options).await)
+            } else {
                 None
             }
         };
+        let ((window_info, window_ms), ocr_result) = tokio::join!(window_lookup, ocr_run);
+        if !window_reused {
+            last_window_lookup = Some(Instant::now());
+        }
+        record_debug(
+            sink.as_ref(),
+            &state,
+            DebugEventType::Window,
+            "Active window",
+            serde_json::json!({
+                "app": window_info.as_ref().map(|w| &w.app_name),
+                "reused": window_reused,
+            }),
+            Some(window_ms),
+            cycle_id.as_deref(),
+        )
+        .await;
+        // `observe:change-detected` waits for the window so the transition
+        // can be labelled
+        let change_kind = change_detector::classify_change(
+            previous_hash,
+            current_hash,
+            last_window.as_ref(),
+            window_info.as_ref(),
+            tile_mask,
+        );
+        sink.emit(
+            events::OBSERVE_CHANGE,
+            serde_json::json!({
+                "changeRatio": change_ratio,
+                "kind": change_kind,
+                "tilesChanged": tile_mask.count_ones(),
+            }),
+        );
+
+        // Playback detection keys on the window, so it runs once it's known
+        let window_key = window_info
+            .as_ref()
+            .map(|w| format!("{}/{}", w.app_name, w.title));
+        let transition = media.record(change_ratio, window_key.as_deref(), &media_settings);
+        if let Some(transition) = transition {
+            report_media(&sink, transition, window_info.as_ref(), &media_settings);
+        }
+
+        let previous_app = last_window.as_ref().map(|w| w.app_name.clone());
+        last_window = window_info.clone();
+
+        // App switch — a strong activity signal for adaptive refresh
+        if let (Some(from), Some(to)) = (previous_app, window_info.as_ref().map(|w| &w.app_name)) {
+            if from != *to {
+                {
+                    let mut ar = state.adaptive_refresh.write().await;
+                    ar.record_activity(super::adaptive_refresh::ActivityEventType::WindowSwitch);
+                }
+                record_debug(
+                    sink.as_ref(),
+                    &state,
+                    DebugEventType::Window,
+                    "App switched",
+                    serde_json::json!({ "from": from, "to": to }),
+                    None,
+                    cycle_id.as_deref(),
+                )
+                .await;
+            }
+        }
+
+        if !accessibility_signalled && perception::window::accessibility_permission_needed() {
+            accessibility_signalled = true;
+            sink.emit(
+                events::PERMISSION_REQUIRED,
+                serde_json::json!({ "permission": "accessibility" }),
+            );
+        }
+
+        if window_stats_since.0.elapsed() >= WINDOW_STATS_INTERVAL {
+            let now = perception::window::lookup_stats();
+            let (since, prev) = window_stats_since;
+            let minutes = since.elapsed().as_secs_f64() / 60.0;
+            let lookups = now.lookups - prev.lookups;
+            record_debug(
+                sink.as_ref(),
+                &state,
+                DebugEventType::Window,
+                "Window lookup rate",
+                serde_json::json!({
+                    "lookups": lookups,
+                    "cacheHits": now.cache_hits - prev.cache_hits,
+                    "lookupsPerMinute": lookups as f64 / minutes,
+                }),
+                None,
+                None,
+            )
+            .await;
+            window_stats_since = (Instant::now(), now);
+        }
+
+        let ocr_failed = !matches!(ocr_result, Some(Ok(_)));
+        let ocr_text = match ocr_result {
+            None => None,
+            Some(Ok(result)) => {
+                record_debug(
+                    sink.as_ref(),
+                    &state,
+                    DebugEventType::Ocr,
+                    "OCR",
+                    serde_json::json!({
+                        "backend": result.backend,
+                        "regions": result.regions.len(),
+                        "chars": result.text.chars().count(),
+                    }),
+                    Some(result.duration_ms),
+                    cycle_id.as_deref(),
+                )
+                .await;
+                Some(result.text)
+            }
+            Some(Err(e)) => {
+                log_throttle::warn(&format!("[Observe] OCR failed: {}", e));
+                record_debug(
+                    sink.as_ref(),
+                    &state,
+                    DebugEventType::Error,
+                    "OCR failed",
+                    serde_json::json!({ "error": e.to_string() }),
+                    None,
+                    cycle_id.as_deref(),
+                )
+                .await;
+                None
+            }
+        };
+
+        // A frame past the threshold can still show the same content once a
+        // transient animation settles; optionally drop it before it reaches
+        // intents, the activity log, the life tree and the frontend
+        let content = content_signature(window_info.as_ref(), ocr_text.as_deref());
+        let duplicate = content.is_some() && content == last_content;
+        if content.is_some() {
+            last_content = content;
+        }
+        if duplicate && state.config.read().await.observe_skip_duplicates {
+            log::debug!("[Observe] Frame identical to the last observation; skipped");
+            record_debug(
+                sink.as_ref(),
+                &state,
+                DebugEventType::Observe,
+                "Duplicate frame skipped",
+                serde_json::json!({
+                    "app": window_info.as_ref().map(|w| &w.app_name),
+                    "changeRatio": change_ratio,
+                }),
+                None,
+                cycle_id.as_deref(),
+            )
+            .await;
+            if let Some(id) = &cycle_id {
+                finish_debug(sink.as_ref(), &state, id, cycle_start.elapsed().as_millis() as u64).await;
+            }
+            continue;
+        }
+
+        let timestamp = SystemClock.now_ms();
 
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
+        let thumbnail_base64 = match perception::screen::encode_thumbnail(
+            &rgba_data,
+            perception::screen::THUMBNAIL_WIDTH,
+        ) {
+            Ok(thumb) => Some(thumb),
+            Err(e) => {
+                log_throttle::warn(&format!("[Observe] Thumbnail failed: {}", e));
+                None
+            }
+        };
 
         let window_response = window_info.map(|w| {
             crate::commands::perception_cmd::WindowInfoResponse {
@@ -145,65 +625,494 @@ async fn run_loop(
             }
         });
 
+        let (ocr_char_count, ocr_word_count) =
+            perception::ocr::text_counts(ocr_text.as_deref().unwrap_or_default());
         let observation = ObservationResult {
             screenshot_base64: Some(base64_data),
+            thumbnail_base64,
             ocr_text,
+            ocr_failed,
+            ocr_char_count,
+            ocr_word_count,
             active_window: window_response,
             change_ratio,
+            phash: change_detector::phash_hex(current_hash),
             timestamp,
         };
 
-        // Log activity entry for summarization
-        {
-            let mut log = state.activity_log.write().await;
-            log.push(super::activity_log::ActivityEntry {
-                timestamp,
-                app_name: observation.active_window.as_ref().map(|w| w.app_name.clone()),
-                window_title: observation.active_window.as_ref().map(|w| w.title.clone()),
-                ocr_snippet: observation.ocr_text.as_ref().map(|t| t.chars().take(200).collect()),
-                change_ratio,
-            });
-        }
-
         // Run intent recognition
         {
-            let input = super::intent::RecognitionInput {
-                app_name: observation.active_window.as_ref().map(|w| w.app_name.clone()),
+            let app_name = observation.active_window.as_ref().map(|w| w.app_name.clone());
+            // The clipboard was polled under the previous window; re-check
+            // the exclusion list against the current one.
+            let (excluded, local_only) = {
+                let config = state.config.read().await;
+                (
+                    perception::clipboard::is_excluded(app_name.as_deref(), &config.excluded_apps),
+                    config.local_only,
+                )
+            };
+            let input = RecognitionInput {
+                app_name,
                 window_title: observation.active_window.as_ref().map(|w| w.title.clone()),
                 ocr_text: observation.ocr_text.clone(),
-                clipboard: None,
+                clipboard: clipboard.filter(|_| !excluded),
             };
-            let mut recognizer = state.intent_recognizer.write().await;
-            let intents = recognizer.recognize(&input);
+            let intent_start = Instant::now();
+            let intents = state.intent_recognizer.write().await.recognize(&input);
+            record_debug(
+                sink.as_ref(),
+                &state,
+                DebugEventType::Intent,
+                "Intent recognition",
+                serde_json::json!({
+                    "count": intents.len(),
+                    "top": intents.first().map(|i| &i.description),
+                    "ocrFailed": observation.ocr_failed,
+                }),
+                Some(intent_start.elapsed().as_millis() as u64),
+                cycle_id.as_deref(),
+            )
+            .await;
             if !intents.is_empty() {
                 log::debug!("[Observe] Intents: {:?}", intents.iter().map(|i| &i.description).collect::<Vec<_>>());
                 if let Ok(payload) = serde_json::to_value(&intents) {
                     sink.emit(events::INTENT_RECOGNIZED, payload);
                 }
+
+                // Opt-in AI refinement, rate-limited so it never runs per frame
+                let enhance = state.config.read().await.ai_intent_enhancement;
+                let due = last_ai_intent.map_or(true, |t| t.elapsed() >= AI_INTENT_INTERVAL);
+                if enhance && due {
+                    last_ai_intent = Some(Instant::now());
+                    // local_only: clipboard text never goes to an AI provider
+                    let mut ai_input = input;
+                    if local_only {
+                        ai_input.clipboard = None;
+                    }
+                    tokio::spawn(enhance_intents(
+                        Arc::clone(&sink),
+                        Arc::clone(&state),
+                        ai_input,
+                        intents,
+                        cycle_id.clone(),
+                    ));
+                }
             }
         }
 
-        // Update life tree
-        {
-            let activity_ctx = crate::life_tree::ActivityContext {
-                app_name: observation.active_window.as_ref().map(|w| w.app_name.clone()),
-                window_title: observation.active_window.as_ref().map(|w| w.title.clone()),
-                ocr_snippet: observation.ocr_text.as_ref().map(|t| t.chars().take(200).collect()),
-                timestamp,
-            };
-            let mut tree = state.life_tree.write().await;
-            tree.process_activity(&activity_ctx);
+        // Activity log, life tree and last observation (with the full-res image)
+        let classified = record_frame(&state, &observation, frames_generation).await;
+        check_focus(&sink, &state, &observation, classified, cycle_id.as_deref()).await;
+
+        // The update is display-only; skip serializing it when the window is
+        // hidden (tray-only). `get_observe_status` still serves it on demand.
+        if sink.has_viewer() {
+            // The emitted update only carries the thumbnail to keep IPC traffic small
+            let mut update = observation;
+            update.screenshot_base64 = None;
+
+            if let Ok(payload) = serde_json::to_value(&update) {
+                sink.emit(events::OBSERVE_UPDATE, payload);
+            }
         }
 
-        // Store last observation
-        {
-            let mut last = state.last_observation.write().await;
-            *last = Some(observation.clone());
+        if let Some(id) = &cycle_id {
+            finish_debug(sink.as_ref(), &state, id, cycle_start.elapsed().as_millis() as u64).await;
+        }
+    }
+}
+
+/// Log and announce media playback starting or ending
+fn report_media(
+    sink: &SharedSink,
+    transition: MediaTransition,
+    window: Option<&perception::window::WindowInfo>,
+    settings: &MediaSampling,
+) {
+    match transition {
+        MediaTransition::Started => {
+            log::info!(
+                "[Observe] Media playback detected in {}; sampling every {}ms",
+                window.map_or("unknown window", |w| w.app_name.as_str()),
+                settings.interval_ms()
+            );
+            sink.emit(
+                events::OBSERVE_MEDIA_DETECTED,
+                serde_json::json!({
+                    "app": window.map(|w| &w.app_name),
+                    "title": window.map(|w| &w.title),
+                    "intervalMs": settings.interval_ms(),
+                }),
+            );
+        }
+        MediaTransition::Ended => {
+            log::info!("[Observe] Media playback ended; resuming normal cadence");
+            sink.emit(events::OBSERVE_MEDIA_ENDED, serde_json::Value::Null);
         }
+    }
+}
+
+/// Log a capture failure. Once [`MAX_CONSECUTIVE_FAILURES`] are reached in
+/// a row, emits `observe:error`, clears `AppState::observe_loop` if it still
+/// holds this loop and returns `true` so the loop exits.
+async fn give_up_after_failure(
+    sink: &SharedSink,
+    state: &AppState,
+    loop_id: u64,
+    failures: u32,
+    error: &anyhow::Error,
+) -> bool {
+    if failures < MAX_CONSECUTIVE_FAILURES {
+        log::warn!(
+            "[Observe] Capture failed ({} in a row, backing off {:?}): {}",
+            failures,
+            failure_backoff(failures),
+            error
+        );
+        return false;
+    }
+
+    let reason = format!("Screen capture failed {} times in a row: {}", failures, error);
+    log::error!("[Observe] {}; stopping", reason);
+    record_debug(
+        sink.as_ref(),
+        state,
+        DebugEventType::Error,
+        "Observe loop stopped",
+        serde_json::json!({ "reason": reason, "consecutiveFailures": failures }),
+        None,
+        None,
+    )
+    .await;
+    sink.emit(
+        events::OBSERVE_ERROR,
+        serde_json::json!({ "reason": reason, "consecutiveFailures": failures }),
+    );
+    release_own_handle(&mut *state.observe_loop.write().await, loop_id);
+    clear_recent_frames(state).await;
+    sink.emit(events::OBSERVE_STOPPED, serde_json::Value::Null);
+    true
+}
 
-        // Emit to host (frontend / stdout)
-        if let Ok(payload) = serde_json::to_value(&observation) {
-            sink.emit(events::OBSERVE_UPDATE, payload);
+/// Clear `slot` if it still holds the loop `loop_id`. A loop that gives up
+/// after being stopped and restarted must not drop its successor's handle.
+fn release_own_handle(slot: &mut Option<ObserveLoop>, loop_id: u64) -> bool {
+    if slot.as_ref().is_some_and(|observe| observe.id == loop_id) {
+        *slot = None;
+        true
+    } else {
+        false
+    }
+}
+
+/// Hash of what a frame shows: app, window title and OCR text. `None`
+/// without OCR text, since the pixels alone can't prove a repeat.
+fn content_signature(
+    window: Option<&perception::window::WindowInfo>,
+    ocr_text: Option<&str>,
+) -> Option<u64> {
+    use std::hash::{Hash, Hasher};
+
+    let ocr_text = ocr_text?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    window.map(|w| (&w.app_name, &w.title)).hash(&mut hasher);
+    ocr_text.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Crop a primary-display frame to the focused window, when its bounds are known
+fn crop_to_focused(
+    frame: &image::RgbaImage,
+    window: &perception::window::WindowInfo,
+) -> Option<image::RgbaImage> {
+    let bounds = window.bounds.as_ref()?;
+    let monitor = perception::screen::list_monitors().ok()?.into_iter().next()?;
+    perception::screen::crop_to_window(frame, &monitor, bounds)
+}
+
+/// Record a processed frame into the activity log, life tree and
+/// `last_observation`. Each lock is held only for its own update, per the
+/// lock ordering documented on [`AppState`]. `frames_generation` is the
+/// recent-frames generation read when the frame was captured.
+pub(crate) async fn record_frame(
+    state: &AppState,
+    observation: &ObservationResult,
+    frames_generation: u64,
+) -> (crate::life_tree::LifeStage, String) {
+    let app_name = observation.active_window.as_ref().map(|w| w.app_name.clone());
+    let window_title = observation.active_window.as_ref().map(|w| w.title.clone());
+    let ocr_snippet: Option<String> =
+        observation.ocr_text.as_ref().map(|t| t.chars().take(200).collect());
+
+    state.activity_log.write().await.push(super::activity_log::ActivityEntry {
+        timestamp: observation.timestamp,
+        end_timestamp: observation.timestamp,
+        app_name: app_name.clone(),
+        window_title: window_title.clone(),
+        ocr_snippet: ocr_snippet.clone(),
+        change_ratio: observation.change_ratio,
+    });
+
+    let activity_ctx = crate::life_tree::ActivityContext {
+        app_name,
+        window_title,
+        ocr_snippet,
+        timestamp: observation.timestamp,
+    };
+    let classified = state.life_tree.write().await.process_activity(&activity_ctx);
+
+    let capacity = state
+        .config
+        .read()
+        .await
+        .recent_frames_count
+        .unwrap_or(DEFAULT_RECENT_FRAMES);
+    if capacity > 0 {
+        let frame = RecentFrame::new(observation, classified.0.clone(), classified.1.clone());
+        let frames = state.recent_frames.read().await.clone();
+        let result =
+            tokio::task::spawn_blocking(move || frames.push(&frame, capacity, frames_generation))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|r| r);
+        if let Err(e) = result {
+            log_throttle::warn(&format!("[Observe] Failed to store recent frame: {}", e));
         }
     }
+
+    *state.last_observation.write().await = Some(observation.clone());
+    classified
+}
+
+/// Delete the recorded frames unless `retain_recent_frames` is set. Called
+/// wherever observing ends: stop, give-up, capture off and app exit.
+pub(crate) async fn clear_recent_frames(state: &AppState) {
+    if state.config.read().await.retain_recent_frames {
+        return;
+    }
+    let frames = state.recent_frames.read().await.clone();
+    let result = tokio::task::spawn_blocking(move || frames.clear())
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|r| r);
+    if let Err(e) = result {
+        log::warn!("[Observe] Failed to clear recent frames: {}", e);
+    }
+}
+
+/// Check a recorded frame against the running focus session, emitting
+/// `focus:distraction` when it moves the session off-task
+async fn check_focus(
+    sink: &SharedSink,
+    state: &AppState,
+    observation: &ObservationResult,
+    (stage, task_label): (crate::life_tree::LifeStage, String),
+    parent_id: Option<&str>,
+) {
+    let app_name = observation.active_window.as_ref().map(|w| w.app_name.as_str());
+    let distraction = match state.focus_session.write().await.as_mut() {
+        Some(session) => session.record(observation.timestamp, &stage, app_name, &task_label),
+        None => return,
+    };
+    let Some(distraction) = distraction else {
+        return;
+    };
+
+    log::info!("[Focus] Distraction: {:?} ({})", distraction.stage, distraction.task_label);
+    let payload = serde_json::to_value(&distraction).unwrap_or_default();
+    record_debug(
+        sink.as_ref(),
+        state,
+        DebugEventType::Observe,
+        "Focus distraction",
+        payload.clone(),
+        None,
+        parent_id,
+    )
+    .await;
+    sink.emit(events::FOCUS_DISTRACTION, payload);
+}
+
+/// Refine rule-based intents with a single AI call and emit the merged
+/// result. Runs off the loop task so a slow provider never delays capture.
+async fn enhance_intents(
+    sink: Arc<dyn EventSink>,
+    state: Arc<AppState>,
+    input: RecognitionInput,
+    rule_intents: Vec<UserIntent>,
+    parent_id: Option<String>,
+) {
+    let provider = match state.ai_client.read().await.as_ref().cloned() {
+        Some(p) => p,
+        None => return,
+    };
+    if !rate_limit::acquire(&state, provider.provider_name(), RequestPriority::Background).await {
+        return;
+    }
+
+    let persona = crate::ai::Persona::from_config(&*state.config.read().await);
+    let prompt = persona.style_prompt(IntentRecognizer::build_ai_prompt(
+        &input,
+        &rule_intents,
+        &persona.language,
+    ));
+    record_debug(
+        sink.as_ref(),
+        &state,
+        DebugEventType::LlmInput,
+        "Intent enhancement prompt",
+        serde_json::json!({
+            "provider": provider.provider_name(),
+            "model": provider.model_name(),
+            "prompt": prompt,
+        }),
+        None,
+        parent_id.as_deref(),
+    )
+    .await;
+
+    let messages = vec![ChatMessage {
+        role: "user".to_string(),
+        content: prompt,
+    }];
+
+    match provider.chat(messages).await {
+        Ok(response) => {
+            record_debug(
+                sink.as_ref(),
+                &state,
+                DebugEventType::LlmOutput,
+                "Intent enhancement response",
+                serde_json::json!({ "text": response.text, "usage": response.usage }),
+                Some(response.duration_ms),
+                parent_id.as_deref(),
+            )
+            .await;
+
+            let merged = IntentRecognizer::merge_ai_response(&rule_intents, &response.text);
+            if let Ok(payload) = serde_json::to_value(&merged) {
+                sink.emit(events::INTENT_RECOGNIZED, payload);
+            }
+        }
+        Err(e) => {
+            log_throttle::warn(&format!("[Observe] AI intent enhancement failed: {}", e));
+            record_debug(
+                sink.as_ref(),
+                &state,
+                DebugEventType::Error,
+                "Intent enhancement failed",
+                serde_json::json!({ "error": e.to_string() }),
+                None,
+                parent_id.as_deref(),
+            )
+            .await;
+        }
+    }
+}
+
+/// Current clipboard text, truncated. `None` when no reader is installed
+/// (headless) or the frontmost app is excluded for privacy.
+async fn read_clipboard(state: &AppState, app_name: Option<&str>) -> Option<String> {
+    let excluded = perception::clipboard::is_excluded(
+        app_name,
+        &state.config.read().await.excluded_apps,
+    );
+    if excluded {
+        return None;
+    }
+
+    let reader = state.clipboard_reader.read().await.clone()?;
+    let text = tokio::task::spawn_blocking(move || reader()).await.ok().flatten()?;
+    Some(perception::clipboard::truncate(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn health_goes_stale_after_threshold_or_when_task_ends() {
+        let fresh = LoopHealth::new(10_000, 13_500, false);
+        assert_eq!(fresh.secs_since_last_tick, 3.5);
+        assert!(fresh.healthy);
+
+        let stale_ms = 10_000 + STALL_THRESHOLD.as_millis() as u64;
+        assert!(!LoopHealth::new(10_000, stale_ms, false).healthy);
+        assert!(!LoopHealth::new(10_000, 10_500, true).healthy);
+    }
+
+    #[test]
+    fn failure_backoff_doubles_up_to_the_cap() {
+        assert_eq!(failure_backoff(0), Duration::ZERO);
+        assert_eq!(failure_backoff(1), Duration::from_secs(1));
+        assert_eq!(failure_backoff(4), Duration::from_secs(8));
+        assert_eq!(failure_backoff(MAX_CONSECUTIVE_FAILURES), MAX_FAILURE_BACKOFF);
+        assert_eq!(failure_backoff(u32::MAX), MAX_FAILURE_BACKOFF);
+
+        // The slowest sleep between heartbeats still reads as healthy
+        let longest = Duration::from_millis(MAX_SAMPLE_INTERVAL_MS) + MAX_FAILURE_BACKOFF;
+        assert!(longest < STALL_THRESHOLD);
+    }
+
+    #[test]
+    fn window_lookup_is_skipped_only_for_recent_local_changes() {
+        let now = Instant::now();
+        let looked_up = Some(now - Duration::from_secs(2));
+        // Identical frame (a threshold of 0 lets it through)
+        assert!(reuse_window(Some(7), 7, 0, looked_up, now));
+        // A few tiles changed: typing
+        assert!(reuse_window(Some(0), 0b111, 0b111, looked_up, now));
+        // Most of the screen repainted: look again
+        assert!(!reuse_window(Some(0), u64::MAX, u64::MAX, looked_up, now));
+        // No lookup yet, or the last one is too old
+        assert!(!reuse_window(Some(7), 7, 0, None, now));
+        let stale = Some(now - WINDOW_REUSE_MAX_AGE);
+        assert!(!reuse_window(Some(7), 7, 0, stale, now));
+        // First frame
+        assert!(!reuse_window(None, 7, u64::MAX, looked_up, now));
+    }
+
+    #[test]
+    fn content_signature_needs_ocr_and_tracks_window() {
+        let window = |title: &str| perception::window::WindowInfo {
+            app_name: "Code".to_string(),
+            title: title.to_string(),
+            bundle_id: None,
+            bounds: None,
+        };
+        let a = window("main.rs");
+        assert!(content_signature(Some(&a), None).is_none());
+        assert_eq!(
+            content_signature(Some(&a), Some("fn main()")),
+            content_signature(Some(&window("main.rs")), Some("fn main()"))
+        );
+        assert_ne!(
+            content_signature(Some(&a), Some("fn main()")),
+            content_signature(Some(&window("lib.rs")), Some("fn main()"))
+        );
+        assert_ne!(
+            content_signature(Some(&a), Some("fn main()")),
+            content_signature(Some(&a), Some("fn main() {}"))
+        );
+    }
+
+    #[tokio::test]
+    async fn a_loop_only_releases_its_own_handle() {
+        let handle = || ObserveLoop {
+            id: NEXT_LOOP_ID.fetch_add(1, Ordering::Relaxed),
+            stop_tx: watch::channel(false).0,
+            last_tick: Arc::default(),
+            task: tokio::spawn(async {}),
+        };
+        let stale = handle();
+        let current = handle();
+        let current_id = current.id;
+
+        let mut slot = Some(current);
+        assert!(!release_own_handle(&mut slot, stale.id));
+        assert!(slot.is_some());
+        assert!(release_own_handle(&mut slot, current_id));
+        assert!(slot.is_none());
+    }
 }
@@ -0,0 +1,144 @@
+//! Media playback detection
+//!
+//! Video playing in a window trips the change detector on every frame, so
+//! the loop would OCR (and maybe call the AI) for nothing. A run of frames
+//! that all change heavily in the same window is taken as playback: the
+//! loop drops to a slow sampling interval until a calm frame or a different
+//! window ends it.
+
+use serde::{Deserialize, Serialize};
+
+/// Longest sampling interval honoured. The loop's stall threshold is
+/// derived from it, so a slow-sampling loop that is also backing off after
+/// capture failures still reports healthy.
+pub(crate) const MAX_SAMPLE_INTERVAL_MS: u64 = 30_000;
+
+/// Media detection settings
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MediaSampling {
+    pub enabled: bool,
+    /// Change ratio a frame needs to count towards playback
+    pub min_change_ratio: f64,
+    /// Consecutive such frames in one window before sampling slows down
+    pub min_frames: u32,
+    /// Loop interval while playback is detected (at most 30s)
+    pub sample_interval_ms: u64,
+}
+
+impl Default for MediaSampling {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_change_ratio: 0.25,
+            min_frames: 8,
+            sample_interval_ms: 15_000,
+        }
+    }
+}
+
+impl MediaSampling {
+    /// The sampling interval, clamped to 1–30s
+    pub fn interval_ms(&self) -> u64 {
+        self.sample_interval_ms.clamp(1_000, MAX_SAMPLE_INTERVAL_MS)
+    }
+}
+
+/// Returned by [`MediaDetector::record`] when playback starts or ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaTransition {
+    Started,
+    Ended,
+}
+
+#[derive(Debug, Default)]
+pub struct MediaDetector {
+    /// Window the current run of busy frames is in
+    window: Option<String>,
+    streak: u32,
+    active: bool,
+}
+
+impl MediaDetector {
+    /// Whether the loop should sample slowly
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Account for a frame. `window` identifies the focused window (`None`
+    /// when unknown, which never counts as the same window).
+    pub fn record(
+        &mut self,
+        change_ratio: f64,
+        window: Option<&str>,
+        settings: &MediaSampling,
+    ) -> Option<MediaTransition> {
+        let busy = settings.enabled && change_ratio >= settings.min_change_ratio;
+        let same_window = window.is_some() && window == self.window.as_deref();
+
+        if busy && same_window {
+            self.streak += 1;
+            if !self.active && self.streak >= settings.min_frames.max(1) {
+                self.active = true;
+                return Some(MediaTransition::Started);
+            }
+            return None;
+        }
+
+        self.window = window.map(str::to_string);
+        self.streak = u32::from(busy);
+        if self.active {
+            self.active = false;
+            return Some(MediaTransition::Ended);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sustained_change_in_one_window_is_playback() {
+        let settings = MediaSampling {
+            min_frames: 3,
+            ..MediaSampling::default()
+        };
+        let mut detector = MediaDetector::default();
+        let video = Some("VLC — movie.mkv");
+
+        assert_eq!(detector.record(0.6, video, &settings), None);
+        assert_eq!(detector.record(0.5, video, &settings), None);
+        assert_eq!(
+            detector.record(0.7, video, &settings),
+            Some(MediaTransition::Started)
+        );
+        assert!(detector.is_active());
+        assert_eq!(detector.record(0.6, video, &settings), None);
+
+        // The picture settles: back to normal cadence
+        assert_eq!(
+            detector.record(0.02, video, &settings),
+            Some(MediaTransition::Ended)
+        );
+        assert!(!detector.is_active());
+
+        // Busy frames spread over different windows never add up
+        for window in ["Slack", "Code", "Safari", "Mail"] {
+            assert_eq!(detector.record(0.9, Some(window), &settings), None);
+        }
+        // Nor does an unknown window
+        for _ in 0..5 {
+            assert_eq!(detector.record(0.9, None, &settings), None);
+        }
+
+        let disabled = MediaSampling {
+            enabled: false,
+            ..settings
+        };
+        for _ in 0..5 {
+            assert_eq!(detector.record(0.9, video, &disabled), None);
+        }
+    }
+}
@@ -1,12 +1,21 @@
 //! Observe module — background screen monitoring with change detection
 
+pub mod activity_export;
 pub mod activity_log;
 pub mod adaptive_refresh;
+pub mod benchmark;
 pub mod change_detector;
+pub mod change_stats;
+pub mod focus;
+pub mod frame_diff;
 pub mod intent;
 pub mod loop_runner;
+pub mod media;
+pub mod once;
+pub mod recent_frames;
 
 pub use activity_log::ActivityLog;
 pub use adaptive_refresh::AdaptiveRefresh;
+pub use change_stats::{ChangeStats, ObserveParams};
 pub use intent::IntentRecognizer;
 pub use loop_runner::ObserveLoop;
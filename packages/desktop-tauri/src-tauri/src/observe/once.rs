@@ -0,0 +1,126 @@
+//! One-shot observation for tuning heuristics
+//!
+//! Runs capture → OCR → window → intent → life-stage classification a single
+//! time. With `commit = false` nothing is recorded, so intent rules and
+//! `classify_stage` can be iterated on without touching the life tree,
+//! activity log, or adaptive refresh state.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::clock::{Clock, SystemClock};
+use crate::commands::perception_cmd::WindowInfoResponse;
+use crate::life_tree::{ActivityContext, LifeStage};
+use crate::observe::adaptive_refresh::ActivityEventType;
+use crate::observe::change_detector;
+use crate::observe::intent::{RecognitionInput, UserIntent};
+use crate::observe::loop_runner::record_frame;
+use crate::perception;
+use crate::perception::screen::CaptureOptions;
+use crate::state::{AppState, ObservationResult};
+
+/// Everything the pipeline derived from one frame
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObserveOnceResult {
+    pub observation: ObservationResult,
+    pub intents: Vec<UserIntent>,
+    pub life_stage: LifeStage,
+    pub task_label: String,
+    /// Whether the result was recorded into app state
+    pub committed: bool,
+}
+
+/// Observe the screen once. When `commit` is true the result is recorded
+/// exactly as the observe loop would record a changed frame.
+pub async fn observe_once(state: &AppState, commit: bool) -> Result<ObserveOnceResult> {
+    state.ensure_capture_enabled().await?;
+    let frames_generation = state.recent_frames.read().await.generation();
+    let capture_options = CaptureOptions::from_config(&*state.config.read().await);
+    let (base64_data, rgba_data) =
+        perception::screen::capture_screenshot_with_pixels(capture_options).await?;
+    let thumbnail_base64 =
+        perception::screen::encode_thumbnail(&rgba_data, perception::screen::THUMBNAIL_WIDTH).ok();
+    let phash =
+        change_detector::compute_phash(rgba_data.as_raw(), rgba_data.width(), rgba_data.height());
+
+    let window_info = perception::window::get_active_window(true).await.ok().flatten();
+
+    let ocr_options = state.ocr_options().await;
+    let ocr_text = if *state.ocr_available.read().await {
+        match perception::ocr::run_ocr_for_monitor(&base64_data, 0, &ocr_options).await {
+            Ok(result) => Some(result.text),
+            Err(e) => {
+                log::warn!("[Observe] OCR failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let timestamp = SystemClock.now_ms();
+
+    let (ocr_char_count, ocr_word_count) =
+        perception::ocr::text_counts(ocr_text.as_deref().unwrap_or_default());
+    let observation = ObservationResult {
+        screenshot_base64: Some(base64_data),
+        thumbnail_base64,
+        ocr_failed: ocr_text.is_none(),
+        ocr_char_count,
+        ocr_word_count,
+        ocr_text,
+        active_window: window_info.map(|w| WindowInfoResponse {
+            app_name: w.app_name,
+            title: w.title,
+            bundle_id: w.bundle_id,
+        }),
+        // No previous frame to compare against; treated like the loop's first capture
+        change_ratio: 1.0,
+        phash: change_detector::phash_hex(phash),
+        timestamp,
+    };
+
+    let app_name = observation.active_window.as_ref().map(|w| w.app_name.clone());
+    let window_title = observation.active_window.as_ref().map(|w| w.title.clone());
+    let ocr_snippet: Option<String> =
+        observation.ocr_text.as_ref().map(|t| t.chars().take(200).collect());
+
+    let input = RecognitionInput {
+        app_name: app_name.clone(),
+        window_title: window_title.clone(),
+        ocr_text: observation.ocr_text.clone(),
+        clipboard: None,
+    };
+    let activity_ctx = ActivityContext {
+        app_name: app_name.clone(),
+        window_title: window_title.clone(),
+        ocr_snippet: ocr_snippet.clone(),
+        timestamp,
+    };
+
+    let (life_stage, task_label) = state.life_tree.read().await.classify(&activity_ctx);
+
+    let intents = if commit {
+        state.intent_recognizer.write().await.recognize(&input)
+    } else {
+        state.intent_recognizer.read().await.evaluate(&input)
+    };
+
+    if commit {
+        state
+            .adaptive_refresh
+            .write()
+            .await
+            .record_activity(ActivityEventType::ScreenChange);
+        record_frame(state, &observation, frames_generation).await;
+    }
+
+    Ok(ObserveOnceResult {
+        observation,
+        intents,
+        life_stage,
+        task_label,
+        committed: commit,
+    })
+}
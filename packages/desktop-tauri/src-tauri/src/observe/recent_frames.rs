@@ -0,0 +1,229 @@
+//! Recent frames kept on disk for debugging
+//!
+//! `last_observation` only explains the newest frame. To investigate a
+//! misclassification the last few recorded frames are written, thumbnail
+//! only, one JSON file each under `<data_dir>/recent_frames/`, and the
+//! oldest are pruned past the configured count. Writes run off the async
+//! runtime. The ring is cleared whenever observing ends (loop stopped or
+//! given up, capture switched off, app exit) unless `retain_recent_frames`
+//! is set. A frame captured before a clear is dropped rather than stored
+//! after it.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::commands::perception_cmd::WindowInfoResponse;
+use crate::life_tree::LifeStage;
+use crate::state::ObservationResult;
+
+/// Frames kept when `recent_frames_count` is unset
+pub const DEFAULT_RECENT_FRAMES: usize = 20;
+
+/// A recorded frame with what it was classified as
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFrame {
+    pub timestamp: u64,
+    /// Downscaled JPEG, as sent with `observe:update`
+    pub thumbnail_base64: Option<String>,
+    pub ocr_text: Option<String>,
+    pub ocr_failed: bool,
+    pub active_window: Option<WindowInfoResponse>,
+    pub change_ratio: f64,
+    /// Perceptual hash as hex; missing on frames stored by older versions
+    #[serde(default)]
+    pub phash: Option<String>,
+    pub life_stage: LifeStage,
+    pub task_label: String,
+}
+
+impl RecentFrame {
+    pub fn new(observation: &ObservationResult, life_stage: LifeStage, task_label: String) -> Self {
+        Self {
+            timestamp: observation.timestamp,
+            thumbnail_base64: observation.thumbnail_base64.clone(),
+            ocr_text: observation.ocr_text.clone(),
+            ocr_failed: observation.ocr_failed,
+            active_window: observation.active_window.clone(),
+            change_ratio: observation.change_ratio,
+            phash: Some(observation.phash.clone()),
+            life_stage,
+            task_label,
+        }
+    }
+}
+
+/// File-backed ring of the most recent frames. Cheap to clone, so blocking
+/// work can take a copy off the lock.
+#[derive(Clone)]
+pub struct RecentFrames {
+    dir: PathBuf,
+    /// Number of clears so far. Held across every file operation, so a
+    /// clear waits for a push in flight and reads never see a partial file.
+    generation: Arc<Mutex<u64>>,
+}
+
+impl Default for RecentFrames {
+    fn default() -> Self {
+        Self::with_dir(crate::paths::data_dir().join("recent_frames"))
+    }
+}
+
+impl RecentFrames {
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            generation: Arc::default(),
+        }
+    }
+
+    /// Current clear count; read it when a frame is captured and hand it to
+    /// [`Self::push`]
+    pub fn generation(&self) -> u64 {
+        *self.lock()
+    }
+
+    /// Store `frame`, then drop the oldest beyond `capacity`. Skipped when
+    /// the ring was cleared since `generation` was read.
+    pub fn push(&self, frame: &RecentFrame, capacity: usize, generation: u64) -> Result<()> {
+        let current = self.lock();
+        if *current != generation {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.dir.join(format!("{}.json", frame.timestamp));
+        std::fs::write(&path, serde_json::to_vec(frame)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        let files = self.files()?;
+        let excess = files.len().saturating_sub(capacity.max(1));
+        for (_, path) in &files[..excess] {
+            let _ = std::fs::remove_file(path);
+        }
+        Ok(())
+    }
+
+    /// Up to `count` frames, newest first. Unreadable files are skipped.
+    pub fn recent(&self, count: usize) -> Result<Vec<RecentFrame>> {
+        let _guard = self.lock();
+        let frames = self
+            .files()?
+            .into_iter()
+            .rev()
+            .filter_map(|(_, path)| {
+                let json = std::fs::read(&path).ok()?;
+                serde_json::from_slice(&json).ok()
+            })
+            .take(count)
+            .collect();
+        Ok(frames)
+    }
+
+    /// The frame recorded at `timestamp`, if it is still stored
+    pub fn get(&self, timestamp: u64) -> Result<Option<RecentFrame>> {
+        let _guard = self.lock();
+        let path = self.dir.join(format!("{}.json", timestamp));
+        match std::fs::read(&path) {
+            Ok(json) => Ok(Some(serde_json::from_slice(&json)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// Delete every stored frame
+    pub fn clear(&self) -> Result<()> {
+        let mut generation = self.lock();
+        *generation += 1;
+        match std::fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", self.dir.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, u64> {
+        self.generation.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Stored frame files with their timestamps, oldest first
+    fn files(&self) -> Result<Vec<(u64, PathBuf)>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut files: Vec<(u64, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    return None;
+                }
+                let timestamp = path.file_stem()?.to_str()?.parse().ok()?;
+                Some((timestamp, path))
+            })
+            .collect();
+        files.sort_by_key(|(timestamp, _)| *timestamp);
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(timestamp: u64) -> RecentFrame {
+        RecentFrame {
+            timestamp,
+            thumbnail_base64: Some("AAAA".to_string()),
+            ocr_text: Some(format!("frame {}", timestamp)),
+            ocr_failed: false,
+            active_window: None,
+            change_ratio: 0.4,
+            phash: None,
+            life_stage: LifeStage::Career,
+            task_label: "main.rs".to_string(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest_frames_up_to_capacity() {
+        let dir = std::env::temp_dir().join(format!("hawkeye-frames-{}", uuid::Uuid::new_v4()));
+        let frames = RecentFrames::with_dir(dir.clone());
+        assert!(frames.recent(5).unwrap().is_empty());
+
+        for timestamp in [1_000, 2_000, 3_000, 4_000] {
+            frames.push(&frame(timestamp), 3, frames.generation()).unwrap();
+        }
+        let recent = frames.recent(10).unwrap();
+        let stamps: Vec<u64> = recent.iter().map(|f| f.timestamp).collect();
+        assert_eq!(stamps, vec![4_000, 3_000, 2_000]);
+        let newest = &frames.recent(1).unwrap()[0];
+        assert_eq!(newest.ocr_text.as_deref(), Some("frame 4000"));
+        assert_eq!(frames.get(3_000).unwrap().unwrap().timestamp, 3_000);
+        assert!(frames.get(1_000).unwrap().is_none());
+
+        frames.clear().unwrap();
+        assert!(frames.recent(5).unwrap().is_empty());
+        frames.clear().unwrap();
+    }
+
+    #[test]
+    fn a_frame_captured_before_a_clear_is_dropped() {
+        let dir = std::env::temp_dir().join(format!("hawkeye-frames-{}", uuid::Uuid::new_v4()));
+        let frames = RecentFrames::with_dir(dir.clone());
+        let captured_at = frames.generation();
+
+        frames.clear().unwrap();
+        frames.push(&frame(1_000), 3, captured_at).unwrap();
+        assert!(frames.recent(5).unwrap().is_empty());
+
+        frames.push(&frame(2_000), 3, frames.generation()).unwrap();
+        assert_eq!(frames.recent(5).unwrap().len(), 1);
+        frames.clear().unwrap();
+    }
+}
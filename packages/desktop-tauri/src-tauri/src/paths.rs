@@ -0,0 +1,73 @@
+//! Filesystem locations for app data
+//!
+//! Everything Hawkeye persists (models, life tree, config) is resolved here
+//! so installs can be redirected for tests or portable setups. Precedence:
+//! the `HAWKEYE_DATA_DIR` env var, then `AppConfig::data_dir_override`, then
+//! the platform default.
+
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Env var that relocates all app data, including the config file
+pub const DATA_DIR_ENV: &str = "HAWKEYE_DATA_DIR";
+
+/// Identifier used for the platform data directory
+const APP_ID: &str = "com.hawkeye.desktop";
+
+static DATA_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+fn env_data_dir() -> Option<PathBuf> {
+    std::env::var_os(DATA_DIR_ENV)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Set the config-provided data directory. Must run before state that
+/// resolves paths (model manager, life tree) is built.
+pub fn set_data_dir_override(dir: Option<PathBuf>) {
+    if let Ok(mut guard) = DATA_DIR_OVERRIDE.write() {
+        *guard = dir.filter(|d| !d.as_os_str().is_empty());
+    }
+}
+
+/// Root directory for models, the life tree and other app data
+pub fn data_dir() -> PathBuf {
+    let configured = DATA_DIR_OVERRIDE.read().ok().and_then(|g| g.clone());
+    resolve_data_dir(env_data_dir(), configured)
+}
+
+/// The env var wins over the config override, which wins over the
+/// platform default
+fn resolve_data_dir(env: Option<PathBuf>, configured: Option<PathBuf>) -> PathBuf {
+    env.or(configured).unwrap_or_else(|| {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(APP_ID)
+    })
+}
+
+/// Directory holding `config.json`. Only the env var can move it — the
+/// config-file override is read from this file, so it can't relocate it.
+pub fn config_dir() -> Option<PathBuf> {
+    env_data_dir().or_else(|| dirs::config_dir().map(|d| d.join("hawkeye")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_var_beats_config_override_beats_platform_default() {
+        let env = PathBuf::from("/tmp/hawkeye-env");
+        let configured = PathBuf::from("/tmp/hawkeye-config");
+
+        assert_eq!(
+            resolve_data_dir(Some(env.clone()), Some(configured.clone())),
+            env
+        );
+        assert_eq!(resolve_data_dir(None, Some(configured.clone())), configured);
+
+        let default = resolve_data_dir(None, None);
+        assert!(default.ends_with(APP_ID), "{}", default.display());
+    }
+}
@@ -0,0 +1,33 @@
+//! Clipboard access for the observe loop
+//!
+//! Reading the clipboard needs the Tauri `AppHandle`, so the host installs a
+//! [`ClipboardReader`] into `AppState` at setup. Headless runs (CLI, tests)
+//! leave it unset and the loop simply sees no clipboard.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Returns the current clipboard text, or `None` if empty / non-text
+pub type ClipboardReader = Arc<dyn Fn() -> Option<String> + Send + Sync>;
+
+/// Maximum clipboard characters kept for intent recognition
+pub const MAX_CLIPBOARD_CHARS: usize = 2000;
+
+/// Truncate clipboard text to [`MAX_CLIPBOARD_CHARS`] characters
+pub fn truncate(text: &str) -> String {
+    text.chars().take(MAX_CLIPBOARD_CHARS).collect()
+}
+
+/// Cheap fingerprint for change detection, so the loop doesn't have to keep
+/// the previous clipboard text around
+pub fn content_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether `app_name` is in the privacy exclusion list (case-insensitive)
+pub fn is_excluded(app_name: Option<&str>, excluded_apps: &[String]) -> bool {
+    app_name.is_some_and(|name| excluded_apps.iter().any(|e| e.eq_ignore_ascii_case(name)))
+}
@@ -1,24 +1,39 @@
 //! Perception module — screen capture, OCR, window tracking
 
+pub mod clipboard;
 pub mod ocr;
+pub mod permissions;
 pub mod screen;
 pub mod window;
+pub mod window_capture;
 
 use anyhow::Result;
 use screenshots::Screen;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::state::AppState;
+
 static INITIALIZED: AtomicBool = AtomicBool::new(false);
 
 /// Initialize the perception engine. UI-agnostic — usable from Tauri,
 /// CLI, or tests.
-pub async fn init() -> Result<()> {
+///
+/// Probes for the OCR helper once and records the result in
+/// `AppState::ocr_available`, so the observe loop skips OCR rather than
+/// failing on every frame.
+pub async fn init(state: &AppState) -> Result<()> {
     if INITIALIZED.swap(true, Ordering::SeqCst) {
         return Ok(());
     }
 
     log::info!("[Perception] Initializing...");
 
+    let ocr_available = ocr::is_available();
+    *state.ocr_available.write().await = ocr_available;
+    if !ocr_available {
+        log::warn!("[Perception] OCR unavailable. {}", ocr::MISSING_HELPER_HINT);
+    }
+
     let screens = Screen::all().map_err(|e| anyhow::anyhow!("Failed to get screens: {}", e))?;
     log::info!("[Perception] Found {} screen(s)", screens.len());
 
@@ -2,9 +2,165 @@
 
 use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose::STANDARD, Engine};
-use serde::Deserialize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::io::Write;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use super::screen::MonitorInfo;
+use crate::config::AppConfig;
+use crate::log_throttle;
+
+/// Vision `VNRequestTextRecognitionLevel`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecognitionLevel {
+    Fast,
+    #[default]
+    Accurate,
+}
+
+impl RecognitionLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecognitionLevel::Fast => "fast",
+            RecognitionLevel::Accurate => "accurate",
+        }
+    }
+}
+
+/// How long the OCR helper may run before it is killed
+pub const DEFAULT_OCR_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Options forwarded to the Vision OCR helper
+#[derive(Debug, Clone)]
+pub struct OcrOptions {
+    /// Recognition languages in priority order (e.g. "zh-Hans", "en-US").
+    /// Empty means automatic detection, which misreads mixed CJK/Latin text.
+    pub languages: Vec<String>,
+    pub recognition_level: RecognitionLevel,
+    /// Applied to the helper's output before it reaches callers
+    pub filter: OcrFilter,
+    /// Per-attempt limit; a helper still running after this is killed
+    pub timeout: Duration,
+}
+
+impl Default for OcrOptions {
+    fn default() -> Self {
+        Self {
+            languages: Vec::new(),
+            recognition_level: RecognitionLevel::default(),
+            filter: OcrFilter::default(),
+            timeout: DEFAULT_OCR_TIMEOUT,
+        }
+    }
+}
+
+impl OcrOptions {
+    /// Read OCR defaults from the app config
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::with_filter(config, OcrFilter::from_config(config))
+    }
+
+    /// [`Self::from_config`] with an already compiled filter, such as
+    /// `AppState::ocr_filter`
+    pub fn with_filter(config: &AppConfig, filter: OcrFilter) -> Self {
+        Self {
+            languages: config.ocr_languages.clone(),
+            recognition_level: config.ocr_recognition_level,
+            filter,
+            timeout: config
+                .ocr_timeout_secs
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_OCR_TIMEOUT),
+        }
+    }
+}
+
+/// User-configured noise filter for recognized text (line numbers, UI
+/// chrome). Empty by default, in which case text passes through untouched.
+#[derive(Debug, Clone, Default)]
+pub struct OcrFilter {
+    /// Lowercased whole tokens to drop
+    stopwords: HashSet<String>,
+    patterns: Vec<Regex>,
+}
+
+impl OcrFilter {
+    /// Build a filter; invalid patterns are logged and skipped
+    pub fn new(stopwords: &[String], patterns: &[String]) -> Self {
+        let patterns = patterns
+            .iter()
+            .filter_map(|p| match Regex::new(p) {
+                Ok(re) => Some(re),
+                Err(e) => {
+                    // Only hand-edited or imported configs get here; saves
+                    // reject bad patterns
+                    log_throttle::warn(&format!(
+                        "[OCR] Ignoring invalid filter pattern '{}': {}",
+                        p, e
+                    ));
+                    None
+                }
+            })
+            .collect();
+
+        Self {
+            stopwords: stopwords.iter().map(|w| w.to_lowercase()).collect(),
+            patterns,
+        }
+    }
+
+    /// The filter configured by `ocr_stopwords` and `ocr_filter_patterns`
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(&config.ocr_stopwords, &config.ocr_filter_patterns)
+    }
+
+    /// Fail on the first pattern that doesn't compile, so a bad one is
+    /// refused when the config is saved
+    pub fn validate(patterns: &[String]) -> Result<()> {
+        for pattern in patterns {
+            Regex::new(pattern)
+                .map_err(|e| anyhow!("Invalid OCR filter pattern '{}': {}", pattern, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stopwords.is_empty() && self.patterns.is_empty()
+    }
+
+    /// Strip pattern matches and stopwords from one line of text
+    pub fn clean(&self, text: &str) -> String {
+        let mut cleaned = text.to_string();
+        for pattern in &self.patterns {
+            cleaned = pattern.replace_all(&cleaned, "").into_owned();
+        }
+        if self.stopwords.is_empty() {
+            return cleaned.trim().to_string();
+        }
+        cleaned
+            .split_whitespace()
+            .filter(|token| !self.stopwords.contains(&token.to_lowercase()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Clean every region, drop those left empty and rebuild `text`
+    pub fn apply(&self, result: &mut OcrResult) {
+        if self.is_empty() {
+            return;
+        }
+        for region in &mut result.regions {
+            region.text = self.clean(&region.text);
+        }
+        result.regions.retain(|r| !r.text.is_empty());
+        result.rebuild_text();
+    }
+}
 
 /// RAII guard that removes a temp file on drop
 struct TempFileGuard(PathBuf);
@@ -23,6 +179,35 @@ pub struct OcrResult {
     pub regions: Vec<OcrRegion>,
     pub duration_ms: u64,
     pub backend: String,
+    /// Monitor the image was captured from, when known
+    pub monitor_index: Option<usize>,
+    /// Pixel dimensions of the OCR'd image
+    pub image_width: u32,
+    pub image_height: u32,
+}
+
+impl OcrResult {
+    /// Drop regions recognized with less than `min_confidence`
+    pub fn retain_confident(&mut self, min_confidence: f32) {
+        self.regions.retain(|r| r.confidence >= min_confidence);
+        self.rebuild_text();
+    }
+
+    /// Recompute `text` from the remaining regions
+    fn rebuild_text(&mut self) {
+        self.text = self
+            .regions
+            .iter()
+            .map(|r| r.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
+
+/// Characters and whitespace-separated words in OCR text, a cheap measure
+/// of how text-heavy a screen is
+pub fn text_counts(text: &str) -> (usize, usize) {
+    (text.chars().count(), text.split_whitespace().count())
 }
 
 /// Individual recognized text region
@@ -34,7 +219,8 @@ pub struct OcrRegion {
     pub bbox: BoundingBox,
 }
 
-/// Bounding box from Vision API (normalized 0-1 coordinates)
+/// Bounding box from Vision API (normalized 0-1 coordinates, origin at the
+/// bottom-left of the image)
 #[derive(Debug, Clone, serde::Serialize, Deserialize)]
 pub struct BoundingBox {
     pub x: f64,
@@ -43,8 +229,31 @@ pub struct BoundingBox {
     pub height: f64,
 }
 
+/// Rectangle in global desktop coordinates (origin top-left)
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct GlobalRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Map a normalized Vision bounding box on a full-monitor capture to global
+/// desktop coordinates, flipping Vision's bottom-left origin.
+pub fn to_global_rect(bbox: &BoundingBox, monitor: &MonitorInfo) -> GlobalRect {
+    let width = monitor.width as f64;
+    let height = monitor.height as f64;
+
+    GlobalRect {
+        x: monitor.x as f64 + bbox.x * width,
+        y: monitor.y as f64 + (1.0 - bbox.y - bbox.height) * height,
+        width: bbox.width * width,
+        height: bbox.height * height,
+    }
+}
+
 /// Run OCR on a base64-encoded image using macOS Vision API
-pub async fn run_ocr(image_base64: &str) -> Result<OcrResult> {
+pub async fn run_ocr(image_base64: &str, options: &OcrOptions) -> Result<OcrResult> {
     let start = std::time::Instant::now();
 
     // Decode base64 to raw PNG bytes
@@ -52,21 +261,44 @@ pub async fn run_ocr(image_base64: &str) -> Result<OcrResult> {
         .decode(image_base64)
         .map_err(|e| anyhow!("Failed to decode base64: {}", e))?;
 
+    let (image_width, image_height) = image::ImageReader::new(std::io::Cursor::new(&image_data))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_dimensions().ok())
+        .unwrap_or((0, 0));
+
     #[cfg(target_os = "macos")]
     {
-        run_vision_ocr(&image_data, start).await
+        let mut result = run_vision_ocr(&image_data, options, start).await?;
+        result.image_width = image_width;
+        result.image_height = image_height;
+        options.filter.apply(&mut result);
+        Ok(result)
     }
 
     #[cfg(not(target_os = "macos"))]
     {
-        let _ = image_data;
+        let _ = (image_data, image_width, image_height, options);
         Err(anyhow!("OCR not supported on this platform"))
     }
 }
 
+/// Run OCR on a capture of monitor `monitor_index`, tagging the result so
+/// regions can be mapped with [`to_global_rect`]
+pub async fn run_ocr_for_monitor(
+    image_base64: &str,
+    monitor_index: usize,
+    options: &OcrOptions,
+) -> Result<OcrResult> {
+    let mut result = run_ocr(image_base64, options).await?;
+    result.monitor_index = Some(monitor_index);
+    Ok(result)
+}
+
 #[cfg(target_os = "macos")]
 async fn run_vision_ocr(
     image_data: &[u8],
+    options: &OcrOptions,
     start: std::time::Instant,
 ) -> Result<OcrResult> {
     // Write image to temp file
@@ -88,17 +320,19 @@ async fn run_vision_ocr(
 
     // Call Swift CLI
     let temp_path_str = temp_path.to_string_lossy().to_string();
-    let output = tokio::process::Command::new(&ocr_binary)
+    let mut command = tokio::process::Command::new(&ocr_binary);
+    command
         .arg(&temp_path_str)
-        .output()
-        .await
-        .map_err(|e| anyhow!("Failed to run OCR binary '{}': {}", ocr_binary, e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow!("OCR process failed: {}", stderr));
+        .args(["--level", options.recognition_level.as_str()]);
+    if !options.languages.is_empty() {
+        let languages = options.languages.join(",");
+        command.args(["--languages", languages.as_str()]);
     }
 
+    let output = run_helper(&mut command, options.timeout)
+        .await
+        .map_err(|e| anyhow!("OCR binary '{}': {}", ocr_binary, e))?;
+
     let stdout = String::from_utf8_lossy(&output.stdout);
 
     // Parse JSON output from Swift CLI
@@ -117,11 +351,110 @@ async fn run_vision_ocr(
         regions,
         duration_ms: start.elapsed().as_millis() as u64,
         backend: "macos-vision".to_string(),
+        monitor_index: None,
+        image_width: 0,
+        image_height: 0,
     })
 }
 
+/// Why a helper attempt failed; only `Spawn` is worth not retrying
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[derive(Debug)]
+enum HelperError {
+    Spawn(std::io::Error),
+    TimedOut(Duration),
+    Failed(String),
+}
+
+impl std::fmt::Display for HelperError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HelperError::Spawn(e) => write!(f, "failed to run: {}", e),
+            HelperError::TimedOut(limit) => write!(f, "timed out after {:?}", limit),
+            HelperError::Failed(stderr) => write!(f, "process failed: {}", stderr),
+        }
+    }
+}
+
+/// Run the helper once, killing it if it outlives `timeout`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+async fn run_helper_once(
+    command: &mut tokio::process::Command,
+    timeout: Duration,
+) -> std::result::Result<std::process::Output, HelperError> {
+    // Dropping the timed-out future drops the child, which kills it
+    command.kill_on_drop(true);
+    let output = tokio::time::timeout(timeout, command.output())
+        .await
+        .map_err(|_| HelperError::TimedOut(timeout))?
+        .map_err(HelperError::Spawn)?;
+    if !output.status.success() {
+        return Err(HelperError::Failed(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+    Ok(output)
+}
+
+/// Run the helper, retrying once after a timeout or failed exit. A wedged
+/// helper costs at most two timeouts instead of hanging the observe loop.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+async fn run_helper(
+    command: &mut tokio::process::Command,
+    timeout: Duration,
+) -> Result<std::process::Output> {
+    match run_helper_once(command, timeout).await {
+        Err(HelperError::TimedOut(_) | HelperError::Failed(_)) => {}
+        result => return result.map_err(|e| anyhow!("{}", e)),
+    }
+    log_throttle::warn("[OCR] Helper attempt failed, retrying once");
+    run_helper_once(command, timeout)
+        .await
+        .map_err(|e| anyhow!("{}", e))
+}
+
+/// How to restore a missing OCR helper, for logs and the debug timeline
+pub const MISSING_HELPER_HINT: &str = "The hawkeye-ocr helper is compiled from swift-ocr/ by build.rs; \
+     install Xcode command line tools (`xcode-select --install`) and rebuild with `cargo build`.";
+
+/// Whether OCR can run on this machine (the Swift helper exists on macOS)
+pub fn is_available() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        find_ocr_binary().is_ok()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        false
+    }
+}
+
+/// Helper path, cached after the first successful lookup so the observe
+/// loop doesn't rescan the build directories on every frame. Failures are
+/// not cached: a helper built while the app is running is still picked up.
+#[cfg(target_os = "macos")]
+static OCR_BINARY: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
 #[cfg(target_os = "macos")]
 fn find_ocr_binary() -> Result<String> {
+    let mut cached = OCR_BINARY.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(path) = cached.as_ref() {
+        return Ok(path.clone());
+    }
+    let path = locate_ocr_binary()?;
+    *cached = Some(path.clone());
+    Ok(path)
+}
+
+/// Forget the cached helper path
+#[cfg(all(test, target_os = "macos"))]
+fn reset_ocr_binary_cache() {
+    *OCR_BINARY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+#[cfg(target_os = "macos")]
+fn locate_ocr_binary() -> Result<String> {
     use std::path::Path;
 
     // 1. Check compile-time env from build.rs
@@ -166,3 +499,99 @@ fn find_ocr_binary() -> Result<String> {
         "hawkeye-ocr binary not found. Ensure Swift OCR helper was compiled during build."
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stuck_helper_is_killed_after_timeout_and_retry() {
+        // `sleep` stands in for a wedged OCR helper
+        let mut command = tokio::process::Command::new("sleep");
+        command.arg("30");
+        let start = std::time::Instant::now();
+        let err = run_helper(&mut command, Duration::from_millis(100))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("timed out"), "{}", err);
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let mut missing = tokio::process::Command::new("/nonexistent/hawkeye-ocr");
+        let err = run_helper(&mut missing, Duration::from_secs(1)).await.unwrap_err();
+        assert!(err.to_string().contains("failed to run"), "{}", err);
+    }
+
+    #[test]
+    fn global_rect_flips_vision_origin_and_offsets_by_monitor() {
+        let monitor = MonitorInfo {
+            index: 1,
+            id: 2,
+            x: 1920,
+            y: -100,
+            width: 1000,
+            height: 500,
+            scale_factor: 2.0,
+            is_primary: false,
+        };
+        let bbox = BoundingBox { x: 0.1, y: 0.8, width: 0.2, height: 0.1 };
+
+        let rect = to_global_rect(&bbox, &monitor);
+        assert!((rect.x - 2020.0).abs() < 1e-9);
+        assert!((rect.y - -50.0).abs() < 1e-9);
+        assert!((rect.width - 200.0).abs() < 1e-9);
+        assert!((rect.height - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn filter_strips_patterns_and_stopwords() {
+        let filter = OcrFilter::new(
+            &["Minimap".to_string()],
+            &[r"^\s*\d+\s+".to_string(), "(".to_string()],
+        );
+        assert_eq!(filter.clean("42   let x = 1;"), "let x = 1;");
+        assert_eq!(filter.clean("minimap Outline"), "Outline");
+
+        let region = |text: &str| OcrRegion {
+            text: text.to_string(),
+            confidence: 1.0,
+            bbox: BoundingBox { x: 0.0, y: 0.0, width: 0.1, height: 0.1 },
+        };
+        let mut result = OcrResult {
+            text: String::new(),
+            regions: vec![region("12 fn main()"), region("MINIMAP"), region("13 }")],
+            duration_ms: 0,
+            backend: "test".to_string(),
+            monitor_index: None,
+            image_width: 0,
+            image_height: 0,
+        };
+        filter.apply(&mut result);
+        assert_eq!(result.regions.len(), 2);
+        assert_eq!(result.text, "fn main()\n}");
+
+        assert!(OcrFilter::validate(&[r"^\s*\d+\s+".to_string()]).is_ok());
+        let err = OcrFilter::validate(&["ok".to_string(), "(".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("'('"), "{}", err);
+    }
+
+    #[test]
+    fn text_counts_measure_density() {
+        assert_eq!(text_counts(""), (0, 0));
+        assert_eq!(text_counts("fn main()\n}"), (11, 3));
+        assert_eq!(text_counts("  Café  au lait \n"), (17, 3));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn ocr_binary_lookup_is_cached_until_reset() {
+        reset_ocr_binary_cache();
+        let first = find_ocr_binary().ok();
+        assert_eq!(OCR_BINARY.lock().unwrap().clone(), first);
+        assert_eq!(find_ocr_binary().ok(), first);
+
+        reset_ocr_binary_cache();
+        assert!(OCR_BINARY.lock().unwrap().is_none());
+    }
+}
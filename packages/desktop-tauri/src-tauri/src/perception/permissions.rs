@@ -0,0 +1,196 @@
+//! macOS privacy (TCC) permission checks
+//!
+//! Screen capture, window titles, and voice input each sit behind a TCC
+//! permission. When one is missing the feature fails opaquely (black
+//! captures, empty titles, silent microphones), so onboarding checks these
+//! up front and asks the system to prompt.
+
+use serde::{Deserialize, Serialize};
+
+/// A TCC-gated capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionKind {
+    ScreenRecording,
+    Accessibility,
+    Microphone,
+    SpeechRecognition,
+}
+
+/// Grant state of a single permission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    /// The user has not been asked yet
+    NotDetermined,
+    /// Blocked by device policy (MDM / parental controls)
+    Restricted,
+}
+
+/// Grant state of every permission Hawkeye uses
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionStatus {
+    pub screen_recording: PermissionState,
+    pub accessibility: PermissionState,
+    pub microphone: PermissionState,
+    pub speech_recognition: PermissionState,
+}
+
+impl PermissionStatus {
+    pub fn all_granted(&self) -> bool {
+        [
+            self.screen_recording,
+            self.accessibility,
+            self.microphone,
+            self.speech_recognition,
+        ]
+        .iter()
+        .all(|s| *s == PermissionState::Granted)
+    }
+}
+
+/// Check every permission without prompting
+pub fn check_permissions() -> PermissionStatus {
+    PermissionStatus {
+        screen_recording: check_permission(PermissionKind::ScreenRecording),
+        accessibility: check_permission(PermissionKind::Accessibility),
+        microphone: check_permission(PermissionKind::Microphone),
+        speech_recognition: check_permission(PermissionKind::SpeechRecognition),
+    }
+}
+
+/// Check a single permission without prompting
+#[cfg(target_os = "macos")]
+pub fn check_permission(kind: PermissionKind) -> PermissionState {
+    match kind {
+        PermissionKind::ScreenRecording => macos::screen_recording(),
+        PermissionKind::Accessibility => macos::accessibility(false),
+        PermissionKind::Microphone => macos::microphone(),
+        PermissionKind::SpeechRecognition => macos::speech_recognition(),
+    }
+}
+
+/// Trigger the system prompt for `kind` and return the state at the time of
+/// the call. The prompts are asynchronous (and screen recording /
+/// accessibility send the user to System Settings), so callers should
+/// re-check afterwards.
+#[cfg(target_os = "macos")]
+pub fn request_permission(kind: PermissionKind) -> PermissionState {
+    match kind {
+        PermissionKind::ScreenRecording => macos::request_screen_recording(),
+        PermissionKind::Accessibility => macos::accessibility(true),
+        PermissionKind::Microphone => macos::request_microphone(),
+        PermissionKind::SpeechRecognition => macos::request_speech_recognition(),
+    }
+}
+
+/// Other platforms have no TCC gate; report everything as granted.
+#[cfg(not(target_os = "macos"))]
+pub fn check_permission(_kind: PermissionKind) -> PermissionState {
+    PermissionState::Granted
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn request_permission(_kind: PermissionKind) -> PermissionState {
+    PermissionState::Granted
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::PermissionState;
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_application_services::{kAXTrustedCheckOptionPrompt, AXIsProcessTrustedWithOptions};
+    use objc2_av_foundation::{AVAuthorizationStatus, AVCaptureDevice, AVMediaTypeAudio};
+    use objc2_core_foundation::{CFBoolean, CFDictionary};
+    use objc2_core_graphics::{CGPreflightScreenCaptureAccess, CGRequestScreenCaptureAccess};
+    use objc2_speech::{SFSpeechRecognizer, SFSpeechRecognizerAuthorizationStatus};
+
+    /// CoreGraphics can't distinguish "denied" from "never asked"
+    fn granted_or_denied(granted: bool) -> PermissionState {
+        if granted {
+            PermissionState::Granted
+        } else {
+            PermissionState::Denied
+        }
+    }
+
+    pub fn screen_recording() -> PermissionState {
+        granted_or_denied(CGPreflightScreenCaptureAccess())
+    }
+
+    pub fn request_screen_recording() -> PermissionState {
+        granted_or_denied(CGRequestScreenCaptureAccess())
+    }
+
+    /// With `prompt`, macOS shows the "grant accessibility" dialog when the
+    /// process isn't trusted yet.
+    pub fn accessibility(prompt: bool) -> PermissionState {
+        let options = unsafe {
+            CFDictionary::from_slices(
+                &[kAXTrustedCheckOptionPrompt],
+                &[CFBoolean::new(prompt)],
+            )
+        };
+        let trusted = unsafe { AXIsProcessTrustedWithOptions(Some(options.as_opaque())) };
+        granted_or_denied(trusted)
+    }
+
+    fn microphone_status() -> AVAuthorizationStatus {
+        unsafe {
+            let Some(media_type) = AVMediaTypeAudio else {
+                return AVAuthorizationStatus::NotDetermined;
+            };
+            AVCaptureDevice::authorizationStatusForMediaType(media_type)
+        }
+    }
+
+    pub fn microphone() -> PermissionState {
+        match microphone_status() {
+            AVAuthorizationStatus::Authorized => PermissionState::Granted,
+            AVAuthorizationStatus::Denied => PermissionState::Denied,
+            AVAuthorizationStatus::Restricted => PermissionState::Restricted,
+            _ => PermissionState::NotDetermined,
+        }
+    }
+
+    pub fn request_microphone() -> PermissionState {
+        let state = microphone();
+        if state == PermissionState::NotDetermined {
+            if let Some(media_type) = unsafe { AVMediaTypeAudio } {
+                let handler = RcBlock::new(|granted: Bool| {
+                    log::info!("[Permissions] Microphone granted: {}", granted.as_bool());
+                });
+                unsafe {
+                    AVCaptureDevice::requestAccessForMediaType_completionHandler(
+                        media_type, &handler,
+                    );
+                }
+            }
+        }
+        state
+    }
+
+    pub fn speech_recognition() -> PermissionState {
+        match unsafe { SFSpeechRecognizer::authorizationStatus() } {
+            SFSpeechRecognizerAuthorizationStatus::Authorized => PermissionState::Granted,
+            SFSpeechRecognizerAuthorizationStatus::Denied => PermissionState::Denied,
+            SFSpeechRecognizerAuthorizationStatus::Restricted => PermissionState::Restricted,
+            _ => PermissionState::NotDetermined,
+        }
+    }
+
+    pub fn request_speech_recognition() -> PermissionState {
+        let state = speech_recognition();
+        if state == PermissionState::NotDetermined {
+            let handler = RcBlock::new(|status: SFSpeechRecognizerAuthorizationStatus| {
+                log::info!("[Permissions] Speech recognition status: {:?}", status);
+            });
+            unsafe { SFSpeechRecognizer::requestAuthorization(&handler) };
+        }
+        state
+    }
+}
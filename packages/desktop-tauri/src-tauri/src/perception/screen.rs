@@ -4,9 +4,184 @@ use anyhow::{anyhow, Result};
 use base64::{engine::general_purpose::STANDARD, Engine};
 use image::ImageEncoder;
 use screenshots::Screen;
+use serde::{Deserialize, Serialize};
 
-/// Capture the primary screen and return (base64_png, width, height)
-pub async fn capture_screenshot() -> Result<(String, u32, u32)> {
+use super::window::WindowBounds;
+use crate::config::AppConfig;
+
+/// Image encoding for captured screenshots.
+///
+/// PNG is lossless and remains the default: Vision OCR should always be fed
+/// PNG for best accuracy. JPEG shrinks photographic screen content 5–10× and
+/// suits previews and UI paths.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureFormat {
+    #[default]
+    Png,
+    Jpeg,
+    /// The `image` crate only ships a lossless WebP encoder, so quality is
+    /// ignored for this format.
+    Webp,
+}
+
+impl CaptureFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            CaptureFormat::Png => "image/png",
+            CaptureFormat::Jpeg => "image/jpeg",
+            CaptureFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// Default JPEG quality (1–100)
+pub const DEFAULT_JPEG_QUALITY: u8 = 80;
+
+/// PNG deflate effort. Output is lossless at every level; higher levels
+/// trade encode time for smaller uploads to cloud vision APIs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PngCompression {
+    /// Minimal compression, fastest encode (the `image` crate's default)
+    #[default]
+    Fast,
+    /// zlib's default level
+    Default,
+    /// Smallest files, slowest encode
+    Best,
+}
+
+impl From<PngCompression> for image::codecs::png::CompressionType {
+    fn from(compression: PngCompression) -> Self {
+        match compression {
+            PngCompression::Fast => Self::Fast,
+            PngCompression::Default => Self::Default,
+            PngCompression::Best => Self::Best,
+        }
+    }
+}
+
+/// Screenshot encoding options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureOptions {
+    pub format: CaptureFormat,
+    /// Lossy quality 1–100; only used by JPEG
+    pub quality: u8,
+    /// Only used by PNG
+    pub png_compression: PngCompression,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            format: CaptureFormat::Png,
+            quality: DEFAULT_JPEG_QUALITY,
+            png_compression: PngCompression::default(),
+        }
+    }
+}
+
+impl CaptureOptions {
+    /// PNG with the configured compression; `quality` applies if the caller
+    /// switches to JPEG
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            format: CaptureFormat::Png,
+            quality: config.capture_jpeg_quality.unwrap_or(DEFAULT_JPEG_QUALITY),
+            png_compression: config.capture_png_compression,
+        }
+    }
+}
+
+/// A connected display. Position and size are in global desktop
+/// coordinates as reported by the OS (points on macOS).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    /// Position in `Screen::all()`; capture uses index 0
+    pub index: usize,
+    pub id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+    pub is_primary: bool,
+}
+
+/// List connected displays in capture order
+pub fn list_monitors() -> Result<Vec<MonitorInfo>> {
+    let screens = Screen::all().map_err(|e| anyhow!("Failed to get screens: {}", e))?;
+
+    Ok(screens
+        .iter()
+        .enumerate()
+        .map(|(index, screen)| {
+            let info = &screen.display_info;
+            MonitorInfo {
+                index,
+                id: info.id,
+                x: info.x,
+                y: info.y,
+                width: info.width,
+                height: info.height,
+                scale_factor: info.scale_factor,
+                is_primary: info.is_primary,
+            }
+        })
+        .collect())
+}
+
+/// Display metadata for settings UIs: a [`MonitorInfo`] plus its native
+/// pixel resolution (e.g. 3456×2234 for a 1728×1117-point Retina panel)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayInfo {
+    #[serde(flatten)]
+    pub monitor: MonitorInfo,
+    pub pixel_width: u32,
+    pub pixel_height: u32,
+}
+
+impl From<MonitorInfo> for DisplayInfo {
+    fn from(monitor: MonitorInfo) -> Self {
+        let scale = monitor.scale_factor.max(1.0) as f64;
+        Self {
+            pixel_width: (monitor.width as f64 * scale).round() as u32,
+            pixel_height: (monitor.height as f64 * scale).round() as u32,
+            monitor,
+        }
+    }
+}
+
+/// Metadata for every connected display. Reads `Screen::all()` only;
+/// nothing is captured.
+pub fn display_info() -> Result<Vec<DisplayInfo>> {
+    Ok(list_monitors()?.into_iter().map(DisplayInfo::from).collect())
+}
+
+/// Capture the primary screen and return (base64_image, width, height),
+/// encoded according to `options`
+pub async fn capture_screenshot(options: CaptureOptions) -> Result<(String, u32, u32)> {
+    let (base64_data, rgba) = capture_screenshot_with_pixels(options).await?;
+    Ok((base64_data, rgba.width(), rgba.height()))
+}
+
+/// Like [`capture_screenshot`], but also hands back the raw RGBA frame so
+/// callers that hash or thumbnail it don't have to decode the image again
+pub async fn capture_screenshot_with_pixels(
+    options: CaptureOptions,
+) -> Result<(String, image::RgbaImage)> {
+    let rgba = capture_primary_rgba().await?;
+    let encoded = encode_rgba(&rgba, options)?;
+    let base64_data = STANDARD.encode(&encoded);
+
+    Ok((base64_data, rgba))
+}
+
+/// Capture the primary screen as a raw RGBA frame, without encoding
+pub async fn capture_primary_rgba() -> Result<image::RgbaImage> {
     let screens = Screen::all().map_err(|e| anyhow!("Failed to get screens: {}", e))?;
 
     let screen = screens
@@ -21,18 +196,181 @@ pub async fn capture_screenshot() -> Result<(String, u32, u32)> {
     let width = image.width();
     let height = image.height();
 
-    let mut png_data = Vec::new();
-    let encoder = image::codecs::png::PngEncoder::new(&mut png_data);
-    encoder
-        .write_image(
-            image.as_raw(),
-            width,
-            height,
-            image::ExtendedColorType::Rgba8,
-        )
-        .map_err(|e| anyhow!("Failed to encode PNG: {}", e))?;
+    // `screenshots` links an older `image` release; rewrap the raw buffer
+    image::RgbaImage::from_raw(width, height, image.into_raw())
+        .ok_or_else(|| anyhow!("Captured buffer does not match its dimensions"))
+}
+
+/// Crop a capture of `monitor` to a window frame given in global points.
+/// `None` when the window lies entirely off this monitor.
+pub fn crop_to_window(
+    image: &image::RgbaImage,
+    monitor: &MonitorInfo,
+    bounds: &WindowBounds,
+) -> Option<image::RgbaImage> {
+    if monitor.width == 0 || monitor.height == 0 {
+        return None;
+    }
+    // Points → pixels; derived from the frame so Retina scaling is exact
+    let scale_x = image.width() as f64 / monitor.width as f64;
+    let scale_y = image.height() as f64 / monitor.height as f64;
+
+    let left = ((bounds.x - monitor.x as f64) * scale_x).max(0.0);
+    let top = ((bounds.y - monitor.y as f64) * scale_y).max(0.0);
+    let right = ((bounds.x + bounds.width - monitor.x as f64) * scale_x).min(image.width() as f64);
+    let bottom =
+        ((bounds.y + bounds.height - monitor.y as f64) * scale_y).min(image.height() as f64);
+
+    if right - left < 1.0 || bottom - top < 1.0 {
+        return None;
+    }
+
+    let (x, y) = (left as u32, top as u32);
+    let (width, height) = ((right - left) as u32, (bottom - top) as u32);
+    Some(image::imageops::crop_imm(image, x, y, width, height).to_image())
+}
+
+/// Encode an RGBA frame in the requested format
+pub fn encode_rgba(image: &image::RgbaImage, options: CaptureOptions) -> Result<Vec<u8>> {
+    let (width, height) = image.dimensions();
+    let mut data = Vec::new();
+
+    match options.format {
+        CaptureFormat::Png => {
+            image::codecs::png::PngEncoder::new_with_quality(
+                &mut data,
+                options.png_compression.into(),
+                image::codecs::png::FilterType::Adaptive,
+            )
+            .write_image(
+                image.as_raw(),
+                width,
+                height,
+                image::ExtendedColorType::Rgba8,
+            )
+            .map_err(|e| anyhow!("Failed to encode PNG: {}", e))?;
+        }
+        CaptureFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb = image::DynamicImage::ImageRgba8(image.clone()).to_rgb8();
+            let quality = options.quality.clamp(1, 100);
+            image::codecs::jpeg::JpegEncoder::new_with_quality(&mut data, quality)
+                .write_image(rgb.as_raw(), width, height, image::ExtendedColorType::Rgb8)
+                .map_err(|e| anyhow!("Failed to encode JPEG: {}", e))?;
+        }
+        CaptureFormat::Webp => {
+            image::codecs::webp::WebPEncoder::new_lossless(&mut data)
+                .write_image(image.as_raw(), width, height, image::ExtendedColorType::Rgba8)
+                .map_err(|e| anyhow!("Failed to encode WebP: {}", e))?;
+        }
+    }
+
+    Ok(data)
+}
+
+/// Width of the thumbnail sent with frequent observe updates
+pub const THUMBNAIL_WIDTH: u32 = 320;
+
+/// Downscale an RGBA frame to at most `max_width` pixels wide and encode it
+/// as a base64 JPEG. Keeps per-update IPC payloads small.
+pub fn encode_thumbnail(image: &image::RgbaImage, max_width: u32) -> Result<String> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Err(anyhow!("Cannot thumbnail an empty image"));
+    }
+
+    let thumb_width = max_width.min(width).max(1);
+    let thumb_height = ((height as u64 * thumb_width as u64) / width as u64).max(1) as u32;
+
+    let small = image::imageops::thumbnail(image, thumb_width, thumb_height);
+    let jpeg_data = encode_rgba(
+        &small,
+        CaptureOptions {
+            format: CaptureFormat::Jpeg,
+            quality: 70,
+            ..CaptureOptions::default()
+        },
+    )?;
+
+    Ok(STANDARD.encode(&jpeg_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crop_scales_points_to_pixels_and_clamps() {
+        // 2× display: 100×50 points captured as 200×100 pixels
+        let image = image::RgbaImage::new(200, 100);
+        let monitor = MonitorInfo {
+            index: 0,
+            id: 1,
+            x: 0,
+            y: 0,
+            width: 100,
+            height: 50,
+            scale_factor: 2.0,
+            is_primary: true,
+        };
+
+        let inside = WindowBounds { x: 10.0, y: 5.0, width: 20.0, height: 10.0 };
+        assert_eq!(crop_to_window(&image, &monitor, &inside).unwrap().dimensions(), (40, 20));
+
+        let overhanging = WindowBounds { x: 80.0, y: -10.0, width: 50.0, height: 30.0 };
+        assert_eq!(crop_to_window(&image, &monitor, &overhanging).unwrap().dimensions(), (40, 40));
+
+        let off_screen = WindowBounds { x: 500.0, y: 0.0, width: 10.0, height: 10.0 };
+        assert!(crop_to_window(&image, &monitor, &off_screen).is_none());
+    }
+
+    #[test]
+    fn png_compression_is_lossless_and_defaults_to_fast() {
+        assert_eq!(
+            CaptureOptions::from_config(&AppConfig::default()),
+            CaptureOptions::default()
+        );
 
-    let base64_data = STANDARD.encode(&png_data);
+        let image = image::RgbaImage::from_fn(256, 128, |x, y| {
+            image::Rgba([x as u8, y as u8, (x ^ y) as u8, 255])
+        });
+        let encode = |png_compression| {
+            encode_rgba(
+                &image,
+                CaptureOptions {
+                    png_compression,
+                    ..CaptureOptions::default()
+                },
+            )
+            .unwrap()
+        };
+        let (fast, best) = (encode(PngCompression::Fast), encode(PngCompression::Best));
+        assert!(
+            best.len() <= fast.len(),
+            "best {} > fast {}",
+            best.len(),
+            fast.len()
+        );
+        let decoded = image::load_from_memory(&best).unwrap().to_rgba8();
+        assert_eq!(decoded, image);
+    }
 
-    Ok((base64_data, width, height))
+    #[test]
+    fn display_info_reports_native_pixels() {
+        let monitor = MonitorInfo {
+            index: 0,
+            id: 1,
+            x: 0,
+            y: 0,
+            width: 1728,
+            height: 1117,
+            scale_factor: 2.0,
+            is_primary: true,
+        };
+        let json = serde_json::to_value(DisplayInfo::from(monitor)).unwrap();
+        assert_eq!(json["width"], 1728);
+        assert_eq!(json["pixelWidth"], 3456);
+        assert_eq!(json["pixelHeight"], 2234);
+        assert_eq!(json["isPrimary"], true);
+    }
 }
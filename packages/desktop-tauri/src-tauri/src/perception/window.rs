@@ -2,6 +2,12 @@
 
 use anyhow::{anyhow, Result};
 use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a lookup result is reused before querying the OS again
+pub const CACHE_TTL: Duration = Duration::from_millis(500);
 
 /// Active window info
 #[derive(Debug, Clone, Serialize)]
@@ -10,11 +16,119 @@ pub struct WindowInfo {
     pub app_name: String,
     pub title: String,
     pub bundle_id: Option<String>,
+    /// Focused window frame, when the platform reports it (macOS with
+    /// accessibility permission)
+    pub bounds: Option<WindowBounds>,
+}
+
+/// Window frame in global desktop points, origin at the top-left of the
+/// primary display (same space as [`super::screen::MonitorInfo`])
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+struct CachedWindow {
+    fetched_at: Instant,
+    info: Option<WindowInfo>,
+}
+
+static CACHE: Mutex<Option<CachedWindow>> = Mutex::new(None);
+static LOOKUPS: AtomicU64 = AtomicU64::new(0);
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static ACCESSIBILITY_DENIED: AtomicBool = AtomicBool::new(false);
+
+/// `true` once a lookup found accessibility permission missing (window
+/// titles are then read via the slower `osascript` fallback)
+pub fn accessibility_permission_needed() -> bool {
+    ACCESSIBILITY_DENIED.load(Ordering::Relaxed)
+}
+
+/// Cumulative lookup counters since process start
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowLookupStats {
+    /// Lookups that actually queried the OS (one `osascript` spawn on macOS)
+    pub lookups: u64,
+    /// Calls answered from the cache
+    pub cache_hits: u64,
+}
+
+pub fn lookup_stats() -> WindowLookupStats {
+    WindowLookupStats {
+        lookups: LOOKUPS.load(Ordering::Relaxed),
+        cache_hits: CACHE_HITS.load(Ordering::Relaxed),
+    }
 }
 
-/// Get the currently active window (macOS)
+/// Get the currently active window. Results younger than [`CACHE_TTL`] are
+/// reused; pass `force` to always query the OS.
+pub async fn get_active_window(force: bool) -> Result<Option<WindowInfo>> {
+    if !force {
+        if let Ok(cache) = CACHE.lock() {
+            if let Some(cached) = cache.as_ref().filter(|c| c.fetched_at.elapsed() < CACHE_TTL) {
+                CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached.info.clone());
+            }
+        }
+    }
+
+    LOOKUPS.fetch_add(1, Ordering::Relaxed);
+    let info = lookup_active_window().await?;
+
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = Some(CachedWindow {
+            fetched_at: Instant::now(),
+            info: info.clone(),
+        });
+    }
+
+    Ok(info)
+}
+
+/// Query the frontmost window (macOS).
+///
+/// App name and bundle id come from `NSWorkspace`; the window title comes
+/// from the Accessibility API. Without accessibility permission the title
+/// falls back to `osascript`, and [`accessibility_permission_needed`] flips
+/// to `true` so the UI can prompt the user.
 #[cfg(target_os = "macos")]
-pub async fn get_active_window() -> Result<Option<WindowInfo>> {
+async fn lookup_active_window() -> Result<Option<WindowInfo>> {
+    let Some(app) = native::frontmost_app() else {
+        return Ok(None);
+    };
+
+    if native::is_trusted() {
+        ACCESSIBILITY_DENIED.store(false, Ordering::Relaxed);
+        let window = native::focused_window(app.pid);
+        return Ok(Some(WindowInfo {
+            app_name: app.name,
+            title: window.as_ref().and_then(|w| w.title.clone()).unwrap_or_default(),
+            bundle_id: app.bundle_id,
+            bounds: window.and_then(|w| w.bounds),
+        }));
+    }
+
+    if !ACCESSIBILITY_DENIED.swap(true, Ordering::Relaxed) {
+        log::warn!("[Window] Accessibility permission not granted; falling back to osascript");
+    }
+
+    let fallback = lookup_via_osascript().await?;
+    Ok(Some(WindowInfo {
+        title: fallback.map(|w| w.title).unwrap_or_default(),
+        app_name: app.name,
+        bundle_id: app.bundle_id,
+        bounds: None,
+    }))
+}
+
+/// Legacy System Events lookup, used when accessibility is denied
+#[cfg(target_os = "macos")]
+async fn lookup_via_osascript() -> Result<Option<WindowInfo>> {
     use std::process::Command;
 
     let output = Command::new("osascript")
@@ -44,6 +158,7 @@ pub async fn get_active_window() -> Result<Option<WindowInfo>> {
                 app_name: parts[0].to_string(),
                 title: parts[1].to_string(),
                 bundle_id: None,
+                bounds: None,
             }));
         }
     }
@@ -51,7 +166,94 @@ pub async fn get_active_window() -> Result<Option<WindowInfo>> {
     Ok(None)
 }
 
+/// NSWorkspace + Accessibility (AXUIElement) bindings
+#[cfg(target_os = "macos")]
+pub(super) mod native {
+    use super::WindowBounds;
+    use objc2_app_kit::NSWorkspace;
+    use objc2_application_services::{
+        AXError, AXIsProcessTrusted, AXUIElement, AXValue, AXValueType,
+    };
+    use objc2_core_foundation::{CFRetained, CFString, CFType, CGPoint, CGSize, ConcreteType};
+    use std::ptr::NonNull;
+
+    pub struct FrontmostApp {
+        pub name: String,
+        pub bundle_id: Option<String>,
+        pub pid: i32,
+    }
+
+    /// Frontmost application. Needs no special permission.
+    pub fn frontmost_app() -> Option<FrontmostApp> {
+        let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+        Some(FrontmostApp {
+            name: app.localizedName().map(|n| n.to_string()).unwrap_or_default(),
+            bundle_id: app.bundleIdentifier().map(|b| b.to_string()),
+            pid: app.processIdentifier(),
+        })
+    }
+
+    /// Whether this process has accessibility permission
+    pub fn is_trusted() -> bool {
+        unsafe { AXIsProcessTrusted() }
+    }
+
+    /// Text of a CF value, if it is a string. Shared with `window_capture`.
+    pub fn string_value(value: &CFType) -> Option<String> {
+        value.downcast_ref::<CFString>().map(|s| s.to_string())
+    }
+
+    pub struct FocusedWindow {
+        pub title: Option<String>,
+        pub bounds: Option<WindowBounds>,
+    }
+
+    /// Title and frame of the focused window of `pid`. Requires [`is_trusted`].
+    pub fn focused_window(pid: i32) -> Option<FocusedWindow> {
+        let app = unsafe { AXUIElement::new_application(pid) };
+        let window = copy_attribute::<AXUIElement>(&app, "AXFocusedWindow")?;
+        Some(FocusedWindow {
+            title: copy_attribute::<CFString>(&window, "AXTitle").map(|t| t.to_string()),
+            bounds: window_bounds(&window),
+        })
+    }
+
+    /// `AXPosition` + `AXSize`, both already top-left global points
+    fn window_bounds(window: &AXUIElement) -> Option<WindowBounds> {
+        let mut origin = CGPoint::default();
+        let mut size = CGSize::default();
+        let position = copy_attribute::<AXValue>(window, "AXPosition")?;
+        let extent = copy_attribute::<AXValue>(window, "AXSize")?;
+        let read = unsafe {
+            position.value(AXValueType::CGPoint, NonNull::from(&mut origin).cast())
+                && extent.value(AXValueType::CGSize, NonNull::from(&mut size).cast())
+        };
+        read.then_some(WindowBounds {
+            x: origin.x,
+            y: origin.y,
+            width: size.width,
+            height: size.height,
+        })
+    }
+
+    /// Copy an AX attribute of `element`, if it holds a `T`
+    fn copy_attribute<T: ConcreteType>(
+        element: &AXUIElement,
+        name: &'static str,
+    ) -> Option<CFRetained<T>> {
+        let attribute = CFString::from_static_str(name);
+        let mut value: *const CFType = std::ptr::null();
+        let err = unsafe { element.copy_attribute_value(&attribute, NonNull::from(&mut value)) };
+        if err != AXError::Success {
+            return None;
+        }
+        // The copy is owned by the caller
+        let value = unsafe { CFRetained::from_raw(NonNull::new(value as *mut CFType)?) };
+        value.downcast::<T>().ok()
+    }
+}
+
 #[cfg(not(target_os = "macos"))]
-pub async fn get_active_window() -> Result<Option<WindowInfo>> {
+async fn lookup_active_window() -> Result<Option<WindowInfo>> {
     Ok(None)
 }
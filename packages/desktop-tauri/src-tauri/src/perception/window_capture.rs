@@ -0,0 +1,246 @@
+//! Capture a specific app's window, even when it isn't frontmost
+//!
+//! Full-screen capture can't isolate a background app (e.g. a logs terminal
+//! behind the editor). On macOS this asks the window server for the
+//! window's own backing image via `CGWindowListCreateImage`, so occluding
+//! windows don't show up in the result.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use super::window::WindowBounds;
+
+/// Which window to capture: `app` matches the owning app's name
+/// (case-insensitive) or its bundle id; `title` narrows to windows whose
+/// title contains it
+#[derive(Debug, Clone)]
+pub struct WindowQuery {
+    pub app: String,
+    pub title: Option<String>,
+}
+
+/// An on-screen window as listed by the window server
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedWindow {
+    #[serde(skip)]
+    pub id: u32,
+    pub app_name: String,
+    pub bundle_id: Option<String>,
+    pub title: Option<String>,
+    pub bounds: WindowBounds,
+}
+
+/// Pick the window `query` refers to. Several matches (e.g. an app's main
+/// window plus palettes) resolve to the largest.
+pub fn select_window<'a>(windows: &'a [NamedWindow], query: &WindowQuery) -> Option<&'a NamedWindow> {
+    let app = query.app.trim();
+    let title = query.title.as_deref().map(str::to_lowercase);
+    windows
+        .iter()
+        .filter(|w| {
+            w.app_name.eq_ignore_ascii_case(app)
+                || w.bundle_id.as_deref().is_some_and(|b| b.eq_ignore_ascii_case(app))
+        })
+        .filter(|w| match &title {
+            Some(t) => w.title.as_deref().is_some_and(|wt| wt.to_lowercase().contains(t)),
+            None => true,
+        })
+        .max_by(|a, b| {
+            let area = |w: &NamedWindow| w.bounds.width * w.bounds.height;
+            area(a).partial_cmp(&area(b)).unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
+/// Capture the window matching `query` as an RGBA frame. Needs screen
+/// recording permission.
+pub async fn capture_window(query: WindowQuery) -> Result<(NamedWindow, image::RgbaImage)> {
+    tokio::task::spawn_blocking(move || {
+        let windows = list_windows()?;
+        let window = select_window(&windows, &query).cloned().ok_or_else(|| {
+            anyhow!(
+                "Window not found: nothing on screen for app '{}'{}",
+                query.app,
+                query.title.as_deref().map(|t| format!(" with title containing '{}'", t)).unwrap_or_default()
+            )
+        })?;
+        let image = capture_window_image(window.id)?;
+        Ok((window, image))
+    })
+    .await
+    .map_err(|e| anyhow!("Window capture task failed: {}", e))?
+}
+
+#[cfg(target_os = "macos")]
+fn list_windows() -> Result<Vec<NamedWindow>> {
+    Ok(native::list_windows())
+}
+
+#[cfg(target_os = "macos")]
+fn capture_window_image(id: u32) -> Result<image::RgbaImage> {
+    native::capture(id).ok_or_else(|| {
+        anyhow!("Window capture returned no image; check screen recording permission")
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+fn list_windows() -> Result<Vec<NamedWindow>> {
+    Err(anyhow!("Capturing a named window is only supported on macOS"))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn capture_window_image(_id: u32) -> Result<image::RgbaImage> {
+    Err(anyhow!("Capturing a named window is only supported on macOS"))
+}
+
+/// CoreGraphics window list + window image bindings
+#[cfg(target_os = "macos")]
+mod native {
+    use super::{NamedWindow, WindowBounds};
+    use crate::perception::window::native::string_value;
+    use objc2_app_kit::NSRunningApplication;
+    use objc2_core_foundation::{
+        CFArray, CFDictionary, CFNumber, CFRetained, CFString, CFType, CGPoint, CGRect, CGSize,
+    };
+    use objc2_core_graphics::{
+        kCGWindowBounds, kCGWindowLayer, kCGWindowName, kCGWindowNumber, kCGWindowOwnerName,
+        kCGWindowOwnerPID, CGBitmapContextCreate, CGColorSpace, CGContext, CGImage,
+        CGImageAlphaInfo, CGImageByteOrderInfo, CGRectMakeWithDictionaryRepresentation, CGRectNull,
+        CGWindowImageOption, CGWindowListCopyWindowInfo, CGWindowListCreateImage,
+        CGWindowListOption,
+    };
+
+    /// One entry of the window server's window list
+    type WindowInfo = CFDictionary<CFString, CFType>;
+
+    /// Normal-layer windows currently on screen (covered ones included)
+    pub fn list_windows() -> Vec<NamedWindow> {
+        let options =
+            CGWindowListOption::OptionOnScreenOnly | CGWindowListOption::ExcludeDesktopElements;
+        let Some(list) = CGWindowListCopyWindowInfo(options, 0) else {
+            return Vec::new();
+        };
+        // The list holds one info dictionary per window
+        let list = unsafe { CFRetained::cast_unchecked::<CFArray<WindowInfo>>(list) };
+        list.iter()
+            .filter_map(|info| unsafe { named_window(&info) })
+            .collect()
+    }
+
+    unsafe fn named_window(info: &WindowInfo) -> Option<NamedWindow> {
+        // Layer 0 is ordinary app windows; menus, docks etc. sit above
+        if number(info, kCGWindowLayer)? != 0 {
+            return None;
+        }
+        let id = number(info, kCGWindowNumber)?;
+        let pid = number(info, kCGWindowOwnerPID)?;
+        let app_name = string(info, kCGWindowOwnerName)?;
+        let bounds = info.get(kCGWindowBounds)?.downcast::<CFDictionary>().ok()?;
+        let mut rect = CGRect::default();
+        if !CGRectMakeWithDictionaryRepresentation(Some(&bounds), &mut rect) {
+            return None;
+        }
+        Some(NamedWindow {
+            id: id as u32,
+            app_name,
+            bundle_id: bundle_id(pid as i32),
+            // Empty unless screen recording permission is granted
+            title: string(info, kCGWindowName).filter(|t| !t.is_empty()),
+            bounds: WindowBounds {
+                x: rect.origin.x,
+                y: rect.origin.y,
+                width: rect.size.width,
+                height: rect.size.height,
+            },
+        })
+    }
+
+    /// The window's own content at native resolution, without its shadow.
+    /// `None` when the window server refuses (no permission, window gone).
+    #[allow(deprecated)] // ScreenCaptureKit has no synchronous equivalent
+    pub fn capture(id: u32) -> Option<image::RgbaImage> {
+        let image = CGWindowListCreateImage(
+            unsafe { CGRectNull },
+            CGWindowListOption::OptionIncludingWindow,
+            id,
+            CGWindowImageOption::BoundsIgnoreFraming | CGWindowImageOption::BestResolution,
+        )?;
+        draw_rgba(&image)
+    }
+
+    /// Render a CGImage into an RGBA buffer
+    fn draw_rgba(image: &CGImage) -> Option<image::RgbaImage> {
+        let (width, height) = (CGImage::width(Some(image)), CGImage::height(Some(image)));
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u8; width * height * 4];
+        let space = CGColorSpace::new_device_rgb()?;
+        let context = unsafe {
+            CGBitmapContextCreate(
+                buffer.as_mut_ptr().cast(),
+                width,
+                height,
+                8,
+                width * 4,
+                Some(&space),
+                // RGBA bytes in memory order
+                CGImageAlphaInfo::PremultipliedLast.0 | CGImageByteOrderInfo::Order32Big.0,
+            )
+        }?;
+        let rect = CGRect::new(CGPoint::ZERO, CGSize::new(width as f64, height as f64));
+        CGContext::draw_image(Some(&context), rect, Some(image));
+        // The context draws into `buffer`, so release it first
+        drop(context);
+        image::RgbaImage::from_raw(width as u32, height as u32, buffer)
+    }
+
+    fn number(info: &WindowInfo, key: &CFString) -> Option<i64> {
+        info.get(key)?.downcast::<CFNumber>().ok()?.as_i64()
+    }
+
+    fn string(info: &WindowInfo, key: &CFString) -> Option<String> {
+        string_value(&info.get(key)?)
+    }
+
+    fn bundle_id(pid: i32) -> Option<String> {
+        NSRunningApplication::runningApplicationWithProcessIdentifier(pid)
+            .and_then(|app| app.bundleIdentifier())
+            .map(|b| b.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(app: &str, bundle: &str, title: &str, width: f64) -> NamedWindow {
+        NamedWindow {
+            id: 0,
+            app_name: app.to_string(),
+            bundle_id: Some(bundle.to_string()),
+            title: Some(title.to_string()),
+            bounds: WindowBounds { x: 0.0, y: 0.0, width, height: 100.0 },
+        }
+    }
+
+    #[test]
+    fn selects_largest_match_by_name_bundle_or_title() {
+        let windows = vec![
+            window("Terminal", "com.apple.Terminal", "logs — tail", 400.0),
+            window("Terminal", "com.apple.Terminal", "zsh", 800.0),
+            window("Code", "com.microsoft.VSCode", "main.rs", 1200.0),
+        ];
+        let query = |app: &str, title: Option<&str>| WindowQuery {
+            app: app.to_string(),
+            title: title.map(str::to_string),
+        };
+
+        assert_eq!(select_window(&windows, &query("terminal", None)).unwrap().title.as_deref(), Some("zsh"));
+        assert_eq!(
+            select_window(&windows, &query("com.apple.Terminal", Some("LOGS"))).unwrap().bounds.width,
+            400.0
+        );
+        assert!(select_window(&windows, &query("Safari", None)).is_none());
+    }
+}
@@ -0,0 +1,504 @@
+//! Whole-app state snapshots
+//!
+//! A snapshot is a zip holding everything needed to move an install or
+//! reproduce a support case: config, life tree, activity log, adaptive
+//! refresh state, the model manifest and chat conversations. Model weights
+//! are not included; the manifest only lists them.
+//!
+//! Credentials (API keys, the Hugging Face token) are blanked on export;
+//! an import keeps the ones already configured.
+//!
+//! Imports parse and validate every file before anything is replaced, then
+//! stage the new files next to the old ones and swap them in together, so a
+//! bad archive or a failed write leaves the current state untouched.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::ai::conversations::{Conversation, ConversationStore};
+use crate::config::AppConfig;
+use crate::life_tree::LifeTreeSnapshot;
+use crate::models::manager::LocalModel;
+use crate::observe::activity_log::ActivityLogState;
+use crate::observe::adaptive_refresh::AdaptiveRefreshState;
+use crate::perception::ocr::OcrFilter;
+use crate::state::AppState;
+
+/// Archive layout version. Bump when a file's format changes incompatibly;
+/// imports of any other version are refused.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "snapshot.json";
+const CONFIG_FILE: &str = "config.json";
+const LIFE_TREE_FILE: &str = "life_tree.json";
+const ACTIVITY_LOG_FILE: &str = "activity_log.json";
+const ADAPTIVE_REFRESH_FILE: &str = "adaptive_refresh.json";
+const MODEL_MANIFEST_FILE: &str = "models/manifest.json";
+const CONVERSATIONS_DIR: &str = "conversations/";
+
+/// `snapshot.json` — identifies the archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+    pub version: u32,
+    /// Hawkeye version that wrote the archive
+    pub app_version: String,
+    pub created_at: u64,
+}
+
+/// Parsed contents of a snapshot archive
+#[derive(Debug, Clone)]
+pub struct StateSnapshot {
+    pub manifest: SnapshotManifest,
+    pub config: AppConfig,
+    pub life_tree: LifeTreeSnapshot,
+    pub activity_log: ActivityLogState,
+    pub adaptive_refresh: AdaptiveRefreshState,
+    pub models: Vec<LocalModel>,
+    pub conversations: Vec<Conversation>,
+}
+
+/// What an export or import covered
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotReport {
+    pub path: String,
+    pub version: u32,
+    pub app_version: String,
+    pub created_at: u64,
+    pub activity_entries: usize,
+    pub life_tree_nodes: usize,
+    pub models: usize,
+    pub conversations: usize,
+}
+
+impl SnapshotReport {
+    pub fn new(path: &Path, snapshot: &StateSnapshot) -> Self {
+        Self {
+            path: path.display().to_string(),
+            version: snapshot.manifest.version,
+            app_version: snapshot.manifest.app_version.clone(),
+            created_at: snapshot.manifest.created_at,
+            activity_entries: snapshot.activity_log.entries.len(),
+            life_tree_nodes: snapshot.life_tree.nodes.len(),
+            models: snapshot.models.len(),
+            conversations: snapshot.conversations.len(),
+        }
+    }
+}
+
+/// Gather the current state. Each lock is held only for its own copy.
+pub async fn capture(state: &AppState, created_at: u64) -> Result<StateSnapshot> {
+    let config = state.config.read().await.clone();
+    let models = state
+        .model_manager
+        .read()
+        .await
+        .list_models()
+        .map_err(|e| anyhow!(e))?;
+    let life_tree = state.life_tree.read().await.snapshot();
+    let activity_log = state.activity_log.read().await.export_state();
+    let adaptive_refresh = state.adaptive_refresh.write().await.export_state();
+    let conversations = state.conversations.read().await.list()?;
+
+    Ok(StateSnapshot {
+        manifest: SnapshotManifest {
+            version: SNAPSHOT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at,
+        },
+        config,
+        life_tree,
+        activity_log,
+        adaptive_refresh,
+        models,
+        conversations,
+    })
+}
+
+/// Replace the running state with `snapshot`. The observe loop is stopped
+/// first so it can't record into half-restored state. Machine-specific
+/// settings (data and models directories, the capture switch) and
+/// credentials keep their local values; conversations not in the snapshot
+/// are removed. The AI provider is not re-initialized; call `init_ai` to
+/// pick up imported settings.
+pub async fn restore(state: &AppState, snapshot: StateSnapshot) -> Result<()> {
+    check_life_tree(&snapshot.life_tree)?;
+    let conversation_files = snapshot
+        .conversations
+        .iter()
+        .map(|c| Ok((ConversationStore::file_name(&c.id)?, serde_json::to_string_pretty(c)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(observe) = state.observe_loop.write().await.take() {
+        observe.stop();
+        log::info!("[Snapshot] Stopped observe loop for import");
+    }
+
+    let conversations_dir = state.conversations.read().await.dir().to_path_buf();
+    let ocr_filter;
+    {
+        // Held until the swap is done so no save lands in between. The tree
+        // is a leaf lock, so it is taken last.
+        let mut current_config = state.config.write().await;
+        let model_manager = state.model_manager.read().await;
+        let mut tree = state.life_tree.write().await;
+
+        let mut config = snapshot.config;
+        config.data_dir_override = current_config.data_dir_override.clone();
+        config.models_dir_override = current_config.models_dir_override.clone();
+        config.observation_enabled = current_config.observation_enabled;
+        config.copy_secrets_from(&current_config);
+
+        let mut staging = Staging::default();
+        staging.file(
+            crate::config::get_config_path()?,
+            &serde_json::to_string_pretty(&config)?,
+        )?;
+        staging.file(
+            tree.data_path().to_path_buf(),
+            &serde_json::to_string_pretty(&snapshot.life_tree)?,
+        )?;
+        staging.file(
+            model_manager.manifest_path(),
+            &serde_json::to_string_pretty(&snapshot.models)?,
+        )?;
+        staging.dir(conversations_dir, &conversation_files)?;
+        // Ordered after any background tree save still in flight
+        tree.replace_file(|| staging.commit())?;
+
+        tree.set_phase_thresholds(config.experiment_phase_thresholds);
+        tree.restore(snapshot.life_tree);
+        ocr_filter = OcrFilter::from_config(&config);
+        *current_config = config;
+    }
+    *state.ocr_filter.write().await = ocr_filter;
+
+    state.activity_log.write().await.restore_state(snapshot.activity_log);
+    state
+        .adaptive_refresh
+        .write()
+        .await
+        .restore_state(snapshot.adaptive_refresh);
+    Ok(())
+}
+
+fn check_life_tree(life_tree: &LifeTreeSnapshot) -> Result<()> {
+    if !life_tree.nodes.iter().any(|n| n.id == life_tree.root_id) {
+        return Err(anyhow!("{}: root node '{}' is missing", LIFE_TREE_FILE, life_tree.root_id));
+    }
+    Ok(())
+}
+
+/// New files and directories written next to their targets, then moved
+/// into place together by [`Staging::commit`]. Staged copies that are never
+/// committed are removed on drop.
+#[derive(Default)]
+struct Staging {
+    /// (target, staged copy)
+    entries: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Staging {
+    fn file(&mut self, target: PathBuf, contents: &str) -> Result<()> {
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let staged = sibling(&target, "import");
+        self.entries.push((target, staged.clone()));
+        std::fs::write(&staged, contents)
+            .with_context(|| format!("Failed to write {}", staged.display()))
+    }
+
+    /// Stage a directory holding exactly `files` (name, contents)
+    fn dir(&mut self, target: PathBuf, files: &[(String, String)]) -> Result<()> {
+        let staged = sibling(&target, "import");
+        remove_path(&staged);
+        std::fs::create_dir_all(&staged)
+            .with_context(|| format!("Failed to create {}", staged.display()))?;
+        self.entries.push((target, staged.clone()));
+        for (name, contents) in files {
+            std::fs::write(staged.join(name), contents)
+                .with_context(|| format!("Failed to write {}", name))?;
+        }
+        Ok(())
+    }
+
+    /// Move each staged copy over its target, setting the old one aside.
+    /// If a move fails, the targets already replaced are put back.
+    fn commit(mut self) -> Result<()> {
+        let entries = std::mem::take(&mut self.entries);
+        let mut replaced = Vec::with_capacity(entries.len());
+        for (target, staged) in &entries {
+            match swap_in(target, staged) {
+                Ok(previous) => replaced.push((target, previous)),
+                Err(e) => {
+                    for (target, previous) in replaced.into_iter().rev() {
+                        remove_path(target);
+                        if let Some(previous) = previous {
+                            let _ = std::fs::rename(previous, target);
+                        }
+                    }
+                    for (_, staged) in &entries {
+                        remove_path(staged);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        for (_, previous) in replaced {
+            if let Some(previous) = previous {
+                remove_path(&previous);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        for (_, staged) in &self.entries {
+            remove_path(staged);
+        }
+    }
+}
+
+/// Move `staged` to `target`, returning where the old target was set aside
+fn swap_in(target: &Path, staged: &Path) -> Result<Option<PathBuf>> {
+    let previous = if target.exists() {
+        let aside = sibling(target, "pre-import");
+        remove_path(&aside);
+        std::fs::rename(target, &aside)
+            .with_context(|| format!("Failed to move {} aside", target.display()))?;
+        Some(aside)
+    } else {
+        None
+    };
+    if let Err(e) = std::fs::rename(staged, target) {
+        if let Some(aside) = &previous {
+            let _ = std::fs::rename(aside, target);
+        }
+        return Err(anyhow!("Failed to replace {}: {}", target.display(), e));
+    }
+    Ok(previous)
+}
+
+/// `path` with `.suffix` appended to its file name
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", suffix));
+    path.with_file_name(name)
+}
+
+fn remove_path(path: &Path) {
+    let _ = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+}
+
+/// Write `snapshot` as a zip at `path`. The archive is built next to the
+/// target and renamed into place, so a failed export never leaves a
+/// truncated file behind.
+pub fn write_archive(path: &Path, snapshot: &StateSnapshot) -> Result<SnapshotReport> {
+    let tmp_path = path.with_extension(format!("{}.tmp", uuid::Uuid::new_v4()));
+    let written = write_entries(&tmp_path, snapshot).and_then(|_| {
+        std::fs::rename(&tmp_path, path)
+            .with_context(|| format!("Failed to move snapshot to {}", path.display()))
+    });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    written.map(|_| SnapshotReport::new(path, snapshot))
+}
+
+fn write_entries(path: &Path, snapshot: &StateSnapshot) -> Result<()> {
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+
+    let mut add = |name: &str, json: String| -> Result<()> {
+        zip.start_file(name, options)?;
+        zip.write_all(json.as_bytes())?;
+        Ok(())
+    };
+    add(MANIFEST_FILE, serde_json::to_string_pretty(&snapshot.manifest)?)?;
+    let mut config = snapshot.config.clone();
+    config.clear_secrets();
+    add(CONFIG_FILE, serde_json::to_string_pretty(&config)?)?;
+    add(LIFE_TREE_FILE, serde_json::to_string_pretty(&snapshot.life_tree)?)?;
+    add(ACTIVITY_LOG_FILE, serde_json::to_string_pretty(&snapshot.activity_log)?)?;
+    add(ADAPTIVE_REFRESH_FILE, serde_json::to_string_pretty(&snapshot.adaptive_refresh)?)?;
+    add(MODEL_MANIFEST_FILE, serde_json::to_string_pretty(&snapshot.models)?)?;
+    for conversation in &snapshot.conversations {
+        add(
+            &format!("{}{}.json", CONVERSATIONS_DIR, conversation.id),
+            serde_json::to_string_pretty(conversation)?,
+        )?;
+    }
+
+    zip.finish()?.sync_all()?;
+    Ok(())
+}
+
+/// Read and validate a snapshot archive. Fails on a missing or unparsable
+/// file, or an archive written with a different [`SNAPSHOT_VERSION`].
+pub fn read_archive(path: &Path) -> Result<StateSnapshot> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut zip = zip::ZipArchive::new(file)
+        .with_context(|| format!("{} is not a snapshot archive", path.display()))?;
+
+    let manifest: SnapshotManifest = parse_entry(&mut zip, MANIFEST_FILE)?;
+    if manifest.version != SNAPSHOT_VERSION {
+        return Err(anyhow!(
+            "Snapshot version {} (Hawkeye {}) is incompatible with this build, which reads version {}",
+            manifest.version,
+            manifest.app_version,
+            SNAPSHOT_VERSION
+        ));
+    }
+
+    let conversation_files: Vec<String> = zip
+        .file_names()
+        .filter(|name| name.starts_with(CONVERSATIONS_DIR) && name.ends_with(".json"))
+        .map(str::to_string)
+        .collect();
+    let mut conversations = Vec::with_capacity(conversation_files.len());
+    for name in conversation_files {
+        conversations.push(parse_entry::<Conversation>(&mut zip, &name)?);
+    }
+
+    let life_tree: LifeTreeSnapshot = parse_entry(&mut zip, LIFE_TREE_FILE)?;
+    check_life_tree(&life_tree)?;
+
+    Ok(StateSnapshot {
+        config: parse_entry(&mut zip, CONFIG_FILE)?,
+        life_tree,
+        activity_log: parse_entry(&mut zip, ACTIVITY_LOG_FILE)?,
+        adaptive_refresh: parse_entry(&mut zip, ADAPTIVE_REFRESH_FILE)?,
+        models: parse_entry(&mut zip, MODEL_MANIFEST_FILE)?,
+        conversations,
+        manifest,
+    })
+}
+
+fn parse_entry<T: serde::de::DeserializeOwned>(
+    zip: &mut zip::ZipArchive<std::fs::File>,
+    name: &str,
+) -> Result<T> {
+    let mut entry = zip
+        .by_name(name)
+        .map_err(|_| anyhow!("Snapshot is missing {}", name))?;
+    let mut json = String::new();
+    entry.read_to_string(&mut json)?;
+    serde_json::from_str(&json).with_context(|| format!("Snapshot has an invalid {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::life_tree::LifeTree;
+    use crate::observe::activity_log::ActivityEntry;
+
+    fn snapshot(dir: &Path) -> StateSnapshot {
+        StateSnapshot {
+            manifest: SnapshotManifest {
+                version: SNAPSHOT_VERSION,
+                app_version: "0.1.0".to_string(),
+                created_at: 42,
+            },
+            config: AppConfig {
+                ai_provider: "openai".to_string(),
+                openai_api_key: Some("sk-secret".to_string()),
+                hf_token: Some("hf_secret".to_string()),
+                ..AppConfig::default()
+            },
+            life_tree: LifeTree::with_data_path(dir.join("life_tree.json")).snapshot(),
+            activity_log: ActivityLogState {
+                entries: vec![ActivityEntry {
+                    timestamp: 1,
+                    end_timestamp: 2,
+                    app_name: Some("Code".to_string()),
+                    window_title: None,
+                    ocr_snippet: None,
+                    change_ratio: 0.5,
+                }],
+                summaries: Vec::new(),
+            },
+            adaptive_refresh: AdaptiveRefreshState {
+                enabled: true,
+                activity_score: 80.0,
+            },
+            models: Vec::new(),
+            conversations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn archive_round_trips_and_rejects_other_versions() {
+        let dir = std::env::temp_dir().join(format!("hawkeye-snapshot-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.zip");
+
+        let report = write_archive(&path, &snapshot(&dir)).unwrap();
+        assert_eq!(report.activity_entries, 1);
+        let read = read_archive(&path).unwrap();
+        assert_eq!(read.config.ai_provider, "openai");
+        assert_eq!(read.config.openai_api_key, None);
+        assert_eq!(read.config.hf_token, None);
+        assert_eq!(read.activity_log.entries[0].app_name.as_deref(), Some("Code"));
+        assert_eq!(read.life_tree.nodes.len(), report.life_tree_nodes);
+
+        let mut newer = snapshot(&dir);
+        newer.manifest.version = SNAPSHOT_VERSION + 1;
+        write_archive(&path, &newer).unwrap();
+        let err = read_archive(&path).unwrap_err().to_string();
+        assert!(err.contains("incompatible"), "{}", err);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn staged_files_swap_in_together_or_not_at_all() {
+        let dir = std::env::temp_dir().join(format!("hawkeye-staging-{}", uuid::Uuid::new_v4()));
+        let conversations = dir.join("conversations");
+        std::fs::create_dir_all(&conversations).unwrap();
+        std::fs::write(dir.join("config.json"), "old").unwrap();
+        std::fs::write(conversations.join("stale.json"), "{}").unwrap();
+        let listing = |path: &Path| {
+            let mut names: Vec<String> = std::fs::read_dir(path)
+                .unwrap()
+                .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+
+        // The second file's staged copy vanishes: the first swap is undone
+        let mut staging = Staging::default();
+        staging.file(dir.join("config.json"), "new").unwrap();
+        staging.file(dir.join("life_tree.json"), "{}").unwrap();
+        std::fs::remove_file(dir.join("life_tree.json.import")).unwrap();
+        assert!(staging.commit().is_err());
+        assert_eq!(std::fs::read_to_string(dir.join("config.json")).unwrap(), "old");
+        assert_eq!(listing(&dir), vec!["config.json", "conversations"]);
+
+        // Directories are replaced, not merged
+        let mut staging = Staging::default();
+        staging.file(dir.join("config.json"), "new").unwrap();
+        staging
+            .dir(conversations.clone(), &[("c1.json".to_string(), "{}".to_string())])
+            .unwrap();
+        staging.commit().unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("config.json")).unwrap(), "new");
+        assert_eq!(listing(&conversations), vec!["c1.json"]);
+        assert_eq!(listing(&dir), vec!["config.json", "conversations"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
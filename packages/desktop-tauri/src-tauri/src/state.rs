@@ -4,39 +4,90 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::agent::DaemonSupervisor;
+use crate::ai::conversations::ConversationStore;
+use crate::ai::diagnostics::AiDiagnostics;
+use crate::ai::rate_limit::RateLimiter;
 use crate::ai::AiProvider;
 use crate::commands::debug_cmd::DebugTimeline;
 use crate::commands::gesture_cmd::GestureConfig;
 use crate::config::AppConfig;
+use crate::error::HawkeyeError;
 use crate::gaze::data_buffer::GazeDataBuffer;
 use crate::gaze::inference::GazeModel;
 use crate::life_tree::LifeTree;
 use crate::models::ModelManager;
-use crate::observe::{ActivityLog, AdaptiveRefresh, IntentRecognizer, ObserveLoop};
+use crate::perception::clipboard::ClipboardReader;
+use crate::perception::ocr::{OcrFilter, OcrOptions};
+use crate::observe::focus::FocusSession;
+use crate::observe::recent_frames::RecentFrames;
+use crate::observe::{
+    ActivityLog, AdaptiveRefresh, ChangeStats, IntentRecognizer, ObserveLoop, ObserveParams,
+};
 use crate::training::TrainingCollector;
 
 /// Observation result emitted by the observe loop
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ObservationResult {
+    /// Full-resolution PNG. Stripped from `observe:update` events; fetch it
+    /// on demand with `get_last_full_screenshot`.
     pub screenshot_base64: Option<String>,
+    /// Downscaled JPEG preview sent with every update
+    pub thumbnail_base64: Option<String>,
     pub ocr_text: Option<String>,
+    /// OCR errored or is unavailable, so `ocr_text` is missing rather than
+    /// empty and classification fell back to the window title alone
+    pub ocr_failed: bool,
+    /// Length of `ocr_text` in characters (0 without OCR text)
+    pub ocr_char_count: usize,
+    /// Whitespace-separated words in `ocr_text`
+    pub ocr_word_count: usize,
     pub active_window: Option<crate::commands::perception_cmd::WindowInfoResponse>,
     pub change_ratio: f64,
+    /// Perceptual hash of the frame as 16 hex digits; compare two frames by
+    /// the Hamming distance of their hashes (see `change_detector`)
+    pub phash: String,
     pub timestamp: u64,
 }
 
 /// Shared application state
+///
+/// # Lock ordering
+///
+/// The observe loop, commands and background tasks all take these locks, so
+/// when more than one is held at a time they must be acquired in this order:
+///
+/// 1. `observe_loop`
+/// 2. `ai_client`
+/// 3. `config`
+/// 4. `model_manager`
+///
+/// Every other field is a leaf lock: hold it only for its own update and
+/// never acquire another lock while holding it.
 pub struct AppState {
     /// Active AI provider. `Arc` so commands and the agent runner can share
     /// it without holding the lock for the duration of an HTTP request.
     pub ai_client: RwLock<Option<Arc<dyn AiProvider>>>,
+    /// Shared per-provider request budget; see `ai::rate_limit::acquire`
+    pub ai_rate_limiter: RwLock<RateLimiter>,
+    /// Latency and last error per provider, recorded by `chat`
+    pub ai_diagnostics: RwLock<AiDiagnostics>,
+    /// Persisted chat history; write-locked while an exchange is appended
+    pub conversations: RwLock<ConversationStore>,
     pub config: RwLock<AppConfig>,
     pub observe_loop: RwLock<Option<ObserveLoop>>,
     pub last_observation: RwLock<Option<ObservationResult>>,
+    /// Live-tunable observe threshold and stats toggle
+    pub observe_params: RwLock<ObserveParams>,
+    /// Recent change ratios, filled only while `record_stats` is on
+    pub change_stats: RwLock<ChangeStats>,
     pub adaptive_refresh: RwLock<AdaptiveRefresh>,
     pub activity_log: RwLock<ActivityLog>,
+    /// On-disk ring of recently recorded frames, for debugging
+    pub recent_frames: RwLock<RecentFrames>,
     pub intent_recognizer: RwLock<IntentRecognizer>,
+    /// Running focus session, checked against every recorded frame
+    pub focus_session: RwLock<Option<FocusSession>>,
     pub model_manager: RwLock<ModelManager>,
     pub life_tree: RwLock<LifeTree>,
     pub gesture_config: RwLock<GestureConfig>,
@@ -52,9 +103,56 @@ pub struct AppState {
     /// `TauriSink`; left as `None` for headless tests / CLI sub-commands
     /// that build sinks ad-hoc.
     pub event_sink: RwLock<Option<crate::event_sink::SharedSink>>,
+    /// Clipboard reader installed by the host; `None` when headless
+    pub clipboard_reader: RwLock<Option<ClipboardReader>>,
+    /// Whether the OCR helper was found by `perception::init`
+    pub ocr_available: RwLock<bool>,
+    /// `ocr_stopwords` and `ocr_filter_patterns` compiled once; rebuilt
+    /// wherever the config is replaced rather than on every OCR call
+    pub ocr_filter: RwLock<OcrFilter>,
+    /// Privacy master switch: when off, nothing captures the screen — not
+    /// the observe loop, capture commands or gestures. Independent of
+    /// whether the loop is running.
+    pub observation_enabled: RwLock<bool>,
+    /// A summary was requested before any AI provider was configured; it is
+    /// generated once `init_ai` succeeds
+    pub deferred_summary: RwLock<bool>,
 }
 
 impl AppState {
+    /// The active AI provider, cloned out so the caller never holds
+    /// `ai_client` across a request
+    pub async fn ai_provider(&self) -> Result<Arc<dyn AiProvider>, HawkeyeError> {
+        self.ai_client
+            .read()
+            .await
+            .as_ref()
+            .cloned()
+            .ok_or(HawkeyeError::AiNotInitialized)
+    }
+
+    /// Whether an AI provider has been initialized
+    pub async fn ai_ready(&self) -> bool {
+        self.ai_client.read().await.is_some()
+    }
+
+    /// OCR settings from the config, with the cached filter
+    pub async fn ocr_options(&self) -> OcrOptions {
+        let filter = self.ocr_filter.read().await.clone();
+        OcrOptions::with_filter(&*self.config.read().await, filter)
+    }
+
+    /// Fail with `permission_denied` when the user has turned capture off
+    pub async fn ensure_capture_enabled(&self) -> Result<(), HawkeyeError> {
+        if *self.observation_enabled.read().await {
+            Ok(())
+        } else {
+            Err(HawkeyeError::PermissionDenied(
+                "Screen capture disabled by user".to_string(),
+            ))
+        }
+    }
+
     fn load_gaze_buffer() -> GazeDataBuffer {
         GazeDataBuffer::default_path()
             .and_then(|p| GazeDataBuffer::load(&p).ok())
@@ -66,16 +164,31 @@ impl AppState {
     }
 
     pub fn new(config: AppConfig) -> Arc<Self> {
+        crate::paths::set_data_dir_override(config.data_dir_override.clone());
+
+        let mut life_tree = LifeTree::default();
+        life_tree.set_phase_thresholds(config.experiment_phase_thresholds);
+        let model_manager = ModelManager::with_dir(config.models_dir_override.clone());
+        let observation_enabled = config.observation_enabled;
+        let ocr_filter = OcrFilter::from_config(&config);
+
         Arc::new(Self {
             ai_client: RwLock::new(None),
+            ai_rate_limiter: RwLock::new(RateLimiter::default()),
+            ai_diagnostics: RwLock::new(AiDiagnostics::default()),
+            conversations: RwLock::new(ConversationStore::default()),
             config: RwLock::new(config),
             observe_loop: RwLock::new(None),
             last_observation: RwLock::new(None),
+            observe_params: RwLock::new(ObserveParams::default()),
+            change_stats: RwLock::new(ChangeStats::default()),
             adaptive_refresh: RwLock::new(AdaptiveRefresh::default()),
             activity_log: RwLock::new(ActivityLog::default()),
+            recent_frames: RwLock::new(RecentFrames::default()),
             intent_recognizer: RwLock::new(IntentRecognizer::default()),
-            model_manager: RwLock::new(ModelManager::default()),
-            life_tree: RwLock::new(LifeTree::default()),
+            focus_session: RwLock::new(None),
+            model_manager: RwLock::new(model_manager),
+            life_tree: RwLock::new(life_tree),
             gesture_config: RwLock::new(GestureConfig::default()),
             debug_timeline: RwLock::new(DebugTimeline::default()),
             gaze_buffer: RwLock::new(Self::load_gaze_buffer()),
@@ -84,6 +197,213 @@ impl AppState {
             training_collector: RwLock::new(TrainingCollector::default()),
             agent_supervisor: RwLock::new(None),
             event_sink: RwLock::new(None),
+            clipboard_reader: RwLock::new(None),
+            ocr_available: RwLock::new(true),
+            ocr_filter: RwLock::new(ocr_filter),
+            observation_enabled: RwLock::new(observation_enabled),
+            deferred_summary: RwLock::new(false),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::sync::OnceLock;
+    use std::time::Duration;
+    use tokio::sync::{Mutex, OwnedMutexGuard};
+
+    /// A test's private data dir. `AppState::new` points the process-wide
+    /// data dir override at it, so tests building a state take turns: the
+    /// turn is held until this is dropped, which also removes the dir.
+    struct TestDataDir {
+        dir: PathBuf,
+        _turn: OwnedMutexGuard<()>,
+    }
+
+    impl Drop for TestDataDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    /// `AppState::new(config)` with data in a fresh temp dir
+    async fn test_state(config: AppConfig) -> (Arc<AppState>, TestDataDir) {
+        static TURN: OnceLock<Arc<Mutex<()>>> = OnceLock::new();
+        let turn = TURN.get_or_init(Arc::default).clone().lock_owned().await;
+
+        let dir = std::env::temp_dir().join(format!("hawkeye-state-{}", uuid::Uuid::new_v4()));
+        let state = AppState::new(AppConfig {
+            data_dir_override: Some(dir.clone()),
+            ..config
+        });
+        (state, TestDataDir { dir, _turn: turn })
+    }
+
+    fn frame(timestamp: u64) -> ObservationResult {
+        ObservationResult {
+            screenshot_base64: None,
+            thumbnail_base64: None,
+            ocr_text: Some("fn main() {}".to_string()),
+            ocr_failed: false,
+            ocr_char_count: 12,
+            ocr_word_count: 3,
+            active_window: Some(crate::commands::perception_cmd::WindowInfoResponse {
+                app_name: "Code".to_string(),
+                title: "main.rs".to_string(),
+                bundle_id: None,
+            }),
+            change_ratio: 0.5,
+            phash: "00000000ffffffff".to_string(),
+            timestamp,
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_provider_reports_ai_not_initialized() {
+        let (state, _data) = test_state(AppConfig::default()).await;
+
+        assert!(!state.ai_ready().await);
+        assert!(matches!(state.ai_provider().await, Err(HawkeyeError::AiNotInitialized)));
+    }
+
+    #[tokio::test]
+    async fn capture_switch_refuses_when_off() {
+        let (state, _data) = test_state(AppConfig::default()).await;
+
+        assert!(state.ensure_capture_enabled().await.is_ok());
+        *state.observation_enabled.write().await = false;
+        let err = state.ensure_capture_enabled().await.unwrap_err();
+        assert_eq!(err.code(), "permission_denied");
+        assert_eq!(err.to_string(), "Screen capture disabled by user");
+    }
+
+    #[tokio::test]
+    async fn capture_switch_is_restored_from_config() {
+        let (state, _data) = test_state(AppConfig {
+            observation_enabled: false,
+            ..AppConfig::default()
+        })
+        .await;
+
+        assert!(state.ensure_capture_enabled().await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_starts_run_a_single_loop() {
+        use crate::event_sink::{NoopSink, SharedSink};
+        use crate::observe::loop_runner::running_loops;
+
+        let (state, _data) = test_state(AppConfig::default()).await;
+
+        let starts: Vec<_> = (0..2)
+            .map(|_| {
+                let s = state.clone();
+                tokio::spawn(async move {
+                    let sink: SharedSink = Arc::new(NoopSink);
+                    ObserveLoop::start_if_idle(&s, sink).await
+                })
+            })
+            .collect();
+        let mut started = 0;
+        for start in starts {
+            if start.await.unwrap() {
+                started += 1;
+            }
+        }
+        assert_eq!(started, 1);
+
+        // The loop sleeps before its first capture, so it is stopped here
+        // without ever touching the screen
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(running_loops(), 1);
+
+        state.observe_loop.write().await.take().unwrap().stop();
+        for _ in 0..100 {
+            if running_loops() == 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(running_loops(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn loop_and_commands_do_not_deadlock() {
+        let (state, _data) = test_state(AppConfig::default()).await;
+
+        let mut tasks = Vec::new();
+
+        // Observe loop: the per-frame lock pattern from `loop_runner`
+        let s = state.clone();
+        tasks.push(tokio::spawn(async move {
+            for i in 0..200 {
+                let observation = frame(i);
+                let generation = s.recent_frames.read().await.generation();
+                crate::observe::loop_runner::record_frame(&s, &observation, generation).await;
+                let input = crate::observe::intent::RecognitionInput {
+                    app_name: Some("Code".to_string()),
+                    window_title: Some("main.rs".to_string()),
+                    ocr_text: observation.ocr_text.clone(),
+                    clipboard: None,
+                };
+                s.intent_recognizer.write().await.recognize(&input);
+                s.adaptive_refresh.write().await.record_activity(
+                    crate::observe::adaptive_refresh::ActivityEventType::ScreenChange,
+                );
+                tokio::task::yield_now().await;
+            }
+        }));
+
+        // Commands that nest locks in the documented order
+        let s = state.clone();
+        tasks.push(tokio::spawn(async move {
+            for _ in 0..200 {
+                let _ai = s.ai_client.read().await;
+                let _config = s.config.read().await;
+                tokio::task::yield_now().await;
+            }
+        }));
+        // Leaf locks, each held on its own across a yield so it contends
+        // with the loop
+        let s = state.clone();
+        tasks.push(tokio::spawn(async move {
+            for _ in 0..200 {
+                {
+                    let _observation = s.last_observation.read().await;
+                    tokio::task::yield_now().await;
+                }
+                {
+                    let _intent = s.intent_recognizer.write().await;
+                    tokio::task::yield_now().await;
+                }
+                {
+                    let _activity = s.activity_log.write().await;
+                    tokio::task::yield_now().await;
+                }
+                {
+                    let _tree = s.life_tree.read().await;
+                    tokio::task::yield_now().await;
+                }
+                let _observe_loop = s.observe_loop.read().await;
+                tokio::task::yield_now().await;
+            }
+        }));
+        let s = state.clone();
+        tasks.push(tokio::spawn(async move {
+            for _ in 0..200 {
+                s.config.write().await.gemini_model = Some("gemini-2.5-flash".to_string());
+                tokio::task::yield_now().await;
+            }
+        }));
+
+        let joined = tokio::time::timeout(Duration::from_secs(10), async {
+            for task in tasks {
+                task.await.unwrap();
+            }
         })
+        .await;
+        assert!(joined.is_ok(), "observe loop and commands deadlocked");
     }
 }
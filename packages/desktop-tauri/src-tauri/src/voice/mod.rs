@@ -1,5 +1,6 @@
 //! Voice module — speech recognition and TTS via macOS native APIs
 
+pub mod notes;
 pub mod speech;
 
 pub use speech::{SpeechResult, SpeechStatus};
@@ -0,0 +1,242 @@
+//! Voice notes — long-form transcription for meeting-style summaries
+//!
+//! Recordings are split into short WAV chunks before transcription: the
+//! native recognizer stops after about a minute of audio, and shorter
+//! chunks keep whisper.cpp memory flat.
+
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+
+use super::speech;
+use crate::models::manager::LocalModel;
+use crate::models::ModelType;
+
+/// Maximum audio length per transcription request
+pub const CHUNK_SECS: u32 = 50;
+
+/// whisper.cpp CLI names, newest first
+const WHISPER_BINARIES: [&str; 2] = ["whisper-cli", "whisper-cpp"];
+
+/// Speech-to-text backend
+#[derive(Debug, Clone)]
+pub enum Transcriber {
+    /// SFSpeechRecognizer via the Swift helper
+    Native,
+    /// whisper.cpp CLI with a downloaded ggml model
+    Whisper { binary: PathBuf, model: PathBuf },
+}
+
+impl Transcriber {
+    /// Whisper when both a whisper.cpp binary and a downloaded Whisper model
+    /// are present, otherwise the native recognizer
+    pub fn detect(models: &[LocalModel]) -> Self {
+        let model = models
+            .iter()
+            .find(|m| m.model_type == ModelType::Whisper)
+            .map(|m| PathBuf::from(&m.path));
+        let binary = WHISPER_BINARIES.iter().find_map(|name| find_in_path(name));
+
+        match (binary, model) {
+            (Some(binary), Some(model)) => Self::Whisper { binary, model },
+            _ => Self::Native,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Native => "native",
+            Self::Whisper { .. } => "whisper",
+        }
+    }
+
+    async fn transcribe(&self, audio: &Path) -> Result<String> {
+        match self {
+            Self::Native => {
+                let result = speech::transcribe_file(&audio.to_string_lossy()).await?;
+                Ok(result.text)
+            }
+            Self::Whisper { binary, model } => {
+                let output = tokio::process::Command::new(binary)
+                    .arg("-m")
+                    .arg(model)
+                    .arg("-f")
+                    .arg(audio)
+                    .args(["--no-timestamps", "--no-prints"])
+                    .output()
+                    .await
+                    .map_err(|e| anyhow!("Failed to run {}: {}", binary.display(), e))?;
+
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    return Err(anyhow!("Whisper transcription failed: {}", stderr));
+                }
+                Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+            }
+        }
+    }
+}
+
+/// Transcript of a whole recording
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    pub text: String,
+    pub chunks: usize,
+}
+
+/// Transcribe `audio` chunk by chunk and join the pieces
+pub async fn transcribe_long(transcriber: &Transcriber, audio: &Path) -> Result<Transcript> {
+    let work_dir = TempDir::new()?;
+    let chunks = split_wav(audio, CHUNK_SECS, &work_dir.0)?;
+
+    let mut pieces = Vec::with_capacity(chunks.len());
+    for chunk in &chunks {
+        let text = transcriber.transcribe(chunk).await?;
+        if !text.trim().is_empty() {
+            pieces.push(text.trim().to_string());
+        }
+    }
+
+    Ok(Transcript {
+        text: pieces.join(" "),
+        chunks: chunks.len(),
+    })
+}
+
+/// Split a WAV file into pieces of at most `chunk_secs` inside `out_dir`.
+/// Non-WAV input and clips that already fit come back as the original path.
+pub fn split_wav(audio: &Path, chunk_secs: u32, out_dir: &Path) -> Result<Vec<PathBuf>> {
+    let Ok(mut reader) = hound::WavReader::open(audio) else {
+        return Ok(vec![audio.to_path_buf()]);
+    };
+
+    let spec = reader.spec();
+    let frames_per_chunk = (spec.sample_rate * chunk_secs) as usize;
+    if reader.duration() as usize <= frames_per_chunk {
+        return Ok(vec![audio.to_path_buf()]);
+    }
+
+    let samples_per_chunk = frames_per_chunk * spec.channels as usize;
+
+    // Read samples in the file's own format; writing them back with the
+    // same spec keeps integer and float WAVs unchanged
+    match spec.sample_format {
+        hound::SampleFormat::Int => {
+            write_chunks(reader.samples::<i32>(), spec, samples_per_chunk, out_dir)
+        }
+        hound::SampleFormat::Float => {
+            write_chunks(reader.samples::<f32>(), spec, samples_per_chunk, out_dir)
+        }
+    }
+}
+
+/// Write `samples` to `out_dir` as consecutive WAV files of
+/// `samples_per_chunk` samples each
+fn write_chunks<S: hound::Sample>(
+    samples: impl Iterator<Item = hound::Result<S>>,
+    spec: hound::WavSpec,
+    samples_per_chunk: usize,
+    out_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut chunks = Vec::new();
+    let mut writer: Option<hound::WavWriter<_>> = None;
+    let mut written = 0usize;
+
+    for sample in samples {
+        let sample = sample.map_err(|e| anyhow!("Failed to read audio: {}", e))?;
+        if writer.is_none() || written == samples_per_chunk {
+            if let Some(w) = writer.take() {
+                w.finalize()?;
+            }
+            let path = out_dir.join(format!("chunk-{:04}.wav", chunks.len()));
+            writer = Some(hound::WavWriter::create(&path, spec)?);
+            chunks.push(path);
+            written = 0;
+        }
+        if let Some(w) = writer.as_mut() {
+            w.write_sample(sample)?;
+        }
+        written += 1;
+    }
+    if let Some(w) = writer {
+        w.finalize()?;
+    }
+
+    Ok(chunks)
+}
+
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Scratch directory removed on drop
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("hawkeye-voice-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self(dir))
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_wav_cuts_into_chunk_sized_files() {
+        let dir = TempDir::new().unwrap();
+        let input = dir.0.join("input.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&input, spec).unwrap();
+        for i in 0..250 {
+            writer.write_sample(i as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let chunks = split_wav(&input, 1, &dir.0).unwrap();
+
+        assert_eq!(chunks.len(), 3);
+        let last = hound::WavReader::open(&chunks[2]).unwrap();
+        assert_eq!(last.duration(), 50);
+    }
+
+    #[test]
+    fn split_wav_keeps_float_samples() {
+        let dir = TempDir::new().unwrap();
+        let input = dir.0.join("input.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 100,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&input, spec).unwrap();
+        for i in 0..150 {
+            writer.write_sample(i as f32 / 150.0).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let chunks = split_wav(&input, 1, &dir.0).unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        let mut last = hound::WavReader::open(&chunks[1]).unwrap();
+        assert_eq!(last.spec(), spec);
+        let first: f32 = last.samples::<f32>().next().unwrap().unwrap();
+        assert_eq!(first, 100.0 / 150.0);
+    }
+}
@@ -6,7 +6,7 @@ import AppKit
 #endif
 
 /// Hawkeye OCR CLI — uses macOS Vision API for text recognition
-/// Usage: hawkeye-ocr <image-path>
+/// Usage: hawkeye-ocr <image-path> [--languages en-US,zh-Hans] [--level fast|accurate]
 /// Output: JSON array of recognized text regions
 
 struct OcrRegion: Codable {
@@ -22,7 +22,7 @@ struct BoundingBox: Codable {
     let height: Double
 }
 
-func runOcr(imagePath: String) -> [OcrRegion] {
+func runOcr(imagePath: String, languages: [String], level: VNRequestTextRecognitionLevel) -> [OcrRegion] {
     guard let imageUrl = URL(string: "file://\(imagePath)") ?? URL(fileURLWithPath: imagePath) as URL?,
           let imageSource = CGImageSourceCreateWithURL(imageUrl as CFURL, nil),
           let cgImage = CGImageSourceCreateImageAtIndex(imageSource, 0, nil) else {
@@ -65,18 +65,19 @@ func runOcr(imagePath: String) -> [OcrRegion] {
         }
     }
 
-    // Configure for accuracy + multiple languages
-    request.recognitionLevel = .accurate
+    request.recognitionLevel = level
     request.usesLanguageCorrection = true
 
-    // Support multiple languages
+    // Explicit hints win over automatic detection, which misreads mixed CJK/Latin screens
     if #available(macOS 13.0, *) {
-        request.automaticallyDetectsLanguage = true
+        request.automaticallyDetectsLanguage = languages.isEmpty
     }
 
-    let supportedLanguages = ["en-US", "zh-Hans", "zh-Hant", "ja", "ko", "de", "fr", "es", "pt", "it"]
+    let supportedLanguages = languages.isEmpty
+        ? ["en-US", "zh-Hans", "zh-Hant", "ja", "ko", "de", "fr", "es", "pt", "it"]
+        : languages
     let revision = VNRecognizeTextRequest.currentRevision
-    if let available = try? VNRecognizeTextRequest.supportedRecognitionLanguages(for: .accurate, revision: revision) {
+    if let available = try? VNRecognizeTextRequest.supportedRecognitionLanguages(for: level, revision: revision) {
         let filtered = supportedLanguages.filter { lang in
             available.contains(where: { $0.hasPrefix(lang.prefix(2)) })
         }
@@ -99,19 +100,38 @@ func runOcr(imagePath: String) -> [OcrRegion] {
 }
 
 // Main
-guard CommandLine.arguments.count >= 2 else {
-    fputs("Usage: hawkeye-ocr <image-path>\n", stderr)
-    exit(1)
+let usage = "Usage: hawkeye-ocr <image-path> [--languages en-US,zh-Hans] [--level fast|accurate]\n"
+
+var imagePathArg: String?
+var languages: [String] = []
+var level: VNRequestTextRecognitionLevel = .accurate
+
+var args = CommandLine.arguments.dropFirst().makeIterator()
+while let arg = args.next() {
+    switch arg {
+    case "--languages":
+        languages = (args.next() ?? "")
+            .split(separator: ",")
+            .map { $0.trimmingCharacters(in: .whitespaces) }
+            .filter { !$0.isEmpty }
+    case "--level":
+        level = args.next() == "fast" ? .fast : .accurate
+    default:
+        imagePathArg = arg
+    }
 }
 
-let imagePath = CommandLine.arguments[1]
+guard let imagePath = imagePathArg else {
+    fputs(usage, stderr)
+    exit(1)
+}
 
 guard FileManager.default.fileExists(atPath: imagePath) else {
     fputs("Error: File not found: \(imagePath)\n", stderr)
     exit(1)
 }
 
-let regions = runOcr(imagePath: imagePath)
+let regions = runOcr(imagePath: imagePath, languages: languages, level: level)
 
 let encoder = JSONEncoder()
 encoder.outputFormatting = .sortedKeys
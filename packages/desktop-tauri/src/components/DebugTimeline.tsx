@@ -116,7 +116,17 @@ export const DebugTimeline: React.FC = () => {
     let unlistenFn: (() => void) | undefined;
 
     listen<DebugEvent>('debug:event', (event) => {
-      setEvents((prev) => [...prev, event.payload].slice(-500));
+      setEvents((prev) => {
+        // A re-sent event (e.g. an observe cycle gaining its duration)
+        // replaces the earlier copy
+        const index = prev.findIndex((e) => e.id === event.payload.id);
+        if (index >= 0) {
+          const next = [...prev];
+          next[index] = event.payload;
+          return next;
+        }
+        return [...prev, event.payload].slice(-500);
+      });
       if (event.payload.timestamp > lastTimestampRef.current) {
         lastTimestampRef.current = event.payload.timestamp;
       }
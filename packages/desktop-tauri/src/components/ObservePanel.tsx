@@ -1,6 +1,11 @@
 import { useState, useCallback } from 'react';
 import { motion } from 'framer-motion';
-import { startObserve, stopObserve, type ObservationResult } from '../hooks/useTauri';
+import {
+  startObserve,
+  stopObserve,
+  type ObservationResult,
+  type ObserveChange,
+} from '../hooks/useTauri';
 import { useTauriEvent } from '../hooks/useEvents';
 
 export function ObservePanel() {
@@ -13,7 +18,7 @@ export function ObservePanel() {
     setObservations((prev) => [obs, ...prev].slice(0, 20)); // Keep last 20
   }, []);
 
-  const handleChange = useCallback((_ratio: number) => {
+  const handleChange = useCallback((_change: ObserveChange) => {
     setChangeCount((c) => c + 1);
   }, []);
 
@@ -105,6 +110,9 @@ export function ObservePanel() {
                 {obs.ocrText.length > 200 && '...'}
               </pre>
             )}
+            {obs.ocrFailed && (
+              <div className="text-xs text-hawkeye-text-muted italic">OCR unavailable</div>
+            )}
           </motion.div>
         ))}
       </div>
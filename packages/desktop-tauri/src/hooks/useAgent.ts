@@ -4,6 +4,7 @@ import {
   chatWithAgent,
   getAgentStatus,
   startAgent,
+  errorMessage,
   type AgentStatus,
   type AgentTurnResult,
   type ChatMessage,
@@ -97,7 +98,7 @@ export function useAgent() {
       setState((st) => ({ ...st, status: s }));
       return s;
     } catch (e) {
-      setState((st) => ({ ...st, error: String(e) }));
+      setState((st) => ({ ...st, error: errorMessage(e) }));
       return null;
     }
   }, []);
@@ -113,7 +114,7 @@ export function useAgent() {
       await refreshStatus();
       return true;
     } catch (e) {
-      const msg = String(e);
+      const msg = errorMessage(e);
       setState((s) => ({ ...s, error: msg }));
       return false;
     }
@@ -127,7 +128,7 @@ export function useAgent() {
         setState((s) => ({ ...s, isLoading: false, lastResult: result }));
         return result;
       } catch (e) {
-        setState((s) => ({ ...s, isLoading: false, error: String(e) }));
+        setState((s) => ({ ...s, isLoading: false, error: errorMessage(e) }));
         return null;
       }
     },
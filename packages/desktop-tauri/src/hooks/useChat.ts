@@ -1,5 +1,11 @@
 import { useState, useCallback, useRef } from 'react';
-import { chat as chatInvoke, initAi, type ChatMessage, type ChatResponse } from './useTauri';
+import {
+  chat as chatInvoke,
+  errorMessage,
+  initAi,
+  type ChatMessage,
+  type ChatResponse,
+} from './useTauri';
 
 interface ChatState {
   messages: ChatMessage[];
@@ -26,7 +32,7 @@ export function useChat() {
       setState((s) => ({ ...s, aiReady: ready }));
       return ready;
     } catch (e) {
-      setState((s) => ({ ...s, error: String(e) }));
+      setState((s) => ({ ...s, error: errorMessage(e) }));
       return false;
     }
   }, []);
@@ -56,7 +62,7 @@ export function useChat() {
       setState((s) => ({
         ...s,
         isLoading: false,
-        error: String(e),
+        error: errorMessage(e),
       }));
     }
   }, []);
@@ -6,6 +6,7 @@ import {
   startExperiment,
   concludeExperiment,
   getUnlockedPhase,
+  errorMessage,
   type LifeTreeSnapshot,
   type LifeTreeNode,
   type LifeStage,
@@ -41,7 +42,7 @@ export function useLifeTree() {
         setSnapshot(tree);
         setUnlockedPhase(phase);
       } catch (e) {
-        setError(e instanceof Error ? e.message : String(e));
+        setError(errorMessage(e));
       } finally {
         setLoading(false);
       }
@@ -72,7 +73,7 @@ export function useLifeTree() {
       const result = await proposeExperiment(nodeId);
       setProposal(result);
     } catch (e) {
-      setError(e instanceof Error ? e.message : String(e));
+      setError(errorMessage(e));
     } finally {
       setProposing(false);
     }
@@ -85,13 +86,13 @@ export function useLifeTree() {
     phase: ExperimentPhase,
   ) => {
     try {
-      await startExperiment(nodeId, title, description, phase);
+      await startExperiment(nodeId, title, description, phase, proposal?.durationDays);
       setProposal(null);
       await refreshTree();
     } catch (e) {
-      setError(e instanceof Error ? e.message : String(e));
+      setError(errorMessage(e));
     }
-  }, [refreshTree]);
+  }, [proposal, refreshTree]);
 
   const handleConcludeExperiment = useCallback(async (expId: string, succeeded: boolean) => {
     try {
@@ -100,7 +101,7 @@ export function useLifeTree() {
       setUnlockedPhase(phase);
       await refreshTree();
     } catch (e) {
-      setError(e instanceof Error ? e.message : String(e));
+      setError(errorMessage(e));
     }
   }, [refreshTree]);
 
@@ -123,7 +124,7 @@ export function useLifeTree() {
       setSnapshot(tree);
       setSelectedNodeId(null);
     } catch (e) {
-      setError(e instanceof Error ? e.message : String(e));
+      setError(errorMessage(e));
     } finally {
       setLoading(false);
     }
@@ -1,6 +1,31 @@
 import { invoke } from '@tauri-apps/api/core';
 
 // Types matching Rust backend
+/** Error rejected by every command; branch on `code`, show `message` */
+export type HawkeyeErrorCode =
+  | 'ai_not_initialized'
+  | 'network'
+  | 'rate_limited'
+  | 'cancelled'
+  | 'permission_denied'
+  | 'not_found'
+  | 'other';
+
+export interface HawkeyeError {
+  code: HawkeyeErrorCode;
+  message: string;
+}
+
+export function isHawkeyeError(e: unknown): e is HawkeyeError {
+  return typeof e === 'object' && e !== null && 'code' in e && 'message' in e;
+}
+
+/** Human-readable text for anything a command may reject with */
+export function errorMessage(e: unknown): string {
+  if (isHawkeyeError(e) || e instanceof Error) return e.message;
+  return String(e);
+}
+
 export interface ScreenshotResult {
   success: boolean;
   dataUrl?: string;
@@ -17,6 +42,41 @@ export interface OcrResult {
   error?: string;
 }
 
+/// Normalized 0-1 coordinates, origin at the bottom-left (Vision convention)
+export interface BoundingBox {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface OcrRegion {
+  text: string;
+  confidence: number;
+  bbox: BoundingBox;
+}
+
+export interface OcrRegionsResult {
+  success: boolean;
+  regions: OcrRegion[];
+  durationMs: number;
+  backend: string;
+  imageWidth: number;
+  imageHeight: number;
+  error?: string;
+}
+
+/** Full OCR output for a frame, as returned by `reocrLastObservation` */
+export interface OcrFrameResult {
+  text: string;
+  regions: OcrRegion[];
+  durationMs: number;
+  backend: string;
+  monitorIndex?: number;
+  imageWidth: number;
+  imageHeight: number;
+}
+
 export interface WindowInfo {
   appName: string;
   title: string;
@@ -31,18 +91,104 @@ export interface AppConfig {
   openaiBaseUrl?: string;
   openaiApiKey?: string;
   openaiModel?: string;
+  requestTimeoutSecs?: number;
+  httpProxy?: string;
+  /** Log AI request metadata (keys redacted) to the debug timeline */
+  logAiRequests?: boolean;
+  /** Sent as the system message ahead of every chat */
+  systemPrompt?: string;
+  /** Tone for chat, summaries, intents and experiments */
+  personality?: string;
+  /** Reply language for all AI features, e.g. "Chinese" */
+  language?: string;
+  historyTrimStrategy?: 'drop_oldest' | 'summarize_oldest';
+  aiRequestsPerMinute?: Record<string, number>;
+  hfToken?: string;
+  dataDirOverride?: string;
+  /** Where downloaded models live; change with `setModelsDir` (`saveConfig` ignores it) */
+  modelsDirOverride?: string;
   syncPort: number;
   autoStartSync: boolean;
   autoUpdate: boolean;
+  /** Start observing at launch */
+  autoStartObserve?: boolean;
+  /** Mirrors the OS login item; change it with `setLaunchAtLogin` */
+  launchAtLogin?: boolean;
+  /** Screen capture master switch; change it with `setCaptureEnabled` (`saveConfig` ignores it) */
+  observationEnabled?: boolean;
   localOnly: boolean;
   onboardingCompleted?: boolean;
+  experimentPhaseThresholds?: PhaseThresholds;
+  summaryMinEntries?: number;
+  summaryMaxEntries?: number;
+  aiIntentEnhancement?: boolean;
+  observeActiveWindowOnly?: boolean;
+  /** Drop changed frames whose app, title and OCR text match the last observation */
+  observeSkipDuplicates?: boolean;
+  /** Recorded frames kept on disk for debugging (default 20, 0 = off) */
+  recentFramesCount?: number;
+  /** Keep those frames when observing stops */
+  retainRecentFrames?: boolean;
+  /** Slow sampling while a video plays */
+  mediaSampling?: MediaSampling;
+  ocrLanguages?: string[];
+  ocrRecognitionLevel?: RecognitionLevel;
+  ocrStopwords?: string[];
+  /** Regexes removed from OCR text; `saveConfig` rejects invalid ones */
+  ocrFilterPatterns?: string[];
+  /** Seconds before a stuck OCR helper is killed (default 5) */
+  ocrTimeoutSecs?: number;
+  /** PNG compression for screenshots; `fast` is the default */
+  capturePngCompression?: 'fast' | 'default' | 'best';
+  /** JPEG quality 1–100 when a capture asks for JPEG (default 80) */
+  captureJpegQuality?: number;
+  excludedApps?: string[];
+  providerProfiles?: ProviderProfile[];
+  activeProfile?: string;
+  apiKeys?: Record<string, string>;
+}
+
+export interface ProviderProfile {
+  name: string;
+  provider: string;
+  baseUrl?: string;
+  model?: string;
+  /// Key name in AppConfig.apiKeys; omitted keeps the provider's current key
+  apiKeyRef?: string;
+}
+
+export type RecognitionLevel = 'fast' | 'accurate';
+
+/// Per-call OCR overrides; omitted fields fall back to the configured defaults.
+export interface OcrOptions {
+  languages?: string[];
+  recognitionLevel?: RecognitionLevel;
+}
+
+export type PermissionKind =
+  | 'screenRecording'
+  | 'accessibility'
+  | 'microphone'
+  | 'speechRecognition';
+
+export type PermissionState = 'granted' | 'denied' | 'notDetermined' | 'restricted';
+
+export interface PermissionStatus {
+  screenRecording: PermissionState;
+  accessibility: PermissionState;
+  microphone: PermissionState;
+  speechRecognition: PermissionState;
 }
 
 export interface HawkeyeStatus {
   initialized: boolean;
   aiReady: boolean;
   aiProvider?: string;
+  /// Active client's model, or the configured one when AI isn't ready
+  aiModel?: string;
   observeRunning: boolean;
+  permissions: PermissionStatus;
+  ocrAvailable: boolean;
 }
 
 export interface ChatMessage {
@@ -63,32 +209,158 @@ export interface ChatResponse {
 
 export interface ObserveStatus {
   running: boolean;
+  /** Seconds since the loop last iterated; absent when not running */
+  secsSinceLastTick?: number;
+  /** `running && !healthy` means the loop stalled or its task died */
+  healthy: boolean;
   lastObservation?: ObservationResult;
 }
 
 export interface ObservationResult {
+  /// Full-resolution PNG — only present on `getObserveStatus`, not on `observe:update`
   screenshotBase64?: string;
+  /// 320px JPEG preview sent with every update
+  thumbnailBase64?: string;
   ocrText?: string;
+  ocrFailed: boolean;
+  /// Characters and words in the OCR text, for density metrics without `ocrText`
+  ocrCharCount: number;
+  ocrWordCount: number;
   activeWindow?: WindowInfo;
   changeRatio: number;
+  /// 64-bit perceptual hash as 16 hex digits; similar screens differ in few bits
+  phash: string;
   timestamp: number;
 }
 
+export type ChangeKind = 'app_switch' | 'new_window' | 'scroll' | 'minor_edit' | 'scene_change';
+
+/// Payload of `observe:change-detected`
+export interface ObserveChange {
+  changeRatio: number;
+  kind: ChangeKind;
+  /// Tiles of the 8x8 grid that changed (0–64)
+  tilesChanged: number;
+}
+
 // Tauri command wrappers
 export async function getStatus(): Promise<HawkeyeStatus> {
   return invoke('get_status');
 }
 
-export async function captureScreen(): Promise<ScreenshotResult> {
-  return invoke('capture_screen');
+export type CaptureFormat = 'png' | 'jpeg' | 'webp';
+
+/// Defaults to PNG. Use 'jpeg' for display-only captures; keep PNG for OCR.
+export async function captureScreen(
+  format?: CaptureFormat,
+  quality?: number,
+): Promise<ScreenshotResult> {
+  return invoke('capture_screen', { format, quality });
+}
+
+export async function runOcr(imageBase64: string, options: OcrOptions = {}): Promise<OcrResult> {
+  return invoke('run_ocr', { imageBase64, ...options });
 }
 
-export async function runOcr(imageBase64: string): Promise<OcrResult> {
-  return invoke('run_ocr', { imageBase64 });
+export async function runOcrRegions(
+  imageBase64: string,
+  options: OcrOptions = {},
+): Promise<OcrRegionsResult> {
+  return invoke('run_ocr_regions', { imageBase64, ...options });
+}
+
+/// Re-OCR the last observed frame; rejects with `not_found` when no full
+/// screenshot is retained.
+export async function reocrLastObservation(
+  options: OcrOptions & { minConfidence?: number } = {},
+): Promise<OcrFrameResult> {
+  return invoke('reocr_last_observation', { ...options });
+}
+
+/// Results are cached for ~500ms; pass force to bypass.
+export async function getActiveWindow(force?: boolean): Promise<WindowInfo> {
+  return invoke('get_active_window', { force });
+}
+
+export interface NamedWindowCapture {
+  appName: string;
+  bundleId?: string;
+  title?: string;
+  bounds: { x: number; y: number; width: number; height: number };
+  /** PNG, base64-encoded */
+  imageBase64: string;
+  width: number;
+  height: number;
 }
 
-export async function getActiveWindow(): Promise<WindowInfo> {
-  return invoke('get_active_window');
+/**
+ * Capture one app's window (app name or bundle id, optional title
+ * substring) even when it's behind other windows. macOS only.
+ */
+export async function captureNamedWindow(app: string, title?: string): Promise<NamedWindowCapture> {
+  return invoke('capture_named_window', { app, title });
+}
+
+/// Privacy master switch. While off, every capture (loop, commands, gestures) is refused.
+/// Saved to the config, so it survives restarts.
+export async function setCaptureEnabled(enabled: boolean): Promise<boolean> {
+  return invoke('set_capture_enabled', { enabled });
+}
+
+export async function isCaptureEnabled(): Promise<boolean> {
+  return invoke('is_capture_enabled');
+}
+
+/** Screen, OCR and active window from a single capture */
+export interface CaptureContext {
+  width: number;
+  height: number;
+  imageDataUrl?: string;
+  ocr?: OcrFrameResult;
+  ocrError?: string;
+  window?: WindowInfo;
+  windowError?: string;
+  durationMs: number;
+}
+
+/** One-shot context snapshot; the image is only returned with `includeImage` */
+export async function captureContext(includeImage?: boolean): Promise<CaptureContext> {
+  return invoke('capture_context', { includeImage });
+}
+
+export async function checkPermissions(): Promise<PermissionStatus> {
+  return invoke('check_permissions');
+}
+
+/// Triggers the system prompt; re-check afterwards for the final state.
+export async function requestPermission(kind: PermissionKind): Promise<PermissionState> {
+  return invoke('request_permission', { kind });
+}
+
+export interface MonitorInfo {
+  index: number;
+  id: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  scaleFactor: number;
+  isPrimary: boolean;
+}
+
+export async function listMonitors(): Promise<MonitorInfo[]> {
+  return invoke('list_monitors');
+}
+
+export interface DisplayInfo extends MonitorInfo {
+  /** Native resolution in physical pixels */
+  pixelWidth: number;
+  pixelHeight: number;
+}
+
+/** Display metadata without taking a screenshot */
+export async function getDisplayInfo(): Promise<DisplayInfo[]> {
+  return invoke('get_display_info');
 }
 
 export async function getClipboard(): Promise<string> {
@@ -103,6 +375,64 @@ export async function saveConfig(config: AppConfig): Promise<void> {
   return invoke('save_config', { config });
 }
 
+/// Switch to a saved provider profile and re-init the AI client; resolves to whether it validated.
+export async function setActiveProfile(name: string): Promise<boolean> {
+  return invoke('set_active_profile', { name });
+}
+
+export interface SnapshotReport {
+  path: string;
+  version: number;
+  appVersion: string;
+  createdAt: number;
+  activityEntries: number;
+  lifeTreeNodes: number;
+  models: number;
+  conversations: number;
+}
+
+/** Zip config (without API keys), life tree, activity log, adaptive state, model manifest and conversations */
+export async function exportState(path: string): Promise<SnapshotReport> {
+  return invoke('export_state', { path });
+}
+
+/** Restore an `exportState` archive, keeping the current API keys; stops the observe loop first. Call `initAi` afterwards. */
+export async function importState(path: string): Promise<SnapshotReport> {
+  return invoke('import_state', { path });
+}
+
+// Granular data resets, for a settings "reset data" screen
+
+/** Reset the adaptive refresh score to its baseline and drop recent events */
+export async function resetAdaptive(): Promise<void> {
+  return invoke('reset_adaptive');
+}
+
+/** Drop all activity log entries and summaries */
+export async function clearActivityLog(): Promise<void> {
+  return invoke('clear_activity_log');
+}
+
+/** Forget recently recognized intents */
+export async function clearIntents(): Promise<void> {
+  return invoke('clear_intents');
+}
+
+/** Start Hawkeye (tray-only) at login; persisted in `launchAtLogin` */
+export async function setLaunchAtLogin(enabled: boolean): Promise<void> {
+  return invoke('set_launch_at_login', { enabled });
+}
+
+/** Whether the OS will start Hawkeye at login (the OS setting wins over config) */
+export async function getLaunchAtLogin(): Promise<boolean> {
+  return invoke('get_launch_at_login');
+}
+
+/** Set (or clear, with an empty/absent prompt) the chat system prompt */
+export async function setSystemPrompt(systemPrompt?: string): Promise<void> {
+  return invoke('set_system_prompt', { systemPrompt });
+}
+
 export async function openUrl(url: string): Promise<void> {
   return invoke('open_url', { url });
 }
@@ -112,8 +442,82 @@ export async function initAi(): Promise<boolean> {
   return invoke('init_ai');
 }
 
-export async function chat(messages: ChatMessage[]): Promise<ChatResponse> {
-  return invoke('chat', { messages });
+/**
+ * Switch the current provider's model and save it; skips re-validation when only the model changes.
+ * `false` means the model was saved but no client could be started on it.
+ */
+export async function setActiveModel(model: string): Promise<boolean> {
+  return invoke('set_active_model', { model });
+}
+
+/// With `conversationId`, the exchange is persisted to that conversation;
+/// the response echoes the id only when it was stored.
+export async function chat(
+  messages: ChatMessage[],
+  conversationId?: string,
+): Promise<ChatResponse & { conversationId?: string }> {
+  return invoke('chat', { messages, conversationId });
+}
+
+export interface Conversation {
+  id: string;
+  createdAt: number;
+  updatedAt: number;
+  messages: (ChatMessage & { timestamp: number })[];
+  usage: {
+    requests: number;
+    promptTokens: number;
+    completionTokens: number;
+  };
+}
+
+export async function getConversation(conversationId: string): Promise<Conversation | null> {
+  return invoke('get_conversation', { conversationId });
+}
+
+export interface ProviderCapabilities {
+  vision: boolean;
+  embeddings: boolean;
+  streaming: boolean;
+  jsonMode: boolean;
+}
+
+/// What the active provider and model support, to hide features that would fail.
+export async function getProviderCapabilities(): Promise<ProviderCapabilities> {
+  return invoke('get_provider_capabilities');
+}
+
+export interface AiDiagnostics {
+  provider: string;
+  model: string;
+  healthy: boolean;
+  requestCount: number;
+  errorCount: number;
+  lastLatencyMs?: number;
+  lastError?: string;
+  lastErrorCode?: HawkeyeErrorCode;
+  lastErrorAt?: number;
+  lastSuccessAt?: number;
+}
+
+/// Latency, last error and request counts of the active provider.
+export async function getAiDiagnostics(): Promise<AiDiagnostics> {
+  return invoke('get_ai_diagnostics');
+}
+
+export interface TokenEstimate {
+  tokens: number;
+  contextLimit?: number;
+  method: 'tiktoken' | 'heuristic';
+}
+
+/// Rough prompt size against the active model, before sending.
+export async function estimateTokens(messages: ChatMessage[]): Promise<TokenEstimate> {
+  return invoke('estimate_tokens', { messages });
+}
+
+export async function embedTexts(texts: string[]): Promise<number[][]> {
+  return invoke('embed_texts', { texts });
 }
 
 // --- Agent (cua-driver desktop control) ---
@@ -162,6 +566,33 @@ export async function chatWithAgent(
   return invoke('chat_with_agent', { history, userInput });
 }
 
+export type PlanStep =
+  | { action: 'screenshot' }
+  | { action: 'ocr' }
+  | { action: 'chat'; prompt: string; includeScreenshot?: boolean; includeOcr?: boolean };
+
+export interface PlanStepOutcome {
+  index: number;
+  action: PlanStep['action'];
+  success: boolean;
+  output?: string;
+  error?: string;
+  durationMs: number;
+}
+
+export interface PlanResult {
+  success: boolean;
+  steps: PlanStepOutcome[];
+  reply?: string;
+  durationMs: number;
+}
+
+/// Run screenshot / OCR / chat steps in order. Listen for `plan:progress`;
+/// the run also appears on the debug timeline.
+export async function executePlan(steps: PlanStep[]): Promise<PlanResult> {
+  return invoke('execute_plan', { steps });
+}
+
 /// Direct tool invocation (debugging only — bypasses the LLM).
 export async function invokeCuaTool(
   name: string,
@@ -183,6 +614,127 @@ export async function getObserveStatus(): Promise<ObserveStatus> {
   return invoke('get_observe_status');
 }
 
+/// Full-resolution base64 PNG of the last observation.
+export async function getLastFullScreenshot(): Promise<string | null> {
+  return invoke('get_last_full_screenshot');
+}
+
+export interface RecentFrame {
+  timestamp: number;
+  thumbnailBase64?: string;
+  ocrText?: string;
+  ocrFailed: boolean;
+  activeWindow?: WindowInfo;
+  changeRatio: number;
+  phash?: string;
+  lifeStage: LifeStage;
+  taskLabel: string;
+}
+
+/// The last `count` recorded frames (default 10), newest first, for debugging.
+export async function getRecentObservations(count?: number): Promise<RecentFrame[]> {
+  return invoke('get_recent_observations', { count });
+}
+
+export interface ObservationDiff {
+  fromTimestamp: number;
+  toTimestamp: number;
+  fromApp?: string;
+  toApp?: string;
+  appChanged: boolean;
+  /** Same app, different window title */
+  windowChanged: boolean;
+  /** OCR lines only in the later frame (first 50; see addedCount) */
+  addedLines: string[];
+  addedCount: number;
+  removedLines: string[];
+  removedCount: number;
+  changeRatio?: number;
+  /** e.g. "Switched from Slack to Code; 12 lines added, 3 removed" */
+  summary: string;
+}
+
+/// What changed between two recorded frames (by timestamp). With no
+/// timestamps, compares the newest frame with the one before it.
+export async function diffObservations(
+  aTimestamp?: number,
+  bTimestamp?: number
+): Promise<ObservationDiff> {
+  return invoke('diff_observations', { aTimestamp, bTimestamp });
+}
+
+export interface ObserveOnceResult {
+  observation: ObservationResult;
+  intents: UserIntent[];
+  lifeStage: LifeStage;
+  taskLabel: string;
+  committed: boolean;
+}
+
+/// Run the pipeline once; nothing is recorded unless commit is true.
+export async function observeOnce(commit = false): Promise<ObserveOnceResult> {
+  return invoke('observe_once', { commit });
+}
+
+/** Latency of one pipeline stage, in milliseconds */
+export interface StageTiming {
+  minMs: number;
+  avgMs: number;
+  p95Ms: number;
+  samples: number;
+}
+
+export interface BenchmarkReport {
+  iterations: number;
+  width: number;
+  height: number;
+  format: string;
+  capture?: StageTiming;
+  encode?: StageTiming;
+  hash?: StageTiming;
+  ocr?: StageTiming;
+  ocrErrors: number;
+  window?: StageTiming;
+  avgEncodedBytes: number;
+  avgThumbnailBytes: number;
+}
+
+/** Time each observe stage over `iterations` runs (default 10, max 50) */
+export async function benchmarkPipeline(iterations?: number): Promise<BenchmarkReport> {
+  return invoke('benchmark_pipeline', { iterations });
+}
+
+export interface ObserveParams {
+  threshold: number;
+  recordStats: boolean;
+}
+
+export interface ChangeStatsSummary {
+  sampleCount: number;
+  min: number;
+  max: number;
+  mean: number;
+  threshold: number;
+  triggered: number;
+  histogram: number[];
+}
+
+export async function setObserveParams(params: {
+  threshold?: number;
+  recordStats?: boolean;
+}): Promise<ObserveParams> {
+  return invoke('set_observe_params', params);
+}
+
+export async function getObserveParams(): Promise<ObserveParams> {
+  return invoke('get_observe_params');
+}
+
+/// Change-ratio distribution; pass a threshold to preview how many frames it would trigger.
+export async function getChangeStats(threshold?: number): Promise<ChangeStatsSummary> {
+  return invoke('get_change_stats', { threshold });
+}
+
 // Adaptive refresh types
 export type ActivityEventType =
   | 'screen_change'
@@ -200,6 +752,8 @@ export interface AdaptiveRefreshStatus {
   activityLevel: ActivityLevel;
   currentIntervalMs: number;
   recentEventCount: number;
+  /** Level pinned by `setActivityLevel` */
+  forced: boolean;
 }
 
 // Adaptive refresh commands
@@ -211,6 +765,15 @@ export async function getRefreshStatus(): Promise<AdaptiveRefreshStatus> {
   return invoke('get_refresh_status');
 }
 
+/** Pin the observe interval to a level's band until `clearForcedLevel` */
+export async function setActivityLevel(level: ActivityLevel): Promise<AdaptiveRefreshStatus> {
+  return invoke('set_activity_level', { level });
+}
+
+export async function clearForcedLevel(): Promise<AdaptiveRefreshStatus> {
+  return invoke('clear_forced_level');
+}
+
 // Activity summarizer types
 export interface ActivitySummary {
   summary: string;
@@ -218,9 +781,25 @@ export interface ActivitySummary {
   periodEnd: number;
   entryCount: number;
   topApps: string[];
+  /** Minutes in focus for each of `topApps`, same order */
+  appUsage: { appName: string; minutes: number }[];
   generatedAt: number;
 }
 
+export interface ActivityEntry {
+  timestamp: number;
+  endTimestamp: number;
+  appName?: string;
+  windowTitle?: string;
+  ocrSnippet?: string;
+  changeRatio: number;
+}
+
+export interface ActivityEntriesPage {
+  entries: ActivityEntry[];
+  total: number;
+}
+
 export interface ActivityStats {
   totalEntries: number;
   pendingEntries: number;
@@ -229,14 +808,43 @@ export interface ActivityStats {
 }
 
 // Activity summarizer commands
-export async function generateSummary(): Promise<ActivitySummary> {
-  return invoke('generate_summary');
+/**
+ * One summary per batch; large backlogs are split into several. With
+ * `deferIfUnavailable`, a request made before `initAi` runs once a provider
+ * is ready and arrives as `activity:summary-generated`.
+ */
+export async function generateSummary(deferIfUnavailable?: boolean): Promise<ActivitySummary[]> {
+  return invoke('generate_summary', { deferIfUnavailable });
 }
 
 export async function getRecentSummaries(count?: number): Promise<ActivitySummary[]> {
   return invoke('get_recent_summaries', { count });
 }
 
+export async function getActivityEntries(
+  offset: number,
+  limit: number,
+  sinceMs?: number,
+): Promise<ActivityEntriesPage> {
+  return invoke('get_activity_entries', { offset, limit, sinceMs });
+}
+
+export type ActivityExportFormat = 'json' | 'csv';
+
+export interface ActivityExportReport {
+  entryCount: number;
+  summaryCount: number;
+  files: string[];
+}
+
+export async function exportActivity(
+  format: ActivityExportFormat,
+  path: string,
+  options: { includeSummaries?: boolean; startMs?: number; endMs?: number } = {},
+): Promise<ActivityExportReport> {
+  return invoke('export_activity', { format, path, ...options });
+}
+
 export async function getActivityStats(): Promise<ActivityStats> {
   return invoke('get_activity_stats');
 }
@@ -280,6 +888,36 @@ export async function getRecentIntents(): Promise<UserIntent[]> {
   return invoke('get_recent_intents');
 }
 
+// Focus session types
+
+/** Payload of `focus:distraction` */
+export interface Distraction {
+  timestamp: number;
+  stage: LifeStage;
+  appName?: string;
+  taskLabel: string;
+}
+
+export interface FocusSummary {
+  startedAt: number;
+  endedAt: number;
+  plannedMs: number;
+  allowedStages: LifeStage[];
+  focusMs: number;
+  distractionMs: number;
+  focusRatio: number;
+  distractions: Distraction[];
+}
+
+/** Flag observed activity outside `allowedStages` until the session ends */
+export async function startFocusSession(durationMinutes: number, allowedStages: LifeStage[]): Promise<void> {
+  return invoke('start_focus_session', { durationMinutes, allowedStages });
+}
+
+export async function endFocusSession(): Promise<FocusSummary> {
+  return invoke('end_focus_session');
+}
+
 // Voice pipeline types
 export interface SpeechResult {
   text: string;
@@ -308,6 +946,17 @@ export async function speechTranscribeFile(audioPath: string): Promise<SpeechRes
   return invoke('speech_transcribe_file', { audioPath });
 }
 
+export interface VoiceNoteSummary {
+  transcript: string;
+  summary: string;
+  engine: 'native' | 'whisper';
+  chunks: number;
+}
+
+export async function transcribeAndSummarize(audioPath: string): Promise<VoiceNoteSummary> {
+  return invoke('transcribe_and_summarize', { audioPath });
+}
+
 // Model manager types
 export type ModelType = 'text_llm' | 'vision_llm' | 'whisper' | 'tts' | 'vad' | 'embedding';
 
@@ -321,6 +970,7 @@ export interface ModelInfo {
   sizeBytes: number;
   downloadUrl: string;
   filename: string;
+  gated: boolean;
 }
 
 export interface LocalModel {
@@ -347,6 +997,18 @@ export async function getModelsDir(): Promise<string> {
   return invoke('get_models_dir');
 }
 
+export interface RelocateReport {
+  modelsDir: string;
+  moved: number;
+  alreadyPresent: number;
+  models: LocalModel[];
+}
+
+/// Move model storage (e.g. to an external disk); empty path restores the default.
+export async function setModelsDir(path: string | null, moveModels = true): Promise<RelocateReport> {
+  return invoke('set_models_dir', { path, moveModels });
+}
+
 export async function listModels(): Promise<LocalModel[]> {
   return invoke('list_models');
 }
@@ -359,10 +1021,19 @@ export async function getModelsByType(modelType: ModelType): Promise<ModelInfo[]
   return invoke('get_models_by_type', { modelType });
 }
 
+export async function getModelsByTypes(modelTypes: ModelType[]): Promise<ModelInfo[]> {
+  return invoke('get_models_by_types', { modelTypes });
+}
+
+export async function searchModels(query: string): Promise<ModelInfo[]> {
+  return invoke('search_models', { query });
+}
+
 export async function modelExists(modelId: string): Promise<boolean> {
   return invoke('model_exists', { modelId });
 }
 
+/** Download a registry model; fails while another download is running */
 export async function downloadModel(modelId: string): Promise<LocalModel> {
   return invoke('download_model', { modelId });
 }
@@ -371,6 +1042,16 @@ export async function cancelModelDownload(): Promise<void> {
   return invoke('cancel_model_download');
 }
 
+export interface PartialCleanup {
+  removedFiles: number;
+  reclaimedBytes: number;
+}
+
+/** Remove abandoned `.part` downloads; `olderThanSecs` defaults to an hour */
+export async function cleanupPartialDownloads(olderThanSecs?: number): Promise<PartialCleanup> {
+  return invoke('cleanup_partial_downloads', { olderThanSecs });
+}
+
 export async function deleteModel(modelId: string): Promise<void> {
   return invoke('delete_model', { modelId });
 }
@@ -400,9 +1081,81 @@ export interface LifeTreeNode {
   experimentPhase?: ExperimentPhase;
   observationCount: number;
   relatedApps: string[];
+  /** Stage nodes only: per-day observations for the last 30 days */
+  dailyCounts?: DailyCount[];
+  /** Experiment nodes: when to conclude it (ms) */
+  dueAt?: number;
+  /** Experiment nodes: set once concluded */
+  outcome?: ExperimentOutcome;
+  /** Task nodes: OCR text the task was first classified with */
+  ocrSnippet?: string;
   entityIds?: string[];
 }
 
+export interface MediaSampling {
+  enabled: boolean;
+  /** Change ratio a frame needs to count towards playback (default 0.25) */
+  minChangeRatio: number;
+  /** Consecutive such frames in one window before slowing down (default 8) */
+  minFrames: number;
+  /** Loop interval while playback is detected (default 15000, at most 30000) */
+  sampleIntervalMs: number;
+}
+
+/// Payload of `observe:media-detected`
+export interface MediaDetected {
+  app?: string;
+  title?: string;
+  intervalMs: number;
+}
+
+export interface PhaseThresholds {
+  /** Task-level completions that unlock goal level */
+  goalLevel: number;
+  /** Task- plus goal-level completions that unlock automation level */
+  automationLevel: number;
+}
+
+export interface PhaseProgress {
+  unlockedPhase: ExperimentPhase;
+  taskLevelCompletions: number;
+  goalLevelCompletions: number;
+  nextPhase?: ExperimentPhase;
+  completionsToNext: number;
+}
+
+export interface ExperimentOutcome {
+  succeeded: boolean;
+  notes?: string;
+  metrics: Record<string, number>;
+  concludedAt: number;
+}
+
+export interface DailyCount {
+  /** Local date, YYYY-MM-DD */
+  date: string;
+  count: number;
+}
+
+export interface StageCorrelation {
+  stageA: LifeStage;
+  stageB: LifeStage;
+  /** Pearson coefficient in [-1, 1] */
+  coefficient: number;
+  sampleDays: number;
+  description: string;
+}
+
+export interface LifeTreeInsights {
+  daysAnalyzed: number;
+  /** Days in the window with any recorded activity */
+  activeDays: number;
+  positive: StageCorrelation[];
+  negative: StageCorrelation[];
+  /** Caveat about the sample size, if any */
+  note?: string;
+}
+
 export interface TreeStats {
   totalNodes: number;
   activeGoals: number;
@@ -428,6 +1181,12 @@ export interface ExperimentProposal {
   durationDays: number;
 }
 
+export interface StageExperimentProposal extends ExperimentProposal {
+  /** Stage node to pass to `startExperiment` */
+  nodeId: string;
+  phase: ExperimentPhase;
+}
+
 // Knowledge graph types
 export type KnowledgeNodeType = 'person' | 'project' | 'technology' | 'concept' | 'place';
 
@@ -467,27 +1226,63 @@ export async function rebuildLifeTree(): Promise<LifeTreeSnapshot> {
   return invoke('rebuild_life_tree');
 }
 
+/// Re-file existing tasks under the current rules; counts and experiments move with them.
+export async function reclassifyLifeTree(): Promise<LifeTreeSnapshot> {
+  return invoke('reclassify_life_tree');
+}
+
+export async function getStageTimeline(stage: LifeStage, days?: number): Promise<DailyCount[]> {
+  return invoke('get_stage_timeline', { stage, days });
+}
+
+export async function analyzeLifeTree(days?: number): Promise<LifeTreeInsights> {
+  return invoke('analyze_life_tree', { days });
+}
+
 export async function proposeExperiment(nodeId: string): Promise<ExperimentProposal> {
   return invoke('propose_experiment', { nodeId });
 }
 
+export async function proposeStageExperiment(stage: LifeStage): Promise<StageExperimentProposal> {
+  return invoke('propose_stage_experiment', { stage });
+}
+
 export async function startExperiment(
   nodeId: string,
   title: string,
   description: string,
-  phase: ExperimentPhase
+  phase: ExperimentPhase,
+  durationDays?: number
 ): Promise<string> {
-  return invoke('start_experiment', { nodeId, title, description, phase });
+  return invoke('start_experiment', { nodeId, title, description, phase, durationDays });
 }
 
-export async function concludeExperiment(experimentId: string, succeeded: boolean): Promise<void> {
-  return invoke('conclude_experiment', { experimentId, succeeded });
+/** Active experiments overdue or due within `withinHours` (default 24) */
+export async function getDueExperiments(withinHours?: number): Promise<LifeTreeNode[]> {
+  return invoke('get_due_experiments', { withinHours });
+}
+
+export async function concludeExperiment(
+  experimentId: string,
+  succeeded: boolean,
+  notes?: string,
+  metrics?: Record<string, number>
+): Promise<void> {
+  return invoke('conclude_experiment', { experimentId, succeeded, notes, metrics });
+}
+
+export async function getExperimentHistory(): Promise<LifeTreeNode[]> {
+  return invoke('get_experiment_history');
 }
 
 export async function getUnlockedPhase(): Promise<ExperimentPhase> {
   return invoke('get_unlocked_phase');
 }
 
+export async function getPhaseProgress(): Promise<PhaseProgress> {
+  return invoke('get_phase_progress');
+}
+
 export async function getExperiments(): Promise<LifeTreeNode[]> {
   return invoke('get_experiments');
 }
@@ -607,6 +1402,10 @@ export async function getDebugEventsSince(sinceMs: number): Promise<DebugEvent[]
   return invoke('get_debug_events_since', { sinceMs });
 }
 
+export async function getDebugChildren(parentId: string): Promise<DebugEvent[]> {
+  return invoke('get_debug_children', { parentId });
+}
+
 export async function searchDebugEvents(query: string): Promise<DebugEvent[]> {
   return invoke('search_debug_events', { query });
 }